@@ -0,0 +1,448 @@
+use anyhow::Result;
+use num_bigint::BigUint;
+use serde_json::{Map, Value};
+use starknet::core::types::FieldElement;
+
+use super::{types::AbiType, ContractAbi};
+
+/// Sequential reader over raw field elements being decoded.
+pub struct FeltReader<'a> {
+    data: &'a [FieldElement],
+    position: usize,
+}
+
+impl ContractAbi {
+    /// Decodes the raw result of calling the function identified by `selector` into a JSON value.
+    /// Functions with a single output are decoded into that output value directly, while other
+    /// functions are decoded into an array.
+    ///
+    /// When `short_strings` is on, `felt252` values that are valid Cairo short strings are shown
+    /// as text instead of hex.
+    pub fn decode_function_outputs(
+        &self,
+        selector: FieldElement,
+        data: &[FieldElement],
+        short_strings: bool,
+    ) -> Result<Value> {
+        let function = self.find_function(selector)?;
+
+        let mut reader = FeltReader::new(data);
+        let mut values = vec![];
+        for output in function.outputs.iter() {
+            let abi_type = AbiType::parse(&output.r#type)?;
+            values.push(self.decode_value(&abi_type, &mut reader, short_strings)?);
+        }
+        reader.finish()?;
+
+        Ok(if values.len() == 1 {
+            values.remove(0)
+        } else {
+            Value::Array(values)
+        })
+    }
+
+    pub fn decode_value(
+        &self,
+        abi_type: &AbiType,
+        reader: &mut FeltReader,
+        short_strings: bool,
+    ) -> Result<Value> {
+        Ok(match abi_type {
+            AbiType::Felt => {
+                let value = reader.read()?;
+                match short_strings.then(|| felt_to_short_string(value)).flatten() {
+                    Some(text) => Value::String(text),
+                    None => Value::String(format!("{:#064x}", value)),
+                }
+            }
+            AbiType::Bytes31 => {
+                let value = reader.read()?;
+                let bytes = value.to_bytes_be();
+                let start = bytes
+                    .iter()
+                    .position(|byte| *byte != 0)
+                    .unwrap_or(bytes.len());
+                Value::String(String::from_utf8_lossy(&bytes[start..]).into_owned())
+            }
+            AbiType::Bool => {
+                let value = reader.read()?;
+                if value == FieldElement::ZERO {
+                    Value::Bool(false)
+                } else if value == FieldElement::ONE {
+                    Value::Bool(true)
+                } else {
+                    anyhow::bail!("invalid bool value: {:#x}", value);
+                }
+            }
+            AbiType::Uint(bits) => {
+                let value = felt_to_biguint(reader.read()?);
+                if value.bits() > *bits as u64 {
+                    anyhow::bail!("value {} out of range for u{}", value, bits);
+                }
+
+                // Large integers are shown as decimal strings to avoid precision loss
+                match u64::try_from(&value) {
+                    Ok(value) if *bits <= 64 => Value::from(value),
+                    _ => Value::String(value.to_string()),
+                }
+            }
+            AbiType::Int(bits) => {
+                let value = reader.read()?;
+
+                // Negative values are stored as `P - magnitude`
+                let negated = FieldElement::ZERO - value;
+                let (negative, magnitude) = if felt_to_biguint(negated) < felt_to_biguint(value) {
+                    (true, felt_to_biguint(negated))
+                } else {
+                    (false, felt_to_biguint(value))
+                };
+                // The range is asymmetric, from `-2^(bits-1)` to `2^(bits-1) - 1`
+                let limit = BigUint::from(1u8) << (*bits as usize - 1);
+                if (negative && magnitude > limit) || (!negative && magnitude >= limit) {
+                    anyhow::bail!("value {:#x} out of range for i{}", value, bits);
+                }
+
+                match (i64::try_from(&magnitude), *bits <= 64) {
+                    (Ok(magnitude), true) => {
+                        Value::from(if negative { -magnitude } else { magnitude })
+                    }
+                    _ => Value::String(format!("{}{}", if negative { "-" } else { "" }, magnitude)),
+                }
+            }
+            AbiType::U256 => {
+                let low = felt_to_biguint(reader.read()?);
+                let high = felt_to_biguint(reader.read()?);
+                if low.bits() > 128 || high.bits() > 128 {
+                    anyhow::bail!("u256 value out of range");
+                }
+
+                Value::String(((high << 128) + low).to_string())
+            }
+            AbiType::ByteArray => Value::String(reader.read_byte_array()?),
+            AbiType::Array(element_type) => {
+                let len = reader.read_len()?;
+
+                let mut values = vec![];
+                for _ in 0..len {
+                    values.push(self.decode_value(element_type, reader, short_strings)?);
+                }
+
+                Value::Array(values)
+            }
+            AbiType::Tuple(member_types) => Value::Array(
+                member_types
+                    .iter()
+                    .map(|member_type| self.decode_value(member_type, reader, short_strings))
+                    .collect::<Result<Vec<_>>>()?,
+            ),
+            AbiType::Named(name) => {
+                if let Some(abi_struct) = self.find_struct(name) {
+                    let mut members = Map::new();
+                    for member in abi_struct.members.iter() {
+                        let member_type = AbiType::parse(&member.r#type)?;
+                        members.insert(
+                            member.name.clone(),
+                            self.decode_value(&member_type, reader, short_strings)?,
+                        );
+                    }
+
+                    Value::Object(members)
+                } else if let Some(abi_enum) = self.find_enum(name) {
+                    let variant_index = reader.read_len()?;
+                    let variant = abi_enum.variants.get(variant_index).ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "invalid variant index {} for enum `{}`",
+                            variant_index,
+                            name
+                        )
+                    })?;
+
+                    let variant_type = AbiType::parse(&variant.r#type)?;
+                    match variant_type {
+                        AbiType::Tuple(members) if members.is_empty() => {
+                            Value::String(variant.name.clone())
+                        }
+                        variant_type => {
+                            let mut object = Map::new();
+                            object.insert(
+                                variant.name.clone(),
+                                self.decode_value(&variant_type, reader, short_strings)?,
+                            );
+                            Value::Object(object)
+                        }
+                    }
+                } else {
+                    anyhow::bail!("type `{}` not found in ABI", name);
+                }
+            }
+        })
+    }
+}
+
+impl<'a> FeltReader<'a> {
+    pub fn new(data: &'a [FieldElement]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    pub fn read(&mut self) -> Result<FieldElement> {
+        let value = self
+            .data
+            .get(self.position)
+            .ok_or_else(|| anyhow::anyhow!("unexpected end of data"))?;
+        self.position += 1;
+
+        Ok(*value)
+    }
+
+    /// Makes sure all elements have been consumed.
+    pub fn finish(self) -> Result<()> {
+        if self.position == self.data.len() {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!(
+                "{} unexpected trailing element(s)",
+                self.data.len() - self.position
+            ))
+        }
+    }
+
+    /// Reads a Cairo `ByteArray` as text, replacing invalid UTF-8 sequences.
+    pub fn read_byte_array(&mut self) -> Result<String> {
+        let word_count = self.read_len()?;
+
+        let mut bytes = vec![];
+        for _ in 0..word_count {
+            // Full words always have exactly 31 bytes
+            bytes.extend_from_slice(&self.read()?.to_bytes_be()[1..]);
+        }
+
+        let pending_word = self.read()?.to_bytes_be();
+        let pending_len = self.read_len()?;
+        if pending_len >= 31 {
+            anyhow::bail!("invalid ByteArray pending word length: {}", pending_len);
+        }
+        bytes.extend_from_slice(&pending_word[(32 - pending_len)..]);
+
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    fn read_len(&mut self) -> Result<usize> {
+        let value = self.read()?;
+        Ok(
+            u64::try_from(value).map_err(|_| anyhow::anyhow!("invalid length: {:#x}", value))?
+                as usize,
+        )
+    }
+}
+
+/// Interprets a field element as a Cairo short string only if all its bytes are printable ASCII.
+fn felt_to_short_string(value: FieldElement) -> Option<String> {
+    let bytes = value.to_bytes_be();
+    let start = bytes.iter().position(|byte| *byte != 0)?;
+
+    if bytes[start..]
+        .iter()
+        .all(|byte| byte.is_ascii_graphic() || *byte == b' ')
+    {
+        // Safe to unwrap as all bytes are ASCII
+        Some(String::from_utf8(bytes[start..].to_vec()).unwrap())
+    } else {
+        None
+    }
+}
+
+fn felt_to_biguint(value: FieldElement) -> BigUint {
+    BigUint::from_bytes_be(&value.to_bytes_be())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use starknet::{core::utils::get_selector_from_name, macros::felt};
+
+    use crate::abi::encode::encode_byte_array;
+
+    use super::*;
+
+    fn test_abi() -> ContractAbi {
+        ContractAbi::new(
+            serde_json::from_value(json!([
+                {
+                    "type": "struct",
+                    "name": "test::Point",
+                    "members": [
+                        { "name": "x", "type": "core::integer::u32" },
+                        { "name": "y", "type": "core::integer::i8" }
+                    ]
+                },
+                {
+                    "type": "enum",
+                    "name": "test::Shape",
+                    "variants": [
+                        { "name": "Empty", "type": "()" },
+                        { "name": "Circle", "type": "core::integer::u32" }
+                    ]
+                },
+                {
+                    "type": "function",
+                    "name": "info",
+                    "inputs": [],
+                    "outputs": [
+                        { "type": "core::integer::u256" },
+                        { "type": "core::felt252" }
+                    ],
+                    "state_mutability": "view"
+                }
+            ]))
+            .unwrap(),
+        )
+    }
+
+    fn decode(abi_type: &str, data: &[FieldElement]) -> Result<Value> {
+        let mut reader = FeltReader::new(data);
+        let value = test_abi().decode_value(&AbiType::parse(abi_type)?, &mut reader, false)?;
+        reader.finish()?;
+
+        Ok(value)
+    }
+
+    fn negative(magnitude: FieldElement) -> FieldElement {
+        FieldElement::ZERO - magnitude
+    }
+
+    #[test]
+    fn test_decode_u256() {
+        assert_eq!(
+            decode("core::integer::u256", &[felt!("1"), felt!("2")]).unwrap(),
+            json!("680564733841876926926749214863536422913")
+        );
+        assert_eq!(
+            decode(
+                "core::integer::u256",
+                &[
+                    felt!("0xffffffffffffffffffffffffffffffff"),
+                    felt!("0xffffffffffffffffffffffffffffffff")
+                ]
+            )
+            .unwrap(),
+            json!("115792089237316195423570985008687907853269984665640564039457584007913129639935")
+        );
+        assert!(decode(
+            "core::integer::u256",
+            &[felt!("0x100000000000000000000000000000000"), felt!("0")]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_byte_array_round_trip() {
+        for text in [
+            "",
+            "hello",
+            "abcdefghijklmnopqrstuvwxyz01234",
+            "abcdefghijklmnopqrstuvwxyz0123456789abcdefghijklmnopqrstuvwxyz",
+            "Multi-byte characters like é and ü can be split across words.",
+        ] {
+            let encoded = encode_byte_array(text.as_bytes());
+
+            let mut reader = FeltReader::new(&encoded);
+            assert_eq!(reader.read_byte_array().unwrap(), text);
+            reader.finish().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_decode_invalid_byte_array() {
+        let data = [felt!("0"), felt!("0x61"), felt!("31")];
+
+        let mut reader = FeltReader::new(&data);
+        assert!(reader.read_byte_array().is_err());
+    }
+
+    #[test]
+    fn test_decode_signed_integer_ranges() {
+        assert_eq!(
+            decode("core::integer::i8", &[felt!("127")]).unwrap(),
+            json!(127)
+        );
+        assert!(decode("core::integer::i8", &[felt!("128")]).is_err());
+        assert_eq!(
+            decode("core::integer::i8", &[negative(felt!("128"))]).unwrap(),
+            json!(-128)
+        );
+        assert!(decode("core::integer::i8", &[negative(felt!("129"))]).is_err());
+
+        assert_eq!(
+            decode(
+                "core::integer::i128",
+                &[felt!("170141183460469231731687303715884105727")]
+            )
+            .unwrap(),
+            json!("170141183460469231731687303715884105727")
+        );
+        assert!(decode(
+            "core::integer::i128",
+            &[felt!("170141183460469231731687303715884105728")]
+        )
+        .is_err());
+        assert_eq!(
+            decode(
+                "core::integer::i128",
+                &[negative(felt!("170141183460469231731687303715884105728"))]
+            )
+            .unwrap(),
+            json!("-170141183460469231731687303715884105728")
+        );
+        assert!(decode(
+            "core::integer::i128",
+            &[negative(felt!("170141183460469231731687303715884105729"))]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_decode_structs_and_enums() {
+        assert_eq!(
+            decode("test::Point", &[felt!("3"), negative(felt!("1"))]).unwrap(),
+            json!({ "x": 3, "y": -1 })
+        );
+        assert_eq!(
+            decode("test::Shape", &[felt!("0")]).unwrap(),
+            json!("Empty")
+        );
+        assert_eq!(
+            decode("test::Shape", &[felt!("1"), felt!("10")]).unwrap(),
+            json!({ "Circle": 10 })
+        );
+        assert!(decode("test::Shape", &[felt!("2")]).is_err());
+        assert!(decode("test::Point", &[felt!("3")]).is_err());
+    }
+
+    #[test]
+    fn test_decode_function_outputs() {
+        let abi = test_abi();
+        let selector = get_selector_from_name("info").unwrap();
+        let data = [felt!("1"), felt!("0"), felt!("0x68656c6c6f")];
+
+        assert_eq!(
+            abi.decode_function_outputs(selector, &data, true).unwrap(),
+            json!(["1", "hello"])
+        );
+        assert_eq!(
+            abi.decode_function_outputs(selector, &data, false).unwrap(),
+            json!([
+                "1",
+                "0x000000000000000000000000000000000000000000000000000068656c6c6f"
+            ])
+        );
+        assert!(abi
+            .decode_function_outputs(selector, &[felt!("1"), felt!("0")], false)
+            .is_err());
+
+        let mut trailing = data.to_vec();
+        trailing.push(felt!("1"));
+        assert!(abi
+            .decode_function_outputs(selector, &trailing, false)
+            .is_err());
+    }
+}
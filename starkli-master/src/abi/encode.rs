@@ -0,0 +1,655 @@
+use std::{future::Future, pin::Pin};
+
+use anyhow::Result;
+use num_bigint::BigUint;
+use starknet::core::{
+    types::{contract::AbiNamedMember, FieldElement},
+    utils::cairo_short_string_to_felt,
+};
+
+use crate::{chain_id::ChainIdSource, decode::FeltDecoder};
+
+use super::{
+    types::AbiType,
+    value::{ArgValue, StructMember},
+    ContractAbi,
+};
+
+/// Max number of bytes stored in a single `bytes31` word of a `ByteArray`.
+const BYTES_PER_WORD: usize = 31;
+
+type EncodeFuture<'a> = Pin<Box<dyn Future<Output = Result<()>> + 'a>>;
+
+impl ContractAbi {
+    /// Encodes calldata for the function identified by `selector`, with each element in `args`
+    /// being the typed value of one function input.
+    pub async fn encode_function_call<S>(
+        &self,
+        selector: FieldElement,
+        args: &[String],
+        felt_decoder: &FeltDecoder<S>,
+    ) -> Result<Vec<FieldElement>>
+    where
+        S: ChainIdSource,
+    {
+        let function = self.find_function(selector)?;
+
+        self.encode_inputs(&function.name, &function.inputs, args, felt_decoder)
+            .await
+    }
+
+    /// Encodes constructor calldata, with each element in `args` being the typed value of one
+    /// constructor input.
+    pub async fn encode_constructor_call<S>(
+        &self,
+        args: &[String],
+        felt_decoder: &FeltDecoder<S>,
+    ) -> Result<Vec<FieldElement>>
+    where
+        S: ChainIdSource,
+    {
+        self.encode_inputs("constructor", self.constructor_inputs(), args, felt_decoder)
+            .await
+    }
+
+    async fn encode_inputs<S>(
+        &self,
+        function_name: &str,
+        inputs: &[AbiNamedMember],
+        args: &[String],
+        felt_decoder: &FeltDecoder<S>,
+    ) -> Result<Vec<FieldElement>>
+    where
+        S: ChainIdSource,
+    {
+        if inputs.len() != args.len() {
+            anyhow::bail!(
+                "`{}` expects {} argument(s) but {} provided. Expected inputs: {}",
+                function_name,
+                inputs.len(),
+                args.len(),
+                if inputs.is_empty() {
+                    String::from("(none)")
+                } else {
+                    inputs
+                        .iter()
+                        .map(|input| format!("{}: {}", input.name, input.r#type))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                }
+            );
+        }
+
+        let mut calldata = vec![];
+        for (input, arg) in inputs.iter().zip(args.iter()) {
+            let abi_type = AbiType::parse(&input.r#type)?;
+            let value = ArgValue::parse(arg)
+                .map_err(|err| anyhow::anyhow!("invalid argument `{}`: {}", input.name, err))?;
+
+            self.encode_value(&abi_type, &value, felt_decoder, &mut calldata)
+                .await
+                .map_err(|err| {
+                    anyhow::anyhow!(
+                        "invalid value for argument `{}` of type `{}`: {}",
+                        input.name,
+                        input.r#type,
+                        err
+                    )
+                })?;
+        }
+
+        Ok(calldata)
+    }
+
+    fn encode_value<'a, S>(
+        &'a self,
+        abi_type: &'a AbiType,
+        value: &'a ArgValue,
+        felt_decoder: &'a FeltDecoder<S>,
+        buffer: &'a mut Vec<FieldElement>,
+    ) -> EncodeFuture<'a>
+    where
+        S: ChainIdSource,
+    {
+        // Boxing is needed as the encoding is recursive
+        Box::pin(async move {
+            match (abi_type, value) {
+                (AbiType::Felt | AbiType::Bytes31, ArgValue::Literal(raw)) => {
+                    buffer.push(felt_decoder.decode_single_with_addr_fallback(raw).await?);
+                }
+                (AbiType::Felt | AbiType::Bytes31, ArgValue::String(raw)) => {
+                    buffer.push(cairo_short_string_to_felt(raw)?);
+                }
+                (AbiType::Bool, ArgValue::Literal(raw)) => match raw.as_str() {
+                    "true" | "True" | "1" => buffer.push(FieldElement::ONE),
+                    "false" | "False" | "0" => buffer.push(FieldElement::ZERO),
+                    _ => anyhow::bail!("invalid bool value: {}", raw),
+                },
+                (AbiType::Uint(bits), ArgValue::Literal(raw)) => {
+                    let decoded = decode_single(felt_decoder, raw).await?;
+                    if felt_to_biguint(decoded).bits() > *bits as u64 {
+                        anyhow::bail!("value {} out of range for u{}", raw, bits);
+                    }
+                    buffer.push(decoded);
+                }
+                (AbiType::Int(bits), ArgValue::Literal(raw)) => {
+                    let (negative, magnitude) = match raw.strip_prefix('-') {
+                        Some(magnitude) => (true, decode_single(felt_decoder, magnitude).await?),
+                        None => (false, decode_single(felt_decoder, raw).await?),
+                    };
+
+                    // Negative values can go one further than positive values
+                    let magnitude_bits = felt_to_biguint(magnitude).bits();
+                    let in_range = if negative {
+                        magnitude_bits < *bits as u64
+                            || felt_to_biguint(magnitude) == BigUint::from(1u8) << (*bits - 1)
+                    } else {
+                        magnitude_bits < *bits as u64
+                    };
+                    if !in_range {
+                        anyhow::bail!("value {} out of range for i{}", raw, bits);
+                    }
+
+                    buffer.push(if negative {
+                        FieldElement::ZERO - magnitude
+                    } else {
+                        magnitude
+                    });
+                }
+                (AbiType::U256, ArgValue::Literal(raw)) => {
+                    // Plain numbers are treated as if they're using the `u256` scheme
+                    let mut decoded = if raw.contains(':') {
+                        felt_decoder.decode(raw).await?
+                    } else {
+                        felt_decoder.decode(&format!("u256:{}", raw)).await?
+                    };
+                    if decoded.len() != 2 {
+                        anyhow::bail!("expected 2 elements for u256 but found {}", decoded.len());
+                    }
+                    buffer.append(&mut decoded);
+                }
+                (AbiType::U256, ArgValue::Tuple(values)) if values.len() == 2 => {
+                    for value in values.iter() {
+                        self.encode_value(&AbiType::Uint(128), value, felt_decoder, buffer)
+                            .await?;
+                    }
+                }
+                (AbiType::U256, ArgValue::Struct(members)) if members.len() == 2 => {
+                    let ordered = order_struct_members(
+                        "u256",
+                        &["low".to_owned(), "high".to_owned()],
+                        members,
+                    )?;
+                    for value in ordered.into_iter() {
+                        self.encode_value(&AbiType::Uint(128), value, felt_decoder, buffer)
+                            .await?;
+                    }
+                }
+                (AbiType::ByteArray, ArgValue::String(raw) | ArgValue::Literal(raw)) => {
+                    buffer.append(&mut encode_byte_array(raw.as_bytes()));
+                }
+                (AbiType::Array(element_type), ArgValue::List(values)) => {
+                    buffer.push(FieldElement::from(values.len() as u64));
+                    for value in values.iter() {
+                        self.encode_value(element_type, value, felt_decoder, buffer)
+                            .await?;
+                    }
+                }
+                (AbiType::Tuple(member_types), ArgValue::Tuple(values)) => {
+                    if member_types.len() != values.len() {
+                        anyhow::bail!(
+                            "expected tuple of {} element(s) but found {}",
+                            member_types.len(),
+                            values.len()
+                        );
+                    }
+                    for (member_type, value) in member_types.iter().zip(values.iter()) {
+                        self.encode_value(member_type, value, felt_decoder, buffer)
+                            .await?;
+                    }
+                }
+                (AbiType::Named(name), value) => {
+                    self.encode_named(name, value, felt_decoder, buffer).await?;
+                }
+                (abi_type, value) => {
+                    anyhow::bail!("unexpected {} for type `{}`", value.kind(), abi_type);
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    async fn encode_named<S>(
+        &self,
+        name: &str,
+        value: &ArgValue,
+        felt_decoder: &FeltDecoder<S>,
+        buffer: &mut Vec<FieldElement>,
+    ) -> Result<()>
+    where
+        S: ChainIdSource,
+    {
+        if let Some(abi_struct) = self.find_struct(name) {
+            let member_names = abi_struct
+                .members
+                .iter()
+                .map(|member| member.name.clone())
+                .collect::<Vec<_>>();
+
+            let values = match value {
+                ArgValue::Struct(members) => order_struct_members(name, &member_names, members)?,
+                ArgValue::Tuple(values) => values.iter().collect(),
+                _ => anyhow::bail!("unexpected {} for struct `{}`", value.kind(), name),
+            };
+            if values.len() != abi_struct.members.len() {
+                anyhow::bail!(
+                    "struct `{}` has {} member(s) but {} provided",
+                    name,
+                    abi_struct.members.len(),
+                    values.len()
+                );
+            }
+
+            for (member, value) in abi_struct.members.iter().zip(values.into_iter()) {
+                let member_type = AbiType::parse(&member.r#type)?;
+                self.encode_value(&member_type, value, felt_decoder, buffer)
+                    .await
+                    .map_err(|err| anyhow::anyhow!("member `{}`: {}", member.name, err))?;
+            }
+
+            Ok(())
+        } else if let Some(abi_enum) = self.find_enum(name) {
+            let (variant_name, variant_value) = match value {
+                ArgValue::Variant {
+                    name: variant_name,
+                    value: variant_value,
+                } => (variant_name, variant_value.as_deref()),
+                ArgValue::Literal(variant_name) => (variant_name, None),
+                _ => anyhow::bail!("unexpected {} for enum `{}`", value.kind(), name),
+            };
+
+            let (variant_index, variant) = abi_enum
+                .variants
+                .iter()
+                .enumerate()
+                .find(|(_, variant)| &variant.name == variant_name)
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "enum `{}` has no variant `{}`. Valid variants: {}",
+                        name,
+                        variant_name,
+                        abi_enum
+                            .variants
+                            .iter()
+                            .map(|variant| variant.name.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )
+                })?;
+
+            buffer.push(FieldElement::from(variant_index as u64));
+
+            let variant_type = AbiType::parse(&variant.r#type)?;
+            match (variant_value, &variant_type) {
+                (None, AbiType::Tuple(members)) if members.is_empty() => Ok(()),
+                (None, _) => Err(anyhow::anyhow!(
+                    "variant `{}` requires a value of type `{}`",
+                    variant_name,
+                    variant.r#type
+                )),
+                (Some(value), variant_type) => {
+                    self.encode_value(variant_type, value, felt_decoder, buffer)
+                        .await
+                }
+            }
+        } else {
+            Err(anyhow::anyhow!("type `{}` not found in ABI", name))
+        }
+    }
+}
+
+/// Matches struct member values to the declared member order. Values must be either all named or
+/// all positional.
+fn order_struct_members<'a>(
+    struct_name: &str,
+    member_names: &[String],
+    members: &'a [StructMember],
+) -> Result<Vec<&'a ArgValue>> {
+    if members.iter().all(|member| member.name.is_none()) {
+        return Ok(members.iter().map(|member| &member.value).collect());
+    }
+    if members.iter().any(|member| member.name.is_none()) {
+        anyhow::bail!(
+            "cannot mix named and positional members for struct `{}`",
+            struct_name
+        );
+    }
+
+    if let Some(unknown) = members
+        .iter()
+        .filter_map(|member| member.name.as_ref())
+        .find(|name| !member_names.contains(name))
+    {
+        anyhow::bail!("struct `{}` has no member `{}`", struct_name, unknown);
+    }
+
+    member_names
+        .iter()
+        .map(|member_name| {
+            members
+                .iter()
+                .find(|member| member.name.as_ref() == Some(member_name))
+                .map(|member| &member.value)
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "missing member `{}` for struct `{}`",
+                        member_name,
+                        struct_name
+                    )
+                })
+        })
+        .collect()
+}
+
+async fn decode_single<S>(felt_decoder: &FeltDecoder<S>, raw: &str) -> Result<FieldElement>
+where
+    S: ChainIdSource,
+{
+    let decoded = felt_decoder.decode(raw).await?;
+
+    if decoded.len() == 1 {
+        Ok(decoded[0])
+    } else {
+        Err(anyhow::anyhow!(
+            "expected 1 element but found {}",
+            decoded.len()
+        ))
+    }
+}
+
+/// Serializes bytes as a Cairo `ByteArray`: the number of full 31-byte words, the full words, the
+/// pending word, and the pending word length.
+pub(super) fn encode_byte_array(bytes: &[u8]) -> Vec<FieldElement> {
+    let full_words = bytes.chunks_exact(BYTES_PER_WORD);
+    let pending_word = full_words.remainder();
+
+    let mut encoded = vec![FieldElement::from((bytes.len() / BYTES_PER_WORD) as u64)];
+
+    // Unwrapping is safe as 31 bytes always fit in a field element
+    encoded.extend(full_words.map(|word| FieldElement::from_byte_slice_be(word).unwrap()));
+    encoded.push(FieldElement::from_byte_slice_be(pending_word).unwrap());
+    encoded.push(FieldElement::from(pending_word.len() as u64));
+
+    encoded
+}
+
+fn felt_to_biguint(value: FieldElement) -> BigUint {
+    BigUint::from_bytes_be(&value.to_bytes_be())
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use starknet::{core::utils::get_selector_from_name, macros::felt};
+
+    use crate::address_book::AddressBookResolver;
+
+    use super::*;
+
+    /// Chain ID source for tests that never touch the address book.
+    struct NoChainId;
+
+    #[async_trait]
+    impl ChainIdSource for NoChainId {
+        async fn get_chain_id(&self) -> Result<FieldElement> {
+            anyhow::bail!("chain id not available")
+        }
+    }
+
+    fn decoder() -> FeltDecoder<NoChainId> {
+        FeltDecoder::new(AddressBookResolver::new(NoChainId))
+    }
+
+    fn test_abi() -> ContractAbi {
+        ContractAbi::new(
+            serde_json::from_value(serde_json::json!([
+                {
+                    "type": "struct",
+                    "name": "test::Point",
+                    "members": [
+                        { "name": "x", "type": "core::integer::u32" },
+                        { "name": "y", "type": "core::integer::i8" }
+                    ]
+                },
+                {
+                    "type": "enum",
+                    "name": "test::Shape",
+                    "variants": [
+                        { "name": "Empty", "type": "()" },
+                        { "name": "Circle", "type": "core::integer::u32" },
+                        { "name": "Line", "type": "(test::Point, test::Point)" }
+                    ]
+                },
+                {
+                    "type": "enum",
+                    "name": "core::option::Option::<core::byte_array::ByteArray>",
+                    "variants": [
+                        { "name": "Some", "type": "core::byte_array::ByteArray" },
+                        { "name": "None", "type": "()" }
+                    ]
+                },
+                {
+                    "type": "function",
+                    "name": "draw",
+                    "inputs": [
+                        { "name": "shape", "type": "test::Shape" },
+                        { "name": "points", "type": "core::array::Array::<test::Point>" },
+                        {
+                            "name": "label",
+                            "type": "core::option::Option::<core::byte_array::ByteArray>"
+                        }
+                    ],
+                    "outputs": [],
+                    "state_mutability": "external"
+                }
+            ]))
+            .unwrap(),
+        )
+    }
+
+    async fn encode(abi_type: &str, value: &str) -> Result<Vec<FieldElement>> {
+        let abi = test_abi();
+        let felt_decoder = decoder();
+
+        let mut buffer = vec![];
+        abi.encode_value(
+            &AbiType::parse(abi_type)?,
+            &ArgValue::parse(value)?,
+            &felt_decoder,
+            &mut buffer,
+        )
+        .await?;
+
+        Ok(buffer)
+    }
+
+    fn negative(magnitude: FieldElement) -> FieldElement {
+        FieldElement::ZERO - magnitude
+    }
+
+    #[tokio::test]
+    async fn test_encode_u256() {
+        let expected = vec![felt!("1"), felt!("2")];
+
+        // 2 * 2^128 + 1
+        assert_eq!(
+            encode(
+                "core::integer::u256",
+                "680564733841876926926749214863536422913"
+            )
+            .await
+            .unwrap(),
+            expected
+        );
+        assert_eq!(
+            encode("core::integer::u256", "(1, 2)").await.unwrap(),
+            expected
+        );
+        assert_eq!(
+            encode("core::integer::u256", "{ high: 2, low: 1 }")
+                .await
+                .unwrap(),
+            expected
+        );
+        assert!(encode(
+            "core::integer::u256",
+            "(0x100000000000000000000000000000000, 0)"
+        )
+        .await
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_encode_byte_array() {
+        assert_eq!(
+            encode("core::byte_array::ByteArray", r#""hello""#)
+                .await
+                .unwrap(),
+            vec![felt!("0"), felt!("0x68656c6c6f"), felt!("5")]
+        );
+        assert_eq!(
+            encode("core::byte_array::ByteArray", r#""""#)
+                .await
+                .unwrap(),
+            vec![felt!("0"), felt!("0"), felt!("0")]
+        );
+
+        // Exactly one full word leaves an empty pending word
+        assert_eq!(
+            encode(
+                "core::byte_array::ByteArray",
+                r#""abcdefghijklmnopqrstuvwxyz01234""#
+            )
+            .await
+            .unwrap(),
+            vec![
+                felt!("1"),
+                felt!("0x6162636465666768696a6b6c6d6e6f707172737475767778797a3031323334"),
+                felt!("0"),
+                felt!("0"),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_encode_integer_ranges() {
+        assert_eq!(
+            encode("core::integer::u8", "255").await.unwrap(),
+            vec![felt!("255")]
+        );
+        assert!(encode("core::integer::u8", "256").await.is_err());
+
+        // Signed ranges are asymmetric
+        assert_eq!(
+            encode("core::integer::i8", "127").await.unwrap(),
+            vec![felt!("127")]
+        );
+        assert!(encode("core::integer::i8", "128").await.is_err());
+        assert_eq!(
+            encode("core::integer::i8", "-128").await.unwrap(),
+            vec![negative(felt!("128"))]
+        );
+        assert!(encode("core::integer::i8", "-129").await.is_err());
+
+        assert_eq!(
+            encode(
+                "core::integer::i128",
+                "-170141183460469231731687303715884105728"
+            )
+            .await
+            .unwrap(),
+            vec![negative(felt!("170141183460469231731687303715884105728"))]
+        );
+        assert!(encode(
+            "core::integer::i128",
+            "170141183460469231731687303715884105728"
+        )
+        .await
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_encode_structs_and_enums() {
+        assert_eq!(
+            encode("test::Point", "{ y: -1, x: 3 }").await.unwrap(),
+            vec![felt!("3"), negative(felt!("1"))]
+        );
+        assert_eq!(
+            encode("test::Point", "(3, -1)").await.unwrap(),
+            vec![felt!("3"), negative(felt!("1"))]
+        );
+        assert!(encode("test::Point", "{ x: 3, 1 }").await.is_err());
+        assert!(encode("test::Point", "{ x: 3, z: 1 }").await.is_err());
+        assert!(encode("test::Point", "{ x: 3 }").await.is_err());
+
+        assert_eq!(
+            encode("test::Shape", "Empty").await.unwrap(),
+            vec![felt!("0")]
+        );
+        assert_eq!(
+            encode("test::Shape", "Line({ x: 1, y: 2 }, (3, -4))")
+                .await
+                .unwrap(),
+            vec![
+                felt!("2"),
+                felt!("1"),
+                felt!("2"),
+                felt!("3"),
+                negative(felt!("4")),
+            ]
+        );
+        assert!(encode("test::Shape", "Circle").await.is_err());
+        assert!(encode("test::Shape", "Square(1)").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_encode_function_call() {
+        let abi = test_abi();
+        let selector = get_selector_from_name("draw").unwrap();
+
+        let calldata = abi
+            .encode_function_call(
+                selector,
+                &[
+                    String::from("Circle(10)"),
+                    String::from("[{ x: 1, y: -1 }, { x: 3, y: 2 }]"),
+                    String::from(r#"Some("hi")"#),
+                ],
+                &decoder(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            calldata,
+            vec![
+                felt!("1"),
+                felt!("10"),
+                felt!("2"),
+                felt!("1"),
+                negative(felt!("1")),
+                felt!("3"),
+                felt!("2"),
+                felt!("0"),
+                felt!("0"),
+                felt!("0x6869"),
+                felt!("2"),
+            ]
+        );
+
+        assert!(abi
+            .encode_function_call(selector, &[String::from("Empty")], &decoder())
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,489 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use starknet::{
+    core::{
+        types::{
+            contract::{
+                AbiEntry, AbiEvent, AbiEventEnum, AbiEventStruct, EventFieldKind, TypedAbiEvent,
+                UntypedAbiEvent,
+            },
+            BlockId, BlockTag, FieldElement,
+        },
+        utils::get_selector_from_name,
+    },
+    providers::Provider,
+};
+
+use super::{decode::FeltReader, types::AbiType, ContractAbi};
+
+/// An event decoded against the ABI of its emitting contract.
+#[derive(Debug, Clone, Serialize)]
+pub struct DecodedEvent {
+    /// Full name of the event type, e.g. `openzeppelin::token::erc20::ERC20::Transfer`.
+    pub name: String,
+    pub fields: Map<String, Value>,
+}
+
+/// Decodes events emitted by any contract, fetching and caching class ABIs by class hash.
+pub struct EventDecoder<P> {
+    provider: P,
+    class_hashes: HashMap<(FieldElement, Option<u64>), Option<FieldElement>>,
+    abis: HashMap<FieldElement, Option<ContractAbi>>,
+}
+
+/// Where an event type is found in the ABI after resolving its selector.
+enum EventMatch<'a> {
+    Struct(&'a AbiEventStruct),
+    Untyped(&'a UntypedAbiEvent),
+}
+
+impl ContractAbi {
+    /// Decodes an event by matching `keys[0]` against the selectors of events defined in the ABI.
+    /// Returns `None` if no matching event is found.
+    pub fn decode_event(
+        &self,
+        keys: &[FieldElement],
+        data: &[FieldElement],
+    ) -> Result<Option<DecodedEvent>> {
+        let selector = match keys.first() {
+            Some(selector) => *selector,
+            None => return Ok(None),
+        };
+
+        let mut key_reader = FeltReader::new(&keys[1..]);
+        let mut data_reader = FeltReader::new(data);
+
+        let event = match self.find_event_by_selector(selector, &mut key_reader)? {
+            Some(event) => event,
+            None => return Ok(None),
+        };
+
+        let mut fields = Map::new();
+        let name = match event {
+            EventMatch::Struct(event) => {
+                for member in event.members.iter() {
+                    let member_type = AbiType::parse(&member.r#type)?;
+                    let reader = match member.kind {
+                        EventFieldKind::Key => &mut key_reader,
+                        EventFieldKind::Data | EventFieldKind::Nested | EventFieldKind::Flat => {
+                            &mut data_reader
+                        }
+                    };
+                    fields.insert(
+                        member.name.clone(),
+                        self.decode_value(&member_type, reader, false)?,
+                    );
+                }
+
+                event.name.clone()
+            }
+            EventMatch::Untyped(event) => {
+                // Legacy Cairo 1 events put all members into data
+                for input in event.inputs.iter() {
+                    let input_type = AbiType::parse(&input.r#type)?;
+                    fields.insert(
+                        input.name.clone(),
+                        self.decode_value(&input_type, &mut data_reader, false)?,
+                    );
+                }
+
+                event.name.clone()
+            }
+        };
+
+        key_reader.finish()?;
+        data_reader.finish()?;
+
+        Ok(Some(DecodedEvent { name, fields }))
+    }
+
+    /// Finds the selector of an event by name, which can be either the variant name used in the
+    /// contract `Event` enum or the full name of the event type. Events of flattened components
+    /// are found by their own variant names.
+    pub fn find_event_selector(&self, name: &str) -> Option<FieldElement> {
+        self.root_event_enums()
+            .find_map(|event_enum| self.find_variant_selector(event_enum, name))
+            .or_else(|| {
+                self.untyped_events()
+                    .find(|event| event.name == name)
+                    .and_then(|event| get_selector_from_name(&event.name).ok())
+            })
+    }
+
+    /// Looks up the selector of the variant matching `name`, descending into flattened variants
+    /// whose own variants are emitted with their selectors as the first key.
+    fn find_variant_selector(&self, event_enum: &AbiEventEnum, name: &str) -> Option<FieldElement> {
+        event_enum
+            .variants
+            .iter()
+            .find_map(|variant| match variant.kind {
+                EventFieldKind::Nested if variant.name == name || variant.r#type == name => {
+                    get_selector_from_name(&variant.name).ok()
+                }
+                EventFieldKind::Flat => self
+                    .find_event_enum(&variant.r#type)
+                    .and_then(|inner| self.find_variant_selector(inner, name)),
+                _ => None,
+            })
+    }
+
+    fn find_event_by_selector(
+        &self,
+        selector: FieldElement,
+        key_reader: &mut FeltReader,
+    ) -> Result<Option<EventMatch>> {
+        for event_enum in self.event_enums() {
+            if let Some(event_type) = self.find_enum_variant_type(event_enum, selector) {
+                return self.resolve_event_type(event_type, key_reader);
+            }
+        }
+
+        Ok(self
+            .untyped_events()
+            .find(|event| get_selector_from_name(&event.name).is_ok_and(|value| value == selector))
+            .map(EventMatch::Untyped))
+    }
+
+    /// Looks up the type of the variant identified by `selector`, descending into flattened
+    /// variants whose selectors are omitted from event keys.
+    fn find_enum_variant_type<'a>(
+        &'a self,
+        event_enum: &'a AbiEventEnum,
+        selector: FieldElement,
+    ) -> Option<&'a str> {
+        event_enum
+            .variants
+            .iter()
+            .find_map(|variant| match variant.kind {
+                EventFieldKind::Nested => get_selector_from_name(&variant.name)
+                    .is_ok_and(|value| value == selector)
+                    .then_some(variant.r#type.as_str()),
+                EventFieldKind::Flat => self
+                    .find_event_enum(&variant.r#type)
+                    .and_then(|inner| self.find_enum_variant_type(inner, selector)),
+                _ => None,
+            })
+    }
+
+    /// Event types can themselves be enums (e.g. events of components that are not flattened), in
+    /// which case the next key is the selector of the inner variant.
+    fn resolve_event_type(
+        &self,
+        event_type: &str,
+        key_reader: &mut FeltReader,
+    ) -> Result<Option<EventMatch>> {
+        if let Some(event_struct) = self.find_event_struct(event_type) {
+            Ok(Some(EventMatch::Struct(event_struct)))
+        } else if let Some(event_enum) = self.find_event_enum(event_type) {
+            let selector = key_reader.read()?;
+            match self.find_enum_variant_type(event_enum, selector) {
+                Some(inner_type) => self.resolve_event_type(inner_type, key_reader),
+                None => Ok(None),
+            }
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn find_event_struct(&self, name: &str) -> Option<&AbiEventStruct> {
+        self.entries.iter().find_map(|entry| match entry {
+            AbiEntry::Event(AbiEvent::Typed(TypedAbiEvent::Struct(inner)))
+                if inner.name == name =>
+            {
+                Some(inner)
+            }
+            _ => None,
+        })
+    }
+
+    fn find_event_enum(&self, name: &str) -> Option<&AbiEventEnum> {
+        self.event_enums().find(|inner| inner.name == name)
+    }
+
+    fn event_enums(&self) -> impl Iterator<Item = &AbiEventEnum> {
+        self.entries.iter().filter_map(|entry| match entry {
+            AbiEntry::Event(AbiEvent::Typed(TypedAbiEvent::Enum(inner))) => Some(inner),
+            _ => None,
+        })
+    }
+
+    /// Event enums not used as the type of any variant, i.e. the `Event` enum of the contract.
+    /// Variants of other enums are only emitted through these.
+    fn root_event_enums(&self) -> impl Iterator<Item = &AbiEventEnum> {
+        self.event_enums().filter(|event_enum| {
+            !self
+                .event_enums()
+                .flat_map(|other| other.variants.iter())
+                .any(|variant| variant.r#type == event_enum.name)
+        })
+    }
+
+    fn untyped_events(&self) -> impl Iterator<Item = &UntypedAbiEvent> {
+        self.entries.iter().filter_map(|entry| match entry {
+            AbiEntry::Event(AbiEvent::Untyped(inner)) => Some(inner),
+            _ => None,
+        })
+    }
+}
+
+impl<P> EventDecoder<P>
+where
+    P: Provider,
+{
+    pub fn new(provider: P) -> Self {
+        Self {
+            provider,
+            class_hashes: HashMap::new(),
+            abis: HashMap::new(),
+        }
+    }
+
+    /// Decodes an event emitted from `from_address` in block `block_number`, or the pending block
+    /// if `None`. Returns `None` if the emitting class has no usable ABI (e.g. legacy classes), or
+    /// the event cannot be decoded with it.
+    pub async fn decode(
+        &mut self,
+        from_address: FieldElement,
+        block_number: Option<u64>,
+        keys: &[FieldElement],
+        data: &[FieldElement],
+    ) -> Option<DecodedEvent> {
+        // Contracts can be upgraded, so the class is the one at the block the event is emitted in
+        let block_id = match block_number {
+            Some(block_number) => BlockId::Number(block_number),
+            None => BlockId::Tag(BlockTag::Pending),
+        };
+
+        let class_hash = match self.class_hashes.get(&(from_address, block_number)) {
+            Some(class_hash) => *class_hash,
+            None => {
+                let class_hash = match self
+                    .provider
+                    .get_class_hash_at(block_id, from_address)
+                    .await
+                {
+                    Ok(class_hash) => Some(class_hash),
+                    Err(err) => {
+                        log::debug!(
+                            "Unable to fetch class hash at {:#064x}: {}",
+                            from_address,
+                            err
+                        );
+                        None
+                    }
+                };
+                self.class_hashes
+                    .insert((from_address, block_number), class_hash);
+                class_hash
+            }
+        }?;
+
+        if !self.abis.contains_key(&class_hash) {
+            let abi = match self.provider.get_class(block_id, class_hash).await {
+                Ok(class) => ContractAbi::from_class(class).ok(),
+                Err(err) => {
+                    log::debug!("Unable to fetch class {:#064x}: {}", class_hash, err);
+                    None
+                }
+            };
+            self.abis.insert(class_hash, abi);
+        }
+
+        let abi = self.abis.get(&class_hash)?.as_ref()?;
+        match abi.decode_event(keys, data) {
+            Ok(decoded) => decoded,
+            Err(err) => {
+                log::debug!(
+                    "Unable to decode event from {:#064x}: {}",
+                    from_address,
+                    err
+                );
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use starknet::macros::felt;
+
+    use super::*;
+
+    /// A contract with a flattened component, a nested component, and an event of its own.
+    fn test_abi() -> ContractAbi {
+        ContractAbi::new(
+            serde_json::from_value(json!([
+                {
+                    "type": "event",
+                    "name": "test::Component::Transfer",
+                    "kind": "struct",
+                    "members": [
+                        {
+                            "name": "from",
+                            "type": "core::starknet::contract_address::ContractAddress",
+                            "kind": "key"
+                        },
+                        {
+                            "name": "to",
+                            "type": "core::starknet::contract_address::ContractAddress",
+                            "kind": "key"
+                        },
+                        { "name": "value", "type": "core::integer::u256", "kind": "data" }
+                    ]
+                },
+                {
+                    "type": "event",
+                    "name": "test::Component::Event",
+                    "kind": "enum",
+                    "variants": [
+                        {
+                            "name": "Transfer",
+                            "type": "test::Component::Transfer",
+                            "kind": "nested"
+                        }
+                    ]
+                },
+                {
+                    "type": "event",
+                    "name": "test::Other::Paused",
+                    "kind": "struct",
+                    "members": [
+                        { "name": "account", "type": "core::felt252", "kind": "data" }
+                    ]
+                },
+                {
+                    "type": "event",
+                    "name": "test::Other::Event",
+                    "kind": "enum",
+                    "variants": [
+                        { "name": "Paused", "type": "test::Other::Paused", "kind": "nested" }
+                    ]
+                },
+                {
+                    "type": "event",
+                    "name": "test::Contract::Minted",
+                    "kind": "struct",
+                    "members": [
+                        { "name": "amount", "type": "core::integer::u128", "kind": "data" }
+                    ]
+                },
+                {
+                    "type": "event",
+                    "name": "test::Contract::Event",
+                    "kind": "enum",
+                    "variants": [
+                        {
+                            "name": "ComponentEvent",
+                            "type": "test::Component::Event",
+                            "kind": "flat"
+                        },
+                        { "name": "OtherEvent", "type": "test::Other::Event", "kind": "nested" },
+                        { "name": "Minted", "type": "test::Contract::Minted", "kind": "nested" }
+                    ]
+                }
+            ]))
+            .unwrap(),
+        )
+    }
+
+    fn selector(name: &str) -> FieldElement {
+        get_selector_from_name(name).unwrap()
+    }
+
+    #[test]
+    fn test_find_event_selector() {
+        let abi = test_abi();
+
+        assert_eq!(abi.find_event_selector("Minted"), Some(selector("Minted")));
+        assert_eq!(
+            abi.find_event_selector("test::Contract::Minted"),
+            Some(selector("Minted"))
+        );
+
+        // Events of flattened components are keyed by their own variant names
+        assert_eq!(
+            abi.find_event_selector("Transfer"),
+            Some(felt!(
+                "0x99cd8bde557814842a3121e8ddfd433a539b8c9f14bf31ebf108d12e6196e9"
+            ))
+        );
+        assert_eq!(
+            abi.find_event_selector("test::Component::Transfer"),
+            Some(selector("Transfer"))
+        );
+        assert_eq!(abi.find_event_selector("ComponentEvent"), None);
+
+        // Events of nested components are keyed by the component variant name
+        assert_eq!(
+            abi.find_event_selector("OtherEvent"),
+            Some(selector("OtherEvent"))
+        );
+        assert_eq!(
+            abi.find_event_selector("test::Other::Event"),
+            Some(selector("OtherEvent"))
+        );
+        assert_eq!(abi.find_event_selector("Paused"), None);
+
+        assert_eq!(abi.find_event_selector("Unknown"), None);
+    }
+
+    #[test]
+    fn test_decode_flattened_event() {
+        let decoded = test_abi()
+            .decode_event(
+                &[selector("Transfer"), felt!("0x1"), felt!("0x2")],
+                &[felt!("100"), felt!("0")],
+            )
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(decoded.name, "test::Component::Transfer");
+        assert_eq!(
+            Value::Object(decoded.fields),
+            json!({
+                "from": format!("{:#064x}", felt!("0x1")),
+                "to": format!("{:#064x}", felt!("0x2")),
+                "value": "100",
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_nested_event() {
+        let decoded = test_abi()
+            .decode_event(
+                &[selector("OtherEvent"), selector("Paused")],
+                &[felt!("0x3")],
+            )
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(decoded.name, "test::Other::Paused");
+        assert_eq!(
+            Value::Object(decoded.fields),
+            json!({ "account": format!("{:#064x}", felt!("0x3")) })
+        );
+    }
+
+    #[test]
+    fn test_decode_unknown_or_malformed_event() {
+        let abi = test_abi();
+
+        assert!(abi
+            .decode_event(&[selector("Unknown")], &[])
+            .unwrap()
+            .is_none());
+        assert!(abi.decode_event(&[], &[]).unwrap().is_none());
+
+        // Missing and trailing elements
+        assert!(abi.decode_event(&[selector("Minted")], &[]).is_err());
+        assert!(abi
+            .decode_event(&[selector("Minted")], &[felt!("1"), felt!("2")])
+            .is_err());
+    }
+}
@@ -0,0 +1,202 @@
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use clap::Parser;
+use starknet::{
+    core::{
+        types::{
+            contract::{
+                AbiConstructor, AbiEntry, AbiEnum, AbiFunction, AbiNamedMember, AbiStruct,
+                SierraClass,
+            },
+            BlockId, ContractClass, FieldElement,
+        },
+        utils::get_selector_from_name,
+    },
+    providers::Provider,
+};
+
+use crate::{path::ExpandedPathbufParser, utils::parse_flattened_sierra_class};
+
+mod decode;
+mod encode;
+mod event;
+mod types;
+mod value;
+
+pub use decode::FeltReader;
+pub use event::EventDecoder;
+
+#[derive(Debug, Clone, Parser)]
+pub struct AbiArgs {
+    #[clap(
+        long,
+        help = "Encode arguments as typed values against the ABI of the class on the network"
+    )]
+    typed: bool,
+    #[clap(
+        long,
+        value_parser = ExpandedPathbufParser,
+        help = "Encode arguments as typed values against the ABI of a local Sierra class artifact"
+    )]
+    abi: Option<PathBuf>,
+}
+
+/// Where to get the ABI from when encoding typed arguments.
+#[derive(Debug)]
+pub enum AbiSource {
+    Network,
+    File(ContractAbi),
+}
+
+/// A Sierra contract ABI with lookup helpers for functions and user-defined types.
+#[derive(Debug, Clone)]
+pub struct ContractAbi {
+    entries: Vec<AbiEntry>,
+}
+
+impl AbiArgs {
+    /// Returns `None` if typed arguments are not requested, in which case all arguments should be
+    /// treated as raw field elements.
+    pub fn into_source(self) -> Result<Option<AbiSource>> {
+        match (self.typed, self.abi) {
+            (_, Some(path)) => Ok(Some(AbiSource::File(ContractAbi::load(&path)?))),
+            (true, None) => Ok(Some(AbiSource::Network)),
+            (false, None) => Ok(None),
+        }
+    }
+}
+
+impl AbiSource {
+    /// Gets the ABI of the contract deployed at `address` as of `block_id`.
+    pub async fn abi_at<P>(
+        &self,
+        provider: P,
+        address: FieldElement,
+        block_id: BlockId,
+    ) -> Result<Cow<ContractAbi>>
+    where
+        P: Provider,
+    {
+        match self {
+            Self::Network => {
+                let class = provider.get_class_at(block_id, address).await?;
+                Ok(Cow::Owned(ContractAbi::from_class(class)?))
+            }
+            Self::File(abi) => Ok(Cow::Borrowed(abi)),
+        }
+    }
+
+    /// Gets the ABI of the declared class `class_hash` as of `block_id`.
+    pub async fn abi_of_class<P>(
+        &self,
+        provider: P,
+        class_hash: FieldElement,
+        block_id: BlockId,
+    ) -> Result<Cow<ContractAbi>>
+    where
+        P: Provider,
+    {
+        match self {
+            Self::Network => {
+                let class = provider.get_class(block_id, class_hash).await?;
+                Ok(Cow::Owned(ContractAbi::from_class(class)?))
+            }
+            Self::File(abi) => Ok(Cow::Borrowed(abi)),
+        }
+    }
+}
+
+impl ContractAbi {
+    pub fn new(entries: Vec<AbiEntry>) -> Self {
+        Self { entries }
+    }
+
+    /// Loads ABI from either a Sierra class artifact or a standalone ABI JSON file.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            anyhow::bail!("ABI file not found: {}", path.display());
+        }
+
+        if let Ok(class) = serde_json::from_reader::<_, SierraClass>(std::fs::File::open(path)?) {
+            Ok(Self::new(class.abi))
+        } else if let Ok(entries) =
+            serde_json::from_reader::<_, Vec<AbiEntry>>(std::fs::File::open(path)?)
+        {
+            Ok(Self::new(entries))
+        } else {
+            anyhow::bail!(
+                "failed to parse ABI from file: {}. \
+                Only Sierra class artifacts and Sierra ABI files are supported.",
+                path.display()
+            );
+        }
+    }
+
+    pub fn from_class(class: ContractClass) -> Result<Self> {
+        match class {
+            ContractClass::Sierra(class) => Ok(Self::new(parse_flattened_sierra_class(class)?.abi)),
+            ContractClass::Legacy(_) => Err(anyhow::anyhow!(
+                "typed arguments are only supported for Cairo 1 classes"
+            )),
+        }
+    }
+
+    /// Finds an external or view function by its selector. Functions declared inside interfaces
+    /// are also searched.
+    pub fn find_function(&self, selector: FieldElement) -> Result<&AbiFunction> {
+        self.functions()
+            .find(|function| {
+                get_selector_from_name(&function.name).is_ok_and(|value| value == selector)
+            })
+            .ok_or_else(|| {
+                anyhow::anyhow!("function with selector {:#064x} not found in ABI", selector)
+            })
+    }
+
+    /// Inputs of the contract constructor. Classes without an explicit constructor take no inputs.
+    pub fn constructor_inputs(&self) -> &[AbiNamedMember] {
+        self.entries
+            .iter()
+            .find_map(|entry| match entry {
+                AbiEntry::Constructor(AbiConstructor { inputs, .. }) => Some(inputs.as_slice()),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn find_struct(&self, name: &str) -> Option<&AbiStruct> {
+        self.entries.iter().find_map(|entry| match entry {
+            AbiEntry::Struct(inner) if inner.name == name => Some(inner),
+            _ => None,
+        })
+    }
+
+    pub fn find_enum(&self, name: &str) -> Option<&AbiEnum> {
+        self.entries.iter().find_map(|entry| match entry {
+            AbiEntry::Enum(inner) if inner.name == name => Some(inner),
+            _ => None,
+        })
+    }
+
+    fn functions(&self) -> impl Iterator<Item = &AbiFunction> {
+        self.entries.iter().flat_map(|entry| {
+            let items: Vec<&AbiFunction> = match entry {
+                AbiEntry::Function(function) => vec![function],
+                AbiEntry::Interface(interface) => interface
+                    .items
+                    .iter()
+                    .filter_map(|item| match item {
+                        AbiEntry::Function(function) => Some(function),
+                        _ => None,
+                    })
+                    .collect(),
+                _ => vec![],
+            };
+            items
+        })
+    }
+}
@@ -0,0 +1,300 @@
+use std::fmt::Display;
+
+use anyhow::Result;
+
+/// Cairo types as they appear in Sierra ABI type strings.
+///
+/// Generic types that are declared as ABI entries themselves (e.g. `Option` and `Result`) are not
+/// modeled explicitly, and are instead kept as [`AbiType::Named`] with their full name including
+/// generic arguments. This is exactly how they're referenced by the ABI entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbiType {
+    /// Any type represented by a single field element without further range checks, such as
+    /// `felt252`, `ContractAddress` and `ClassHash`.
+    Felt,
+    /// Same as [`AbiType::Felt`] on the wire, but holds text.
+    Bytes31,
+    Bool,
+    /// Unsigned integers up to 128 bits, with the bit size attached.
+    Uint(u32),
+    /// Signed integers up to 128 bits, with the bit size attached.
+    Int(u32),
+    U256,
+    ByteArray,
+    /// Both `Array<T>` and `Span<T>` as they're serialized the same way.
+    Array(Box<AbiType>),
+    Tuple(Vec<AbiType>),
+    /// A struct or enum type that must be looked up from the ABI.
+    Named(String),
+}
+
+struct TypeParser<'a> {
+    raw: &'a str,
+    position: usize,
+}
+
+impl AbiType {
+    pub fn parse(raw: &str) -> Result<Self> {
+        let mut parser = TypeParser { raw, position: 0 };
+
+        let parsed = parser.parse_type()?;
+        parser.skip_whitespaces();
+        if parser.position != raw.len() {
+            anyhow::bail!("unexpected trailing content in ABI type: {}", raw);
+        }
+
+        Ok(parsed)
+    }
+
+    fn from_path(path: &str, generic_args: Vec<AbiType>, full_name: String) -> Self {
+        let mut generic_args = generic_args;
+
+        match (path, generic_args.len()) {
+            (
+                "core::felt252"
+                | "felt252"
+                | "core::starknet::contract_address::ContractAddress"
+                | "core::starknet::class_hash::ClassHash"
+                | "core::starknet::eth_address::EthAddress"
+                | "core::starknet::storage_access::StorageAddress"
+                | "core::starknet::storage_access::StorageBaseAddress",
+                0,
+            ) => Self::Felt,
+            ("core::bytes_31::bytes31" | "bytes31", 0) => Self::Bytes31,
+            ("core::bool" | "bool", 0) => Self::Bool,
+            ("core::integer::u8" | "u8", 0) => Self::Uint(8),
+            ("core::integer::u16" | "u16", 0) => Self::Uint(16),
+            ("core::integer::u32" | "u32" | "core::integer::usize" | "usize", 0) => Self::Uint(32),
+            ("core::integer::u64" | "u64", 0) => Self::Uint(64),
+            ("core::integer::u128" | "u128", 0) => Self::Uint(128),
+            ("core::integer::i8" | "i8", 0) => Self::Int(8),
+            ("core::integer::i16" | "i16", 0) => Self::Int(16),
+            ("core::integer::i32" | "i32", 0) => Self::Int(32),
+            ("core::integer::i64" | "i64", 0) => Self::Int(64),
+            ("core::integer::i128" | "i128", 0) => Self::Int(128),
+            ("core::integer::u256" | "u256", 0) => Self::U256,
+            ("core::byte_array::ByteArray" | "ByteArray", 0) => Self::ByteArray,
+            ("core::array::Array" | "core::array::Span" | "Array" | "Span", 1) => {
+                // Length already checked
+                Self::Array(Box::new(generic_args.remove(0)))
+            }
+            ("core::zeroable::NonZero" | "NonZero", 1) => {
+                // `NonZero<T>` is serialized just like `T`
+                generic_args.remove(0)
+            }
+            _ => Self::Named(full_name),
+        }
+    }
+}
+
+impl Display for AbiType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Felt => write!(f, "felt252"),
+            Self::Bytes31 => write!(f, "bytes31"),
+            Self::Bool => write!(f, "bool"),
+            Self::Uint(bits) => write!(f, "u{}", bits),
+            Self::Int(bits) => write!(f, "i{}", bits),
+            Self::U256 => write!(f, "u256"),
+            Self::ByteArray => write!(f, "ByteArray"),
+            Self::Array(inner) => write!(f, "Array<{}>", inner),
+            Self::Tuple(members) => {
+                write!(f, "(")?;
+                for (ind, member) in members.iter().enumerate() {
+                    if ind > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", member)?;
+                }
+                write!(f, ")")
+            }
+            Self::Named(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+impl<'a> TypeParser<'a> {
+    fn parse_type(&mut self) -> Result<AbiType> {
+        self.skip_whitespaces();
+
+        // Snapshot types are serialized the same way as the underlying types
+        if self.peek() == Some('@') {
+            self.position += 1;
+            return self.parse_type();
+        }
+
+        if self.peek() == Some('(') {
+            self.position += 1;
+
+            let mut members = vec![];
+            loop {
+                self.skip_whitespaces();
+                if self.peek() == Some(')') {
+                    self.position += 1;
+                    break;
+                }
+
+                members.push(self.parse_type()?);
+
+                self.skip_whitespaces();
+                match self.advance() {
+                    Some(',') => continue,
+                    Some(')') => break,
+                    _ => anyhow::bail!("invalid tuple type: {}", self.raw),
+                }
+            }
+
+            return Ok(AbiType::Tuple(members));
+        }
+
+        let start = self.position;
+        while let Some(c) = self.peek() {
+            if c.is_alphanumeric() || c == '_' {
+                self.position += c.len_utf8();
+            } else if self.raw[self.position..].starts_with("::")
+                && !self.raw[self.position..].starts_with("::<")
+            {
+                self.position += 2;
+            } else {
+                break;
+            }
+        }
+        let path = &self.raw[start..self.position];
+        if path.is_empty() {
+            anyhow::bail!("invalid ABI type: {}", self.raw);
+        }
+
+        let mut generic_args = vec![];
+        if self.raw[self.position..].starts_with("::<") {
+            self.position += 3;
+
+            loop {
+                generic_args.push(self.parse_type()?);
+
+                self.skip_whitespaces();
+                match self.advance() {
+                    Some(',') => continue,
+                    Some('>') => break,
+                    _ => anyhow::bail!("invalid generic type: {}", self.raw),
+                }
+            }
+        }
+
+        let full_name = self.raw[start..self.position].to_owned();
+
+        Ok(AbiType::from_path(path, generic_args, full_name))
+    }
+
+    fn skip_whitespaces(&mut self) {
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() {
+                self.position += c.len_utf8();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.raw[self.position..].chars().next()
+    }
+
+    fn advance(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+        Some(c)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_core_types() {
+        for (raw, expected) in [
+            ("core::felt252", AbiType::Felt),
+            (
+                "core::starknet::contract_address::ContractAddress",
+                AbiType::Felt,
+            ),
+            ("core::bytes_31::bytes31", AbiType::Bytes31),
+            ("core::bool", AbiType::Bool),
+            ("core::integer::u8", AbiType::Uint(8)),
+            ("core::integer::usize", AbiType::Uint(32)),
+            ("core::integer::i128", AbiType::Int(128)),
+            ("core::integer::u256", AbiType::U256),
+            ("core::byte_array::ByteArray", AbiType::ByteArray),
+            ("u64", AbiType::Uint(64)),
+        ] {
+            assert_eq!(AbiType::parse(raw).unwrap(), expected, "{}", raw);
+        }
+    }
+
+    #[test]
+    fn test_parse_generic_types() {
+        assert_eq!(
+            AbiType::parse("core::array::Array::<core::integer::u32>").unwrap(),
+            AbiType::Array(Box::new(AbiType::Uint(32)))
+        );
+        assert_eq!(
+            AbiType::parse("core::array::Span::<core::array::Array::<core::felt252>>").unwrap(),
+            AbiType::Array(Box::new(AbiType::Array(Box::new(AbiType::Felt))))
+        );
+        assert_eq!(
+            AbiType::parse("core::zeroable::NonZero::<core::integer::u64>").unwrap(),
+            AbiType::Uint(64)
+        );
+
+        // Generic types defined in the ABI keep their full name
+        assert_eq!(
+            AbiType::parse("core::option::Option::<core::integer::u8>").unwrap(),
+            AbiType::Named(String::from("core::option::Option::<core::integer::u8>"))
+        );
+        assert_eq!(
+            AbiType::parse("core::result::Result::<core::felt252, core::bool>").unwrap(),
+            AbiType::Named(String::from(
+                "core::result::Result::<core::felt252, core::bool>"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_tuple_and_snapshot_types() {
+        assert_eq!(
+            AbiType::parse("(core::felt252, (core::bool, core::integer::i16))").unwrap(),
+            AbiType::Tuple(vec![
+                AbiType::Felt,
+                AbiType::Tuple(vec![AbiType::Bool, AbiType::Int(16)]),
+            ])
+        );
+        assert_eq!(AbiType::parse("()").unwrap(), AbiType::Tuple(vec![]));
+        assert_eq!(
+            AbiType::parse("@core::array::Array::<core::felt252>").unwrap(),
+            AbiType::Array(Box::new(AbiType::Felt))
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_types() {
+        for raw in [
+            "",
+            "core::felt252 core::bool",
+            "core::array::Array::<core::felt252",
+            "(core::felt252",
+            "(core::felt252;)",
+        ] {
+            assert!(AbiType::parse(raw).is_err(), "{}", raw);
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            AbiType::parse("core::array::Span::<(core::integer::u8, core::bool)>")
+                .unwrap()
+                .to_string(),
+            "Array<(u8, bool)>"
+        );
+    }
+}
@@ -0,0 +1,344 @@
+use anyhow::Result;
+
+/// A typed argument value as entered on the command line, before being encoded against an ABI
+/// type. The syntax is loosely modeled after Cairo literals:
+///
+/// - `[a, b, c]` for arrays and spans;
+/// - `(a, b)` for tuples;
+/// - `{ a, b }` or `{ name: a, other: b }` for structs;
+/// - `Variant` or `Variant(value)` for enums (including `Some(value)` and `None`);
+/// - `"text"` for quoted strings;
+/// - anything else is kept as a raw literal to be resolved later.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgValue {
+    Literal(String),
+    String(String),
+    List(Vec<ArgValue>),
+    Tuple(Vec<ArgValue>),
+    Struct(Vec<StructMember>),
+    Variant {
+        name: String,
+        value: Option<Box<ArgValue>>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructMember {
+    pub name: Option<String>,
+    pub value: ArgValue,
+}
+
+struct ValueParser<'a> {
+    raw: &'a str,
+    position: usize,
+}
+
+impl ArgValue {
+    pub fn parse(raw: &str) -> Result<Self> {
+        let mut parser = ValueParser { raw, position: 0 };
+
+        let value = parser.parse_value()?;
+        parser.skip_whitespaces();
+        if parser.position != raw.len() {
+            anyhow::bail!(
+                "unexpected character at position {} in argument: {}",
+                parser.position,
+                raw
+            );
+        }
+
+        Ok(value)
+    }
+
+    /// Short description of the value kind used in error messages.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Literal(_) => "literal",
+            Self::String(_) => "string",
+            Self::List(_) => "array",
+            Self::Tuple(_) => "tuple",
+            Self::Struct(_) => "struct",
+            Self::Variant { .. } => "enum variant",
+        }
+    }
+}
+
+impl<'a> ValueParser<'a> {
+    fn parse_value(&mut self) -> Result<ArgValue> {
+        self.skip_whitespaces();
+
+        match self.peek() {
+            Some('[') => {
+                self.position += 1;
+                Ok(ArgValue::List(self.parse_sequence(']')?))
+            }
+            Some('(') => {
+                self.position += 1;
+                Ok(ArgValue::Tuple(self.parse_sequence(')')?))
+            }
+            Some('{') => {
+                self.position += 1;
+                Ok(ArgValue::Struct(self.parse_struct_members()?))
+            }
+            Some('"') => {
+                self.position += 1;
+                Ok(ArgValue::String(self.parse_quoted_string()?))
+            }
+            Some(_) => {
+                let literal = self.parse_literal();
+                if literal.is_empty() {
+                    anyhow::bail!(
+                        "unexpected character at position {} in argument: {}",
+                        self.position,
+                        self.raw
+                    );
+                }
+
+                // An identifier immediately followed by parentheses is an enum variant with data
+                if self.peek() == Some('(') && is_identifier(&literal) {
+                    self.position += 1;
+
+                    let mut values = self.parse_sequence(')')?;
+                    let value = match values.len() {
+                        0 => ArgValue::Tuple(vec![]),
+                        1 => values.remove(0),
+                        _ => ArgValue::Tuple(values),
+                    };
+
+                    Ok(ArgValue::Variant {
+                        name: literal,
+                        value: Some(Box::new(value)),
+                    })
+                } else {
+                    Ok(ArgValue::Literal(literal))
+                }
+            }
+            None => anyhow::bail!("unexpected end of argument: {}", self.raw),
+        }
+    }
+
+    fn parse_sequence(&mut self, end: char) -> Result<Vec<ArgValue>> {
+        let mut values = vec![];
+
+        loop {
+            self.skip_whitespaces();
+            if self.peek() == Some(end) {
+                self.position += 1;
+                break;
+            }
+
+            values.push(self.parse_value()?);
+
+            self.skip_whitespaces();
+            match self.advance() {
+                Some(',') => continue,
+                Some(c) if c == end => break,
+                _ => anyhow::bail!("expected `,` or `{}` in argument: {}", end, self.raw),
+            }
+        }
+
+        Ok(values)
+    }
+
+    fn parse_struct_members(&mut self) -> Result<Vec<StructMember>> {
+        let mut members = vec![];
+
+        loop {
+            self.skip_whitespaces();
+            if self.peek() == Some('}') {
+                self.position += 1;
+                break;
+            }
+
+            // Named members must have whitespace after the colon. Otherwise it would be impossible
+            // to tell them apart from literals like `addr:eth`.
+            let rest = &self.raw[self.position..];
+            let name_len = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            let name = if name_len > 0
+                && rest[name_len..].starts_with(':')
+                && rest[(name_len + 1)..].starts_with(char::is_whitespace)
+            {
+                self.position += name_len + 1;
+                Some(rest[..name_len].to_owned())
+            } else {
+                None
+            };
+
+            members.push(StructMember {
+                name,
+                value: self.parse_value()?,
+            });
+
+            self.skip_whitespaces();
+            match self.advance() {
+                Some(',') => continue,
+                Some('}') => break,
+                _ => anyhow::bail!("expected `,` or `}}` in argument: {}", self.raw),
+            }
+        }
+
+        Ok(members)
+    }
+
+    fn parse_quoted_string(&mut self) -> Result<String> {
+        let mut buffer = String::new();
+
+        loop {
+            match self.advance() {
+                Some('"') => break,
+                Some('\\') => match self.advance() {
+                    Some(c @ ('"' | '\\')) => buffer.push(c),
+                    Some('n') => buffer.push('\n'),
+                    Some(c) => anyhow::bail!("unknown escape sequence: \\{}", c),
+                    None => anyhow::bail!("unterminated string in argument: {}", self.raw),
+                },
+                Some(c) => buffer.push(c),
+                None => anyhow::bail!("unterminated string in argument: {}", self.raw),
+            }
+        }
+
+        Ok(buffer)
+    }
+
+    fn parse_literal(&mut self) -> String {
+        let start = self.position;
+
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() || matches!(c, ',' | '(' | ')' | '[' | ']' | '{' | '}' | '"') {
+                break;
+            }
+            self.position += c.len_utf8();
+        }
+
+        self.raw[start..self.position].to_owned()
+    }
+
+    fn skip_whitespaces(&mut self) {
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() {
+                self.position += c.len_utf8();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.raw[self.position..].chars().next()
+    }
+
+    fn advance(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+        Some(c)
+    }
+}
+
+fn is_identifier(value: &str) -> bool {
+    value
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn literal(value: &str) -> ArgValue {
+        ArgValue::Literal(value.to_owned())
+    }
+
+    #[test]
+    fn test_parse_literals_and_strings() {
+        assert_eq!(ArgValue::parse(" 0x1234 ").unwrap(), literal("0x1234"));
+        assert_eq!(ArgValue::parse("addr:eth").unwrap(), literal("addr:eth"));
+        assert_eq!(ArgValue::parse("-5").unwrap(), literal("-5"));
+        assert_eq!(
+            ArgValue::parse(r#""say \"hi\"\n""#).unwrap(),
+            ArgValue::String(String::from("say \"hi\"\n"))
+        );
+    }
+
+    #[test]
+    fn test_parse_sequences() {
+        assert_eq!(
+            ArgValue::parse("[1, (2, 3), [ ]]").unwrap(),
+            ArgValue::List(vec![
+                literal("1"),
+                ArgValue::Tuple(vec![literal("2"), literal("3")]),
+                ArgValue::List(vec![]),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_struct_members() {
+        assert_eq!(
+            ArgValue::parse("{ low: 1, high: 2 }").unwrap(),
+            ArgValue::Struct(vec![
+                StructMember {
+                    name: Some(String::from("low")),
+                    value: literal("1"),
+                },
+                StructMember {
+                    name: Some(String::from("high")),
+                    value: literal("2"),
+                },
+            ])
+        );
+
+        // Without whitespace after the colon, schemes are not mistaken for member names
+        assert_eq!(
+            ArgValue::parse("{1, addr:eth}").unwrap(),
+            ArgValue::Struct(vec![
+                StructMember {
+                    name: None,
+                    value: literal("1"),
+                },
+                StructMember {
+                    name: None,
+                    value: literal("addr:eth"),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_variants() {
+        assert_eq!(
+            ArgValue::parse("Some(5)").unwrap(),
+            ArgValue::Variant {
+                name: String::from("Some"),
+                value: Some(Box::new(literal("5"))),
+            }
+        );
+        assert_eq!(
+            ArgValue::parse("Pair(1, 2)").unwrap(),
+            ArgValue::Variant {
+                name: String::from("Pair"),
+                value: Some(Box::new(ArgValue::Tuple(vec![literal("1"), literal("2")]))),
+            }
+        );
+        assert_eq!(
+            ArgValue::parse("Unit()").unwrap(),
+            ArgValue::Variant {
+                name: String::from("Unit"),
+                value: Some(Box::new(ArgValue::Tuple(vec![]))),
+            }
+        );
+
+        // Variants without data can't be told apart from literals until encoded
+        assert_eq!(ArgValue::parse("None").unwrap(), literal("None"));
+    }
+
+    #[test]
+    fn test_parse_invalid_values() {
+        for raw in ["", "[1, 2", "(1 2)", "1 2", r#""abc"#, r#""\x""#, "{ a: 1"] {
+            assert!(ArgValue::parse(raw).is_err(), "{}", raw);
+        }
+    }
+}
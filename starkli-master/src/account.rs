@@ -16,9 +16,13 @@ use starknet::{
     signers::{LocalWallet, SigningKey},
 };
 
-use crate::signer::{AnySigner, SignerArgs, SignerResolutionTask};
+use crate::{
+    profile::ProfileDefaults,
+    signer::{AnySigner, SignerArgs, SignerResolutionTask},
+};
 
 const BRAAVOS_SIGNER_TYPE_STARK: FieldElement = FieldElement::ONE;
+const BRAAVOS_SIGNER_TYPE_SECP256R1: FieldElement = FieldElement::TWO;
 
 pub const KNOWN_ACCOUNT_CLASSES: [KnownAccountClass; 8] = [
     KnownAccountClass {
@@ -131,11 +135,29 @@ pub struct AccountArgs {
     #[clap(
         long,
         env = "STARKNET_ACCOUNT",
-        help = "Path to account config JSON file"
+        help = "Path to account config JSON file. Falls back to the default account of the active \
+        profile"
     )]
-    account: String,
+    account: Option<String>,
     #[clap(flatten)]
     signer: SignerArgs,
+    #[clap(
+        long,
+        help = "Path to keystore JSON file of the Argent account guardian, for co-signing \
+        transactions along with the owner"
+    )]
+    guardian_keystore: Option<String>,
+    #[clap(
+        long,
+        conflicts_with = "guardian_keystore",
+        help = "Private key of the Argent account guardian in hex, for co-signing transactions \
+        along with the owner (WARNING: insecure)"
+    )]
+    guardian_private_key: Option<String>,
+    /// Whether transactions are signed by a single signer even if the account has a guardian, as
+    /// with Argent escapes.
+    #[clap(skip)]
+    single_signer: bool,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -228,7 +250,7 @@ pub enum BraavosMultisigConfig {
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum BraavosSigner {
     Stark(BraavosStarkSigner),
-    // TODO: add secp256r1
+    Secp256r1(BraavosSecp256r1Signer),
 }
 
 #[serde_as]
@@ -238,6 +260,19 @@ pub struct BraavosStarkSigner {
     pub public_key: FieldElement,
 }
 
+/// Hardware-backed signer on the secp256r1 curve. Public key coordinates are `u256` values stored as
+/// their low and high halves.
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+pub struct BraavosSecp256r1Signer {
+    /// Index of the signer in the account contract, which identifies the signer in signatures.
+    pub id: u64,
+    #[serde_as(as = "[UfeHex; 2]")]
+    pub public_key_x: [FieldElement; 2],
+    #[serde_as(as = "[UfeHex; 2]")]
+    pub public_key_y: [FieldElement; 2],
+}
+
 #[serde_as]
 #[derive(Serialize, Deserialize)]
 pub struct UndeployedStatus {
@@ -264,6 +299,38 @@ pub enum DeploymentContext {
     Braavos(BraavosDeploymentContext),
 }
 
+/// Account resolved from command line options and the account config file.
+pub struct ResolvedAccount {
+    pub address: FieldElement,
+    pub chain_id: FieldElement,
+    pub encoding: ExecutionEncoding,
+    pub signer: AnySigner,
+    /// Guardian co-signing transactions along with the owner.
+    pub guardian: Option<AnySigner>,
+}
+
+/// Account resolved without its signer, which is enough for building transactions to be signed
+/// elsewhere. The signer is only resolved (possibly prompting for a keystore password) when calling
+/// [resolve_signer](Self::resolve_signer).
+pub struct UnsignedAccount {
+    pub address: FieldElement,
+    pub chain_id: FieldElement,
+    pub encoding: ExecutionEncoding,
+    signer: PendingSigner,
+    guardian: Option<SignerResolutionTask>,
+    /// Whether the account has a guardian that must co-sign transactions.
+    guardian_required: bool,
+    /// Number of signatures required when multisig is enabled on the account.
+    multisig: Option<usize>,
+    /// Whether a hardware signer, which Starkli can't sign with, takes over from the seed signer.
+    hardware_signer: bool,
+}
+
+enum PendingSigner {
+    Builtin(FieldElement),
+    Task(SignerResolutionTask),
+}
+
 #[serde_as]
 #[derive(Serialize, Deserialize)]
 pub struct BraavosDeploymentContext {
@@ -272,13 +339,64 @@ pub struct BraavosDeploymentContext {
 }
 
 impl AccountArgs {
-    pub async fn into_account<P>(self, provider: P) -> Result<SingleOwnerAccount<P, AnySigner>>
+    pub fn new(account: String, signer: SignerArgs) -> Self {
+        Self {
+            account: Some(account),
+            signer,
+            guardian_keystore: None,
+            guardian_private_key: None,
+            single_signer: false,
+        }
+    }
+
+    /// Skips requiring the guardian signer for accounts with a guardian, for transactions signed by
+    /// either the owner or the guardian alone.
+    pub fn single_signer(self) -> Self {
+        Self {
+            single_signer: true,
+            ..self
+        }
+    }
+
+    /// Resolves the account without wrapping it into a `SingleOwnerAccount`, for sending
+    /// transactions not supported by `starknet-accounts`.
+    pub async fn resolve<P>(self, provider: &P) -> Result<ResolvedAccount>
+    where
+        P: Provider + Send + Sync,
+    {
+        self.resolve_unsigned(provider).await?.resolve_signer()
+    }
+
+    pub async fn resolve_unsigned<P>(self, provider: &P) -> Result<UnsignedAccount>
     where
         P: Provider + Send + Sync,
     {
+        let account = match self.account {
+            Some(account) => account,
+            None => ProfileDefaults::load()?.account.ok_or_else(|| {
+                anyhow::anyhow!(
+                    "no account specified. Use the --account option, the STARKNET_ACCOUNT \
+                    environment variable, or set a default account in the active profile"
+                )
+            })?,
+        };
+
         let signer = self.signer.into_task()?;
 
-        let mut account = if let Some(builtin_account) = find_builtin_account(&self.account) {
+        // Environment variables and profile defaults are for owners, so guardian options are only
+        // considered when supplied explicitly.
+        let guardian = match (self.guardian_keystore, self.guardian_private_key) {
+            (None, None) => None,
+            (keystore, private_key) => {
+                Some(SignerArgs::new(keystore, private_key, None).into_task()?)
+            }
+        };
+
+        if let Some(builtin_account) = find_builtin_account(&account) {
+            if guardian.is_some() {
+                anyhow::bail!("built-in accounts do not have guardians");
+            }
+
             if matches!(signer, SignerResolutionTask::Strong(_)) {
                 // The user is supplying a signer explicitly when using a built-in account. This
                 // might be legitimate if the built-in account key has been modified, but it's more
@@ -292,124 +410,208 @@ impl AccountArgs {
 
             let chain_id = provider.chain_id().await?;
 
-            SingleOwnerAccount::new(
-                provider,
-                AnySigner::LocalWallet(LocalWallet::from_signing_key(
-                    SigningKey::from_secret_scalar(builtin_account.private_key),
-                )),
-                builtin_account.address,
+            Ok(UnsignedAccount {
+                address: builtin_account.address,
                 chain_id,
                 // All built-in accounts are now on Cairo 1
-                ExecutionEncoding::New,
-            )
+                encoding: ExecutionEncoding::New,
+                signer: PendingSigner::Builtin(builtin_account.private_key),
+                guardian: None,
+                guardian_required: false,
+                multisig: None,
+                hardware_signer: false,
+            })
         } else {
-            let signer = signer.resolve()?;
-            let account = PathBuf::from(shellexpand::tilde(&self.account).into_owned());
+            let account = PathBuf::from(shellexpand::tilde(&account).into_owned());
 
             if !account.exists() {
                 anyhow::bail!("account config file not found");
             }
 
             let account_config: AccountConfig =
-                serde_json::from_reader(&mut std::fs::File::open(&self.account)?)?;
+                serde_json::from_reader(&mut std::fs::File::open(&account)?)?;
 
             let account_address = match account_config.deployment {
                 DeploymentStatus::Undeployed(_) => anyhow::bail!("account not deployed"),
                 DeploymentStatus::Deployed(inner) => inner.address,
             };
 
+            let has_guardian = matches!(
+                &account_config.variant,
+                AccountVariant::Argent(argent) if argent.guardian != FieldElement::ZERO
+            );
+            if guardian.is_some() && !has_guardian {
+                anyhow::bail!("the account does not have a guardian");
+            }
+
+            let multisig = match &account_config.variant {
+                AccountVariant::Braavos(BraavosAccountConfig {
+                    multisig: BraavosMultisigConfig::On { num_signers },
+                    ..
+                }) => Some(*num_signers),
+                _ => None,
+            };
+
+            // Braavos accounts only accept signatures from the hardware signer once it's added
+            let hardware_signer = matches!(
+                &account_config.variant,
+                AccountVariant::Braavos(braavos) if braavos
+                    .signers
+                    .iter()
+                    .any(|signer| matches!(signer, BraavosSigner::Secp256r1(_)))
+            );
+
             let chain_id = provider.chain_id().await?;
 
-            SingleOwnerAccount::new(
-                provider,
-                signer,
-                account_address,
+            Ok(UnsignedAccount {
+                address: account_address,
                 chain_id,
-                account_config.variant.execution_encoding(),
-            )
+                encoding: account_config.variant.execution_encoding(),
+                signer: PendingSigner::Task(signer),
+                guardian,
+                guardian_required: has_guardian && !self.single_signer,
+                multisig,
+                hardware_signer,
+            })
+        }
+    }
+}
+
+impl UnsignedAccount {
+    /// Number of signatures verified when validating transactions from the account.
+    pub fn num_signatures(&self) -> usize {
+        match self.multisig {
+            Some(num_signers) => num_signers,
+            None if self.guardian_required => 2,
+            None => 1,
+        }
+    }
+
+    pub fn resolve_signer(self) -> Result<ResolvedAccount> {
+        // A single signer can never produce a valid signature for multisig accounts
+        if let Some(num_signers) = self.multisig {
+            anyhow::bail!(
+                "multisig is enabled on this account and transactions need {} signatures. \
+                Build the transaction with --build-only, and then sign it with each signer using \
+                `starkli tx sign --signer-id`",
+                num_signers
+            );
+        }
+        if self.guardian_required && self.guardian.is_none() {
+            anyhow::bail!(
+                "the account has a guardian, which must co-sign transactions. Use \
+                --guardian-keystore or --guardian-private-key"
+            );
+        }
+        if self.hardware_signer {
+            anyhow::bail!(
+                "transactions from this account must be signed by its hardware signer. Build the \
+                transaction with --build-only, and then attach the hardware signature using \
+                `starkli tx sign --signer-id <ID> --raw-signature`"
+            );
+        }
+
+        let signer = match self.signer {
+            PendingSigner::Builtin(private_key) => AnySigner::LocalWallet(
+                LocalWallet::from_signing_key(SigningKey::from_secret_scalar(private_key)),
+            ),
+            PendingSigner::Task(task) => task.resolve()?,
+        };
+        let guardian = match self.guardian {
+            Some(task) => Some(task.resolve()?),
+            None => None,
         };
 
+        Ok(ResolvedAccount {
+            address: self.address,
+            chain_id: self.chain_id,
+            encoding: self.encoding,
+            signer,
+            guardian,
+        })
+    }
+}
+
+impl ResolvedAccount {
+    pub fn into_single_owner<P>(self, provider: P) -> SingleOwnerAccount<P, AnySigner>
+    where
+        P: Provider + Send + Sync,
+    {
+        let mut account = SingleOwnerAccount::new(
+            provider,
+            self.signer,
+            self.address,
+            self.chain_id,
+            self.encoding,
+        );
         account.set_block_id(BlockId::Tag(BlockTag::Pending));
 
-        Ok(account)
+        account
     }
 }
 
 impl AccountConfig {
     pub fn deploy_account_address(&self) -> Result<FieldElement> {
-        let undeployed_status = match &self.deployment {
-            DeploymentStatus::Undeployed(value) => value,
-            DeploymentStatus::Deployed(_) => {
-                anyhow::bail!("account already deployed");
-            }
-        };
+        let undeployed_status = self.undeployed_status()?;
+
+        Ok(get_contract_address(
+            undeployed_status.salt,
+            undeployed_status.class_hash,
+            &self.deploy_account_calldata()?,
+            FieldElement::ZERO,
+        ))
+    }
+
+    /// Constructor calldata of the `DEPLOY_ACCOUNT` transaction. Together with the class hash and
+    /// salt, this determines the account address.
+    pub fn deploy_account_calldata(&self) -> Result<Vec<FieldElement>> {
+        let undeployed_status = self.undeployed_status()?;
 
         match &self.variant {
-            AccountVariant::OpenZeppelin(oz) => Ok(get_contract_address(
-                undeployed_status.salt,
-                undeployed_status.class_hash,
-                &[oz.public_key],
-                FieldElement::ZERO,
-            )),
+            AccountVariant::OpenZeppelin(oz) => Ok(vec![oz.public_key]),
             AccountVariant::Argent(argent) => match argent.implementation {
                 Some(implementation) => {
                     // Legacy Cairo 0 account deployment
-                    Ok(get_contract_address(
-                        undeployed_status.salt,
-                        undeployed_status.class_hash,
-                        &[
-                            implementation,          // implementation
-                            selector!("initialize"), // selector
-                            FieldElement::TWO,       // calldata_len
-                            argent.owner,            // calldata[0]: signer
-                            argent.guardian,         // calldata[1]: guardian
-                        ],
-                        FieldElement::ZERO,
-                    ))
+                    Ok(vec![
+                        implementation,          // implementation
+                        selector!("initialize"), // selector
+                        FieldElement::TWO,       // calldata_len
+                        argent.owner,            // calldata[0]: signer
+                        argent.guardian,         // calldata[1]: guardian
+                    ])
                 }
                 None => {
                     // Cairo 1 account deployment without using proxy
-                    Ok(get_contract_address(
-                        undeployed_status.salt,
-                        undeployed_status.class_hash,
-                        &[argent.owner, argent.guardian],
-                        FieldElement::ZERO,
-                    ))
+                    Ok(vec![argent.owner, argent.guardian])
                 }
             },
 
             AccountVariant::Braavos(braavos) => {
-                if !matches!(braavos.multisig, BraavosMultisigConfig::Off) {
-                    anyhow::bail!("Braavos accounts cannot be deployed with multisig on");
-                }
-                if braavos.signers.len() != 1 {
-                    anyhow::bail!("Braavos accounts can only be deployed with one seed signer");
-                }
+                // Accounts are always deployed with only the seed signer, with other signers and
+                // multisig added afterwards, so the address only depends on the seed signer.
+                let seed_signer = braavos.seed_signer()?;
 
                 match &undeployed_status.context {
-                    Some(DeploymentContext::Braavos(context)) => {
-                        // Safe to unwrap as we already checked for length
-                        match braavos.signers.first().unwrap() {
-                            BraavosSigner::Stark(stark_signer) => {
-                                Ok(get_contract_address(
-                                    undeployed_status.salt,
-                                    undeployed_status.class_hash,
-                                    &[
-                                        context.mock_implementation, // implementation_address
-                                        selector!("initializer"),    // initializer_selector
-                                        FieldElement::ONE,           // calldata_len
-                                        stark_signer.public_key,     // calldata[0]: public_key
-                                    ],
-                                    FieldElement::ZERO,
-                                ))
-                            } // Reject other variants as we add more types
-                        }
-                    }
+                    Some(DeploymentContext::Braavos(context)) => Ok(vec![
+                        context.mock_implementation, // implementation_address
+                        selector!("initializer"),    // initializer_selector
+                        FieldElement::ONE,           // calldata_len
+                        seed_signer.public_key,      // calldata[0]: public_key
+                    ]),
                     _ => Err(anyhow::anyhow!("missing Braavos deployment context")),
                 }
             }
         }
     }
+
+    fn undeployed_status(&self) -> Result<&UndeployedStatus> {
+        match &self.deployment {
+            DeploymentStatus::Undeployed(value) => Ok(value),
+            DeploymentStatus::Deployed(_) => {
+                anyhow::bail!("account already deployed");
+            }
+        }
+    }
 }
 
 impl AccountVariant {
@@ -434,17 +636,37 @@ impl AccountVariant {
     }
 }
 
+impl BraavosAccountConfig {
+    /// Gets the seed signer, which is always the first signer and always a Stark signer.
+    pub fn seed_signer(&self) -> Result<&BraavosStarkSigner> {
+        match self.signers.first() {
+            Some(BraavosSigner::Stark(stark_signer)) => Ok(stark_signer),
+            Some(BraavosSigner::Secp256r1(_)) => {
+                anyhow::bail!("the seed signer of Braavos accounts must be a Stark signer")
+            }
+            None => anyhow::bail!("Braavos account has no signer"),
+        }
+    }
+}
+
 impl BraavosSigner {
-    pub fn decode(raw_signer_model: &[FieldElement]) -> Result<Self> {
+    /// Decodes the signer model stored at index `id` of the account contract.
+    pub fn decode(id: u64, raw_signer_model: &[FieldElement]) -> Result<Self> {
         let raw_signer_type = raw_signer_model
             .get(4)
             .ok_or_else(|| anyhow::anyhow!("unable to read `type` field"))?;
 
+        // Index access is safe as we already checked getting the element after
         if raw_signer_type == &BRAAVOS_SIGNER_TYPE_STARK {
-            // Index access is safe as we already checked getting the element after
             let public_key = raw_signer_model[0];
 
             Ok(Self::Stark(BraavosStarkSigner { public_key }))
+        } else if raw_signer_type == &BRAAVOS_SIGNER_TYPE_SECP256R1 {
+            Ok(Self::Secp256r1(BraavosSecp256r1Signer {
+                id,
+                public_key_x: [raw_signer_model[0], raw_signer_model[1]],
+                public_key_y: [raw_signer_model[2], raw_signer_model[3]],
+            }))
         } else {
             Err(anyhow::anyhow!("unknown signer type: {}", raw_signer_type))
         }
@@ -463,6 +685,11 @@ impl Display for AccountVariantType {
     }
 }
 
+/// Whether `id` refers to a built-in account, which brings its own signer.
+pub fn is_builtin_account(id: &str) -> bool {
+    find_builtin_account(id).is_some()
+}
+
 fn find_builtin_account(id: &str) -> Option<&'static BuiltinAccount> {
     BUILTIN_ACCOUNTS
         .iter()
@@ -0,0 +1,110 @@
+use async_trait::async_trait;
+use starknet::{
+    accounts::{AccountFactory, PreparedAccountDeployment, RawAccountDeployment},
+    core::types::{BlockId, BlockTag, FieldElement},
+    providers::Provider,
+    signers::Signer,
+};
+
+/// Argent X account factory that, unlike the one from `starknet-accounts`, can co-sign deployments
+/// with the guardian, as required by accounts deployed with a guardian.
+pub struct ArgentAccountFactory<S, P> {
+    class_hash: FieldElement,
+    chain_id: FieldElement,
+    owner_public_key: FieldElement,
+    guardian_public_key: FieldElement,
+    signer: S,
+    guardian: Option<S>,
+    provider: P,
+    block_id: BlockId,
+}
+
+impl<S, P> ArgentAccountFactory<S, P>
+where
+    S: Signer,
+{
+    pub async fn new(
+        class_hash: FieldElement,
+        chain_id: FieldElement,
+        guardian_public_key: FieldElement,
+        signer: S,
+        guardian: Option<S>,
+        provider: P,
+    ) -> Result<Self, S::GetPublicKeyError> {
+        let signer_public_key = signer.get_public_key().await?;
+        Ok(Self {
+            class_hash,
+            chain_id,
+            owner_public_key: signer_public_key.scalar(),
+            guardian_public_key,
+            signer,
+            guardian,
+            provider,
+            block_id: BlockId::Tag(BlockTag::Latest),
+        })
+    }
+
+    pub fn set_block_id(&mut self, block_id: BlockId) -> &Self {
+        self.block_id = block_id;
+        self
+    }
+
+    /// Signs a deployment transaction hash with the owner, followed by the guardian if any, which
+    /// can come from either legacy or V3 transactions.
+    pub async fn sign_deployment_hash(
+        &self,
+        tx_hash: FieldElement,
+    ) -> Result<Vec<FieldElement>, S::SignError> {
+        let signature = self.signer.sign_hash(&tx_hash).await?;
+        let mut buffer = vec![signature.r, signature.s];
+
+        if let Some(guardian) = &self.guardian {
+            let signature = guardian.sign_hash(&tx_hash).await?;
+            buffer.push(signature.r);
+            buffer.push(signature.s);
+        }
+
+        Ok(buffer)
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl<S, P> AccountFactory for ArgentAccountFactory<S, P>
+where
+    S: Signer + Sync + Send,
+    P: Provider + Sync + Send,
+{
+    type Provider = P;
+    type SignError = S::SignError;
+
+    fn class_hash(&self) -> FieldElement {
+        self.class_hash
+    }
+
+    fn calldata(&self) -> Vec<FieldElement> {
+        vec![self.owner_public_key, self.guardian_public_key]
+    }
+
+    fn chain_id(&self) -> FieldElement {
+        self.chain_id
+    }
+
+    fn provider(&self) -> &Self::Provider {
+        &self.provider
+    }
+
+    fn block_id(&self) -> BlockId {
+        self.block_id
+    }
+
+    async fn sign_deployment(
+        &self,
+        deployment: &RawAccountDeployment,
+    ) -> Result<Vec<FieldElement>, Self::SignError> {
+        let tx_hash =
+            PreparedAccountDeployment::from_raw(deployment.clone(), self).transaction_hash();
+
+        self.sign_deployment_hash(tx_hash).await
+    }
+}
@@ -50,6 +50,39 @@ where
         self.block_id = block_id;
         self
     }
+
+    /// Signs a deployment transaction hash, which can come from either legacy or V3 transactions.
+    pub async fn sign_deployment_hash(
+        &self,
+        tx_hash: FieldElement,
+    ) -> Result<Vec<FieldElement>, S::SignError> {
+        let sig_hash = compute_hash_on_elements(&[
+            tx_hash,
+            self.impl_class_hash,
+            FieldElement::ZERO,
+            FieldElement::ZERO,
+            FieldElement::ZERO,
+            FieldElement::ZERO,
+            FieldElement::ZERO,
+            FieldElement::ZERO,
+            FieldElement::ZERO,
+        ]);
+
+        let signature = self.signer.sign_hash(&sig_hash).await?;
+
+        Ok(vec![
+            signature.r,
+            signature.s,
+            self.impl_class_hash,
+            FieldElement::ZERO,
+            FieldElement::ZERO,
+            FieldElement::ZERO,
+            FieldElement::ZERO,
+            FieldElement::ZERO,
+            FieldElement::ZERO,
+            FieldElement::ZERO,
+        ])
+    }
 }
 
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
@@ -94,31 +127,6 @@ where
         let tx_hash =
             PreparedAccountDeployment::from_raw(deployment.clone(), self).transaction_hash();
 
-        let sig_hash = compute_hash_on_elements(&[
-            tx_hash,
-            self.impl_class_hash,
-            FieldElement::ZERO,
-            FieldElement::ZERO,
-            FieldElement::ZERO,
-            FieldElement::ZERO,
-            FieldElement::ZERO,
-            FieldElement::ZERO,
-            FieldElement::ZERO,
-        ]);
-
-        let signature = self.signer.sign_hash(&sig_hash).await?;
-
-        Ok(vec![
-            signature.r,
-            signature.s,
-            self.impl_class_hash,
-            FieldElement::ZERO,
-            FieldElement::ZERO,
-            FieldElement::ZERO,
-            FieldElement::ZERO,
-            FieldElement::ZERO,
-            FieldElement::ZERO,
-            FieldElement::ZERO,
-        ])
+        self.sign_deployment_hash(tx_hash).await
     }
 }
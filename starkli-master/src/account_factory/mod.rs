@@ -1,13 +1,19 @@
+use std::sync::Arc;
+
+use anyhow::Result;
 use async_trait::async_trait;
 use starknet::{
-    accounts::{
-        AccountFactory, ArgentAccountFactory, OpenZeppelinAccountFactory, RawAccountDeployment,
-    },
+    accounts::{AccountFactory, OpenZeppelinAccountFactory, RawAccountDeployment},
     core::types::{BlockId, FieldElement},
     providers::Provider,
     signers::Signer,
 };
 
+use crate::{signer::AnySigner, v3::TransactionSigner};
+
+mod argent;
+pub use argent::ArgentAccountFactory;
+
 mod braavos;
 pub use braavos::BraavosAccountFactory;
 
@@ -17,6 +23,12 @@ pub enum AnyAccountFactory<S, P> {
     Braavos(BraavosAccountFactory<S, P>),
 }
 
+/// Signs V3 account deployments, which are not supported by `AccountFactory`.
+pub struct V3DeploymentSigner<'a, P> {
+    pub factory: &'a AnyAccountFactory<Arc<AnySigner>, P>,
+    pub signer: &'a AnySigner,
+}
+
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
 impl<S, P> AccountFactory for AnyAccountFactory<S, P>
@@ -78,3 +90,27 @@ where
         }
     }
 }
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl<'a, P> TransactionSigner for V3DeploymentSigner<'a, P>
+where
+    P: Provider + Sync + Send,
+{
+    async fn sign_transaction_hash(
+        &self,
+        transaction_hash: FieldElement,
+    ) -> Result<Vec<FieldElement>> {
+        match self.factory {
+            AnyAccountFactory::OpenZeppelin(_) => {
+                self.signer.sign_transaction_hash(transaction_hash).await
+            }
+            AnyAccountFactory::Argent(inner) => {
+                Ok(inner.sign_deployment_hash(transaction_hash).await?)
+            }
+            AnyAccountFactory::Braavos(inner) => {
+                Ok(inner.sign_deployment_hash(transaction_hash).await?)
+            }
+        }
+    }
+}
@@ -1,18 +1,29 @@
-// Very temporary implementation of a hard-coded address book
-
-use std::cell::OnceCell;
+use std::{
+    cell::OnceCell,
+    io::{Read, Write},
+    path::PathBuf,
+};
 
 use anyhow::Result;
+use clap::Parser;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
 use starknet::{
-    core::{chain_id, types::FieldElement},
+    core::{
+        chain_id,
+        types::FieldElement,
+        utils::{cairo_short_string_to_felt, parse_cairo_short_string},
+    },
     macros::{felt, short_string},
 };
 
-use crate::chain_id::ChainIdSource;
+use crate::{chain_id::ChainIdSource, profile::Profiles, ProviderArgs};
 
 const CHAIN_ID_KATANA: FieldElement = felt!("0x4b4154414e41");
 
-pub const HARDCODED_ADDRESS_BOOK: [AddressBookEntry; 6] = [
+/// Entries available without any configuration. Entries from the address book file take
+/// precedence over these.
+pub const BUILTIN_ADDRESS_BOOK: [AddressBookEntry; 10] = [
     AddressBookEntry {
         chain_id: chain_id::MAINNET,
         name: "eth",
@@ -38,6 +49,26 @@ pub const HARDCODED_ADDRESS_BOOK: [AddressBookEntry; 6] = [
         name: "eth",
         address: felt!("0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7"),
     },
+    AddressBookEntry {
+        chain_id: chain_id::MAINNET,
+        name: "strk",
+        address: felt!("0x04718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d"),
+    },
+    AddressBookEntry {
+        chain_id: chain_id::TESTNET,
+        name: "strk",
+        address: felt!("0x04718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d"),
+    },
+    AddressBookEntry {
+        chain_id: short_string!("SN_SEPOLIA"),
+        name: "strk",
+        address: felt!("0x04718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d"),
+    },
+    AddressBookEntry {
+        chain_id: short_string!("SN_INTEGRATION_SEPOLIA"),
+        name: "strk",
+        address: felt!("0x04718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d"),
+    },
     AddressBookEntry {
         chain_id: chain_id::MAINNET,
         name: "zklend",
@@ -51,12 +82,133 @@ pub struct AddressBookEntry {
     pub address: FieldElement,
 }
 
+/// User-editable address book stored in `addresses.toml` next to the profiles file. Entries are
+/// grouped by chain ID in its short string representation.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AddressBook {
+    pub chains: IndexMap<String, IndexMap<String, FieldElement>>,
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct ChainIdArgs {
+    #[clap(
+        long,
+        help = "Chain ID as a short string (e.g. SN_SEPOLIA). Fetched from the network if not \
+        specified"
+    )]
+    chain_id: Option<String>,
+    #[clap(flatten)]
+    provider: ProviderArgs,
+}
+
 /// A resolver that lazily fetches chain id to avoid unnecessary network calls.
 pub struct AddressBookResolver<S> {
     chain_id_source: S,
     chain_id: OnceCell<FieldElement>,
 }
 
+impl AddressBook {
+    pub fn load() -> Result<Self> {
+        let path = Self::get_address_book_path()?;
+
+        let loaded_address_book = if path.exists() {
+            let mut file = std::fs::File::open(path)?;
+            let mut buffer = String::new();
+            file.read_to_string(&mut buffer)?;
+
+            toml::from_str(&buffer)
+                .map_err(|err| anyhow::anyhow!("invalid address book file: {}", err))?
+        } else {
+            Self::default()
+        };
+
+        Ok(loaded_address_book)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let serialized = toml::to_string_pretty(self)?;
+
+        let config_folder = Profiles::get_config_folder()?;
+        if !config_folder.exists() {
+            std::fs::create_dir_all(config_folder)?;
+        }
+
+        let path = Self::get_address_book_path()?;
+        let mut file = std::fs::File::create(path)?;
+
+        file.write_all(serialized.as_bytes())?;
+
+        Ok(())
+    }
+
+    /// Looks up an entry from the address book file, falling back to built-in entries.
+    pub fn resolve(&self, chain_id: FieldElement, name: &str) -> Option<FieldElement> {
+        let user_entry = parse_cairo_short_string(&chain_id)
+            .ok()
+            .and_then(|chain_id| self.chains.get(&chain_id))
+            .and_then(|entries| entries.get(name));
+
+        match user_entry {
+            Some(address) => Some(*address),
+            None => BUILTIN_ADDRESS_BOOK.iter().find_map(|entry| {
+                if entry.chain_id == chain_id && entry.name == name {
+                    Some(entry.address)
+                } else {
+                    None
+                }
+            }),
+        }
+    }
+
+    pub fn validate_name(name: &str) -> Result<()> {
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+        {
+            anyhow::bail!(
+                "invalid address book entry name `{}`: only alphanumeric characters, `-`, `_` \
+                and `.` are allowed",
+                name
+            );
+        }
+
+        // Names that parse as felts would never be resolved from the address book
+        if name.parse::<FieldElement>().is_ok() {
+            anyhow::bail!(
+                "invalid address book entry name `{}`: name must not be a number",
+                name
+            );
+        }
+
+        Ok(())
+    }
+
+    fn get_address_book_path() -> Result<PathBuf> {
+        let mut path = Profiles::get_config_folder()?;
+        path.push("addresses.toml");
+        Ok(path)
+    }
+}
+
+impl ChainIdArgs {
+    /// Resolves the chain ID into its short string representation.
+    pub async fn resolve(self) -> Result<String> {
+        match self.chain_id {
+            Some(chain_id) => {
+                // Makes sure the value is a valid short string
+                cairo_short_string_to_felt(&chain_id)?;
+                Ok(chain_id)
+            }
+            None => {
+                let chain_id = self.provider.into_provider()?.get_chain_id().await?;
+                Ok(parse_cairo_short_string(&chain_id)?)
+            }
+        }
+    }
+}
+
 impl<S> AddressBookResolver<S> {
     pub fn new(chain_id_source: S) -> Self {
         Self {
@@ -85,12 +237,6 @@ where
             }
         };
 
-        Ok(HARDCODED_ADDRESS_BOOK.iter().find_map(|entry| {
-            if entry.chain_id == chain_id && entry.name == name {
-                Some(entry.address)
-            } else {
-                None
-            }
-        }))
+        Ok(AddressBook::load()?.resolve(chain_id, name))
     }
 }
@@ -0,0 +1,119 @@
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use clap::Parser;
+use colored::Colorize;
+use starknet::{
+    core::types::{BlockId, MaybePendingBlockWithTxHashes},
+    providers::Provider,
+};
+
+use crate::{error::provider_error_mapper, utils::parse_block_id};
+
+#[derive(Debug, Clone, Parser)]
+pub struct BlockArgs {
+    #[clap(
+        long,
+        default_value = "pending",
+        help = "Block number, hash, tag (latest/pending), or @timestamp to query against"
+    )]
+    block: String,
+}
+
+impl BlockArgs {
+    /// Resolves the block to query against. Timestamps (`@2024-03-01T00:00Z` or `@1709251200`)
+    /// are resolved to the last block produced at or before that time.
+    pub async fn resolve<P>(&self, provider: &P) -> Result<BlockId>
+    where
+        P: Provider,
+    {
+        match self.block.strip_prefix('@') {
+            Some(timestamp) => {
+                let timestamp = parse_timestamp(timestamp)?;
+                let block_number = find_block_at_timestamp(provider, timestamp).await?;
+
+                eprintln!(
+                    "Block at or before timestamp {} resolved to: {}",
+                    format!("{}", timestamp).bright_yellow(),
+                    format!("{}", block_number).bright_yellow()
+                );
+
+                Ok(BlockId::Number(block_number))
+            }
+            None => parse_block_id(&self.block),
+        }
+    }
+}
+
+/// Parses Unix timestamps, RFC 3339 date-times, as well as date-times with seconds or the whole
+/// time omitted (e.g. `2024-03-01T00:00Z` and `2024-03-01`), which are assumed to be in UTC.
+fn parse_timestamp(raw: &str) -> Result<u64> {
+    if let Ok(timestamp) = raw.parse::<u64>() {
+        return Ok(timestamp);
+    }
+
+    let date_time = if let Ok(date_time) = DateTime::parse_from_rfc3339(raw) {
+        date_time.with_timezone(&Utc)
+    } else {
+        let naive = raw.strip_suffix('Z').unwrap_or(raw);
+        let naive = NaiveDateTime::parse_from_str(naive, "%Y-%m-%dT%H:%M:%S")
+            .or_else(|_| NaiveDateTime::parse_from_str(naive, "%Y-%m-%dT%H:%M"))
+            .or_else(|_| {
+                NaiveDate::parse_from_str(naive, "%Y-%m-%d")
+                    .map(|date| date.and_hms_opt(0, 0, 0).unwrap())
+            })
+            .map_err(|_| anyhow::anyhow!("invalid timestamp: {}", raw))?;
+        Utc.from_utc_datetime(&naive)
+    };
+
+    date_time
+        .timestamp()
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("timestamp out of range: {}", raw))
+}
+
+/// Binary searches block headers for the last block with a timestamp not later than `timestamp`.
+async fn find_block_at_timestamp<P>(provider: &P, timestamp: u64) -> Result<u64>
+where
+    P: Provider,
+{
+    let latest = provider
+        .block_number()
+        .await
+        .map_err(provider_error_mapper)?;
+
+    if get_block_timestamp(provider, 0).await? > timestamp {
+        anyhow::bail!("no block exists at or before timestamp {}", timestamp);
+    }
+    if get_block_timestamp(provider, latest).await? <= timestamp {
+        return Ok(latest);
+    }
+
+    // Invariant: block `low` is at or before the timestamp, and block `high` is after it
+    let mut low = 0;
+    let mut high = latest;
+    while high - low > 1 {
+        let mid = low + (high - low) / 2;
+        if get_block_timestamp(provider, mid).await? <= timestamp {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    Ok(low)
+}
+
+async fn get_block_timestamp<P>(provider: &P, block_number: u64) -> Result<u64>
+where
+    P: Provider,
+{
+    let block = provider
+        .get_block_with_tx_hashes(BlockId::Number(block_number))
+        .await
+        .map_err(provider_error_mapper)?;
+
+    Ok(match block {
+        MaybePendingBlockWithTxHashes::Block(block) => block.timestamp,
+        MaybePendingBlockWithTxHashes::PendingBlock(block) => block.timestamp,
+    })
+}
@@ -0,0 +1,191 @@
+use std::{io::Write, path::Path};
+
+use anyhow::Result;
+use colored::Colorize;
+use indexmap::IndexMap;
+use serde::Deserialize;
+use starknet::{accounts::Call, core::types::FieldElement};
+
+/// A batch of contract calls loaded from a TOML or JSON file. `${name}` placeholders in targets,
+/// function names, and arguments are substituted with values from the `variables` table.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CallsFile {
+    #[serde(default)]
+    variables: IndexMap<String, String>,
+    calls: Vec<RawCallEntry>,
+}
+
+/// A call entry with all variables substituted.
+#[derive(Debug)]
+pub struct CallsFileEntry {
+    /// Contract address or address book entry name.
+    pub to: String,
+    pub function: String,
+    /// Typed arguments to be encoded against the contract ABI.
+    pub args: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawCallEntry {
+    to: String,
+    function: String,
+    #[serde(default)]
+    args: Vec<RawCallArg>,
+}
+
+/// Plain numbers are accepted for convenience so that they don't have to be quoted.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawCallArg {
+    String(String),
+    Integer(u64),
+}
+
+impl CallsFile {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+
+        let is_json = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+        let calls_file = if is_json {
+            serde_json::from_str(&content)
+                .map_err(|err| anyhow::anyhow!("invalid calls file: {}", err))?
+        } else {
+            toml::from_str(&content)
+                .map_err(|err| anyhow::anyhow!("invalid calls file: {}", err))?
+        };
+
+        Ok(calls_file)
+    }
+
+    /// Substitutes variables in all call entries. Values in `overrides` take precedence over the
+    /// ones defined in the file.
+    pub fn into_entries(mut self, overrides: &[(String, String)]) -> Result<Vec<CallsFileEntry>> {
+        for (name, value) in overrides.iter() {
+            self.variables.insert(name.to_owned(), value.to_owned());
+        }
+
+        let variables = &self.variables;
+        self.calls
+            .into_iter()
+            .enumerate()
+            .map(|(ind, entry)| {
+                let substitute = |raw: &str| {
+                    substitute_variables(raw, variables)
+                        .map_err(|err| anyhow::anyhow!("call #{}: {}", ind + 1, err))
+                };
+
+                Ok(CallsFileEntry {
+                    to: substitute(&entry.to)?,
+                    function: substitute(&entry.function)?,
+                    args: entry
+                        .args
+                        .iter()
+                        .map(|arg| match arg {
+                            RawCallArg::String(arg) => substitute(arg),
+                            RawCallArg::Integer(arg) => Ok(arg.to_string()),
+                        })
+                        .collect::<Result<Vec<_>>>()?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Parses a `name=value` pair from the command line.
+pub fn parse_variable(raw: &str) -> Result<(String, String)> {
+    match raw.split_once('=') {
+        Some((name, value)) if !name.is_empty() => Ok((name.to_owned(), value.to_owned())),
+        _ => Err(anyhow::anyhow!("expected `name=value` but got `{}`", raw)),
+    }
+}
+
+/// Prints the calls to be sent as a table to stderr.
+pub fn print_summary(entries: &[CallsFileEntry], calls: &[Call]) {
+    let rows = entries
+        .iter()
+        .zip(calls.iter())
+        .enumerate()
+        .map(|(ind, (entry, call))| {
+            let contract = if entry.to.parse::<FieldElement>().is_ok() {
+                format!("{:#064x}", call.to)
+            } else {
+                format!("{:#064x} ({})", call.to, entry.to)
+            };
+
+            [
+                (ind + 1).to_string(),
+                contract,
+                entry.function.clone(),
+                entry.args.join(", "),
+            ]
+        })
+        .collect::<Vec<_>>();
+
+    let headers = ["#", "Contract", "Function", "Arguments"];
+    let mut widths = headers.map(|header| header.len());
+    for row in rows.iter() {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let header_line = headers
+        .iter()
+        .zip(widths.iter())
+        .map(|(header, width)| format!("{:width$}", header, width = width))
+        .collect::<Vec<_>>()
+        .join("  ");
+    eprintln!("{}", header_line.trim_end().bright_green());
+
+    for row in rows.iter() {
+        eprintln!(
+            "{:w0$}  {}  {}  {}",
+            row[0],
+            format!("{:w1$}", row[1], w1 = widths[1]).bright_yellow(),
+            format!("{:w2$}", row[2], w2 = widths[2]),
+            row[3],
+            w0 = widths[0],
+        );
+    }
+}
+
+/// Asks the user to confirm sending the calls. Returns `false` if the user declines.
+pub fn confirm_calls(count: usize) -> Result<bool> {
+    eprint!("Send {} call(s) in a single transaction? [y/N] ", count);
+    std::io::stderr().flush()?;
+
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+/// Substitutes `${name}` placeholders in `raw`. Undefined variables are errors.
+pub fn substitute_variables(raw: &str, variables: &IndexMap<String, String>) -> Result<String> {
+    let mut result = String::with_capacity(raw.len());
+    let mut remaining = raw;
+
+    while let Some(start) = remaining.find("${") {
+        result.push_str(&remaining[..start]);
+
+        let after_start = &remaining[(start + 2)..];
+        let end = after_start
+            .find('}')
+            .ok_or_else(|| anyhow::anyhow!("unterminated variable in `{}`", raw))?;
+
+        let name = &after_start[..end];
+        let value = variables
+            .get(name)
+            .ok_or_else(|| anyhow::anyhow!("undefined variable `{}`", name))?;
+        result.push_str(value);
+
+        remaining = &after_start[(end + 1)..];
+    }
+    result.push_str(remaining);
+
+    Ok(result)
+}
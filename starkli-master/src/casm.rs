@@ -12,9 +12,10 @@ use crate::{
     compiler::{BuiltInCompiler, CompilerBinary, CompilerVersion},
     network::{Network, NetworkSource},
     path::ExpandedPathbufParser,
+    profile::ProfileDefaults,
 };
 
-#[derive(Debug, Clone, Parser)]
+#[derive(Debug, Clone, Default, Parser)]
 pub struct CasmArgs {
     #[clap(long, help = "Statically-linked Sierra compiler version")]
     compiler_version: Option<CompilerVersion>,
@@ -43,6 +44,15 @@ pub enum CasmHashSource {
 }
 
 impl CasmArgs {
+    pub fn from_casm_file(casm_file: PathBuf) -> Self {
+        Self {
+            compiler_version: None,
+            compiler_path: None,
+            casm_file: Some(casm_file),
+            casm_hash: None,
+        }
+    }
+
     pub async fn into_casm_hash_source<N>(self, network_source: N) -> Result<CasmHashSource>
     where
         N: NetworkSource,
@@ -63,6 +73,16 @@ impl CasmArgs {
             (None, None, None, Some(casm_hash)) => Ok(CasmHashSource::Hash(casm_hash.parse()?)),
             // Tries to detect compiler version if nothing provided
             (None, None, None, None) => {
+                if let Some(profile_version) = ProfileDefaults::load()?.compiler_version {
+                    eprintln!(
+                        "Using the compiler version from the active profile: {}. \
+                        Use the --compiler-version flag to choose a different version.",
+                        format!("{}", profile_version).bright_yellow()
+                    );
+
+                    return Ok(CasmHashSource::BuiltInCompiler(profile_version.into()));
+                }
+
                 eprintln!(
                     "Sierra compiler version not specified. \
                     Attempting to automatically decide version to use..."
@@ -7,6 +7,9 @@ use starknet::core::{
 
 use crate::{address_book::AddressBookResolver, chain_id::ChainIdSource};
 
+/// Storage offsets are stored as `u8` in Cairo.
+const MAX_STORAGE_OFFSET: u64 = 256;
+
 pub struct FeltDecoder<S> {
     address_book_resolver: AddressBookResolver<S>,
 }
@@ -78,6 +81,27 @@ where
         &self,
         raw: &str,
         fallback_option: FallbackOption,
+    ) -> Result<Vec<FieldElement>> {
+        if let Some(storage) = raw.strip_prefix("storage:") {
+            return Ok(vec![self.resolve_storage_address(storage).await?]);
+        }
+
+        // Anything with a scheme before the first key is not a storage path
+        let is_storage_fallback = matches!(fallback_option, FallbackOption::Storage)
+            && raw.parse::<FieldElement>().is_err()
+            && !raw.split('[').next().unwrap_or_default().contains(':');
+        if is_storage_fallback {
+            return Ok(vec![self.resolve_storage_address(raw).await?]);
+        }
+
+        self.decode_value(raw, fallback_option).await
+    }
+
+    /// Decodes everything but storage addresses, as storage keys are themselves decoded with this.
+    async fn decode_value(
+        &self,
+        raw: &str,
+        fallback_option: FallbackOption,
     ) -> Result<Vec<FieldElement>> {
         if let Some(addr_name) = raw.strip_prefix("addr:") {
             Ok(vec![self.resolve_addr(addr_name).await?])
@@ -142,11 +166,6 @@ where
             Ok(vec![cairo_short_string_to_felt(short_string)?])
         } else if let Some(selector) = raw.strip_prefix("selector:") {
             Ok(vec![get_selector_from_name(selector)?])
-        } else if let Some(storage) = raw.strip_prefix("storage:") {
-            if storage.contains('[') || storage.contains(']') {
-                anyhow::bail!("cannot resolve storage address: maps not supported yet")
-            }
-            Ok(vec![get_storage_var_address(storage, &[])?])
         } else {
             match raw.parse::<FieldElement>() {
                 Ok(value) => Ok(vec![value]),
@@ -156,18 +175,60 @@ where
                         Err(_) => Err(err.into()),
                     },
                     FallbackOption::Selector => Ok(vec![get_selector_from_name(raw)?]),
-                    FallbackOption::Storage => {
-                        if raw.contains('[') || raw.contains(']') {
-                            anyhow::bail!("cannot resolve storage address: maps not supported yet")
-                        }
-                        Ok(vec![get_storage_var_address(raw, &[])?])
-                    }
-                    FallbackOption::None => Err(err.into()),
+                    // Already handled before getting here
+                    FallbackOption::Storage | FallbackOption::None => Err(err.into()),
                 },
             }
         }
     }
 
+    /// Resolves storage paths in the form of `name[key1][key2]+offset`, where keys and the offset
+    /// are optional. Keys can use any scheme and are hashed into the base address of the variable
+    /// with chained Pedersen hashes, with multi-element keys like `u256` values contributing all
+    /// of their elements. The offset selects a slot after the base address, such as a struct member.
+    async fn resolve_storage_address(&self, raw: &str) -> Result<FieldElement> {
+        // Offsets can only appear after all keys
+        let (path, offset) = match raw
+            .rsplit_once('+')
+            .filter(|(_, offset)| !offset.contains(']'))
+        {
+            Some((path, offset)) => {
+                let offset: FieldElement = offset
+                    .parse()
+                    .map_err(|_| anyhow::anyhow!("invalid storage offset: {}", offset))?;
+                if offset >= FieldElement::from(MAX_STORAGE_OFFSET) {
+                    anyhow::bail!("storage offset must be less than {}", MAX_STORAGE_OFFSET);
+                }
+                (path, offset)
+            }
+            None => (raw, FieldElement::ZERO),
+        };
+
+        let (name, mut remaining) = match path.find('[') {
+            Some(ind) => (&path[..ind], &path[ind..]),
+            None => (path, ""),
+        };
+        if name.is_empty() {
+            anyhow::bail!("missing storage variable name: {}", raw);
+        }
+
+        let mut keys = vec![];
+        while !remaining.is_empty() {
+            let (key, rest) = remaining
+                .strip_prefix('[')
+                .and_then(|remaining| remaining.split_once(']'))
+                .ok_or_else(|| anyhow::anyhow!("invalid storage path: {}", raw))?;
+            if key.is_empty() {
+                anyhow::bail!("empty storage key: {}", raw);
+            }
+
+            keys.append(&mut self.decode_value(key, FallbackOption::Address).await?);
+            remaining = rest;
+        }
+
+        Ok(get_storage_var_address(name, &keys)? + offset)
+    }
+
     async fn resolve_addr(&self, name: &str) -> Result<FieldElement> {
         self.address_book_resolver
             .resolve_name(name)
@@ -175,3 +236,85 @@ where
             .ok_or_else(|| anyhow::anyhow!("address book entry not found for \"{}\"", name))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use starknet::macros::felt;
+
+    use super::*;
+
+    /// Chain ID source for tests that never touch the address book.
+    struct NoChainId;
+
+    #[async_trait]
+    impl ChainIdSource for NoChainId {
+        async fn get_chain_id(&self) -> Result<FieldElement> {
+            anyhow::bail!("chain id not available")
+        }
+    }
+
+    fn decoder() -> FeltDecoder<NoChainId> {
+        FeltDecoder::new(AddressBookResolver::new(NoChainId))
+    }
+
+    #[tokio::test]
+    async fn test_resolve_storage_address() {
+        let decoder = decoder();
+
+        assert_eq!(
+            decoder
+                .resolve_storage_address("ERC20_total_supply")
+                .await
+                .unwrap(),
+            felt!("0x110e2f729c9c2b988559994a3daccd838cf52faf88e18101373e67dd061455a")
+        );
+        assert_eq!(
+            decoder
+                .resolve_storage_address("ERC20_balances[0x1234]")
+                .await
+                .unwrap(),
+            felt!("0x4fc7b23d1ef6e4f099416be09d83699fe35126d2a42325636e93745a36ce3cb")
+        );
+        assert_eq!(
+            decoder
+                .resolve_storage_address("ERC20_balances[0x1234]+1")
+                .await
+                .unwrap(),
+            felt!("0x4fc7b23d1ef6e4f099416be09d83699fe35126d2a42325636e93745a36ce3cc")
+        );
+        assert_eq!(
+            decoder
+                .resolve_storage_address("ERC20_allowances[0x1234][0x5678]")
+                .await
+                .unwrap(),
+            felt!("0x61b42aa29464f85d526b4c2f6ece78ab1f3eb50199544bb906b795c42f0b8f8")
+        );
+        assert_eq!(
+            decoder
+                .resolve_storage_address("balances[u256:100]")
+                .await
+                .unwrap(),
+            felt!("0x3b5f24b8d4235806f6f4f2481411825f8f361e4100fa6b35f2481881bee8840")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_resolve_invalid_storage_address() {
+        let decoder = decoder();
+
+        for raw in [
+            "[0x1234]",
+            "ERC20_balances[]",
+            "ERC20_balances[0x1234",
+            "ERC20_balances[0x1234]+256",
+            "ERC20_balances[0x1234]+abc",
+        ] {
+            assert!(
+                decoder.resolve_storage_address(raw).await.is_err(),
+                "{} should be rejected",
+                raw
+            );
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Result;
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use starknet::core::{serde::unsigned_field_element::UfeHex, types::FieldElement};
+
+/// A deployment manifest for `starkli apply`, listing classes to declare, contracts to deploy, and
+/// calls to make, in that order. `${name}` placeholders are substituted with values from the
+/// `variables` table, as well as outputs of earlier steps:
+///
+/// - `${account}`: address of the account sending the transactions;
+/// - `${classes.<name>}`: hash of a class declared by the manifest;
+/// - `${contracts.<name>}`: address of a contract deployed by the manifest.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeployManifest {
+    #[serde(default)]
+    pub variables: IndexMap<String, String>,
+    #[serde(default)]
+    pub classes: IndexMap<String, ManifestClass>,
+    #[serde(default)]
+    pub contracts: IndexMap<String, ManifestContract>,
+    #[serde(default)]
+    pub calls: IndexMap<String, ManifestCall>,
+}
+
+/// A Sierra class to declare, either from an artifact file or from a contract built by Scarb.
+/// Relative paths are resolved against the folder of the manifest.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ManifestClass {
+    pub file: Option<PathBuf>,
+    pub casm_file: Option<PathBuf>,
+    /// Name or module path of a Scarb contract.
+    pub contract: Option<String>,
+    /// Path to `Scarb.toml`. Defaults to the one next to the manifest.
+    pub scarb: Option<PathBuf>,
+    pub scarb_profile: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ManifestContract {
+    /// Name of a class in the manifest, or a class hash.
+    pub class: String,
+    /// Required so that the deployed address is deterministic, which is how already deployed
+    /// contracts are detected.
+    pub salt: String,
+    #[serde(default = "default_unique")]
+    pub unique: bool,
+    /// Typed constructor arguments encoded against the class ABI.
+    #[serde(default)]
+    pub constructor_args: Vec<ManifestArg>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ManifestCall {
+    /// Contract address, address book entry name, or `${contracts.<name>}`.
+    pub to: String,
+    pub function: String,
+    /// Typed arguments encoded against the contract ABI.
+    #[serde(default)]
+    pub args: Vec<ManifestArg>,
+}
+
+/// Plain numbers are accepted for convenience so that they don't have to be quoted.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum ManifestArg {
+    String(String),
+    Integer(u64),
+}
+
+/// Results of applying manifests, keyed by chain ID, so that the same state file can be used
+/// across networks.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DeployState {
+    #[serde(flatten)]
+    pub chains: IndexMap<String, ChainDeployState>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ChainDeployState {
+    #[serde(default)]
+    pub classes: IndexMap<String, DeclaredClassState>,
+    #[serde(default)]
+    pub contracts: IndexMap<String, DeployedContractState>,
+    #[serde(default)]
+    pub calls: IndexMap<String, SentCallState>,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeclaredClassState {
+    #[serde_as(as = "UfeHex")]
+    pub class_hash: FieldElement,
+    /// `None` when the class had already been declared by someone else.
+    #[serde_as(as = "Option<UfeHex>")]
+    pub transaction_hash: Option<FieldElement>,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DeployedContractState {
+    #[serde_as(as = "UfeHex")]
+    pub address: FieldElement,
+    #[serde_as(as = "UfeHex")]
+    pub class_hash: FieldElement,
+    /// `None` when the contract had already been deployed before the state was recorded.
+    #[serde_as(as = "Option<UfeHex>")]
+    pub transaction_hash: Option<FieldElement>,
+}
+
+#[serde_as]
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SentCallState {
+    #[serde_as(as = "UfeHex")]
+    pub transaction_hash: FieldElement,
+}
+
+impl DeployManifest {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        toml::from_str(&content).map_err(|err| anyhow::anyhow!("invalid deploy manifest: {}", err))
+    }
+}
+
+impl ManifestArg {
+    pub fn to_raw(&self) -> String {
+        match self {
+            Self::String(arg) => arg.to_owned(),
+            Self::Integer(arg) => arg.to_string(),
+        }
+    }
+}
+
+impl DeployState {
+    /// Loads the state file, or starts with an empty state if the file doesn't exist yet.
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        serde_json::from_reader(std::fs::File::open(path)?)
+            .map_err(|err| anyhow::anyhow!("invalid deploy state file: {}", err))
+    }
+
+    /// Gets the state of a chain, identified by its chain ID in text form.
+    pub fn chain(&mut self, chain_id: &str) -> &mut ChainDeployState {
+        self.chains.entry(chain_id.to_owned()).or_default()
+    }
+
+    /// Writes the state file. This is done after every step so that progress is not lost when a
+    /// later step fails.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        // Never truncates the existing state file in place, so that it survives interruptions
+        let mut temp_file_name = path
+            .file_name()
+            .ok_or_else(|| anyhow::anyhow!("unable to determine file name"))?
+            .to_owned();
+        temp_file_name.push(".tmp");
+        let mut temp_path = path.to_owned();
+        temp_path.set_file_name(temp_file_name);
+
+        let mut temp_file = std::fs::File::create(&temp_path)?;
+        serde_json::to_writer_pretty(&mut temp_file, self)?;
+        temp_file.write_all(b"\n")?;
+        std::fs::rename(temp_path, path)?;
+
+        Ok(())
+    }
+}
+
+fn default_unique() -> bool {
+    true
+}
@@ -0,0 +1,203 @@
+//! Helpers for querying ERC20 token contracts, supporting both the legacy camelCase and the newer
+//! snake_case interfaces.
+
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use num_bigint::{BigUint, ToBigInt};
+use starknet::{
+    core::{
+        types::{BlockId, FieldElement, FunctionCall},
+        utils::parse_cairo_short_string,
+    },
+    macros::selector,
+    providers::Provider,
+};
+
+use crate::{abi::FeltReader, error::provider_error_mapper};
+
+/// The default ETH address: 0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7.
+pub const DEFAULT_ETH_ADDRESS: FieldElement = FieldElement::from_mont([
+    4380532846569209554,
+    17839402928228694863,
+    17240401758547432026,
+    418961398025637529,
+]);
+
+/// The default STRK address: 0x04718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d.
+pub const DEFAULT_STRK_ADDRESS: FieldElement = FieldElement::from_mont([
+    16432072983745651214,
+    1325769094487018516,
+    5134018303144032807,
+    468300854463065062,
+]);
+
+/// Gets the raw balance of `account`, trying `balanceOf` first and then `balance_of`.
+pub async fn get_balance<P>(
+    provider: &P,
+    token: FieldElement,
+    account: FieldElement,
+    block_id: BlockId,
+) -> Result<BigUint>
+where
+    P: Provider,
+{
+    let result = call_with_fallback(
+        provider,
+        token,
+        [selector!("balanceOf"), selector!("balance_of")],
+        vec![account],
+        block_id,
+    )
+    .await?;
+
+    parse_u256(&result)
+}
+
+pub async fn get_allowance<P>(
+    provider: &P,
+    token: FieldElement,
+    owner: FieldElement,
+    spender: FieldElement,
+    block_id: BlockId,
+) -> Result<BigUint>
+where
+    P: Provider,
+{
+    let result = provider
+        .call(
+            FunctionCall {
+                contract_address: token,
+                entry_point_selector: selector!("allowance"),
+                calldata: vec![owner, spender],
+            },
+            block_id,
+        )
+        .await
+        .map_err(provider_error_mapper)?;
+
+    parse_u256(&result)
+}
+
+pub async fn get_decimals<P>(provider: &P, token: FieldElement, block_id: BlockId) -> Result<u8>
+where
+    P: Provider,
+{
+    let result = provider
+        .call(
+            FunctionCall {
+                contract_address: token,
+                entry_point_selector: selector!("decimals"),
+                calldata: vec![],
+            },
+            block_id,
+        )
+        .await
+        .map_err(|err| {
+            anyhow::anyhow!(
+                "unable to fetch decimals of token {:#064x}: {}",
+                token,
+                provider_error_mapper(err)
+            )
+        })?;
+
+    match result.as_slice() {
+        [decimals] => u8::try_from(*decimals)
+            .map_err(|_| anyhow::anyhow!("invalid token decimals: {:#x}", decimals)),
+        _ => anyhow::bail!("unexpected decimals result size: {}", result.len()),
+    }
+}
+
+/// Gets the token symbol, which can be either a short string or a `ByteArray` depending on the
+/// Cairo version the token is written in.
+pub async fn get_symbol<P>(provider: &P, token: FieldElement, block_id: BlockId) -> Result<String>
+where
+    P: Provider,
+{
+    let result = provider
+        .call(
+            FunctionCall {
+                contract_address: token,
+                entry_point_selector: selector!("symbol"),
+                calldata: vec![],
+            },
+            block_id,
+        )
+        .await
+        .map_err(provider_error_mapper)?;
+
+    match result.as_slice() {
+        [symbol] => Ok(parse_cairo_short_string(symbol)?),
+        _ => {
+            let mut reader = FeltReader::new(&result);
+            let symbol = reader.read_byte_array()?;
+            reader.finish()?;
+            Ok(symbol)
+        }
+    }
+}
+
+/// Formats a raw token amount in token units.
+pub fn format_amount(raw_amount: &BigUint, decimals: u8) -> String {
+    // `to_bigint()` from `BigUint` always returns `Some`.
+    BigDecimal::new(raw_amount.to_bigint().unwrap(), decimals.into()).to_string()
+}
+
+/// Splits an amount into the low and high parts of a `u256` value.
+pub fn amount_to_u256(amount: FieldElement) -> [FieldElement; 2] {
+    let bytes = amount.to_bytes_be();
+
+    // Each half always fits in a field element
+    [
+        FieldElement::from_byte_slice_be(&bytes[16..]).unwrap(),
+        FieldElement::from_byte_slice_be(&bytes[..16]).unwrap(),
+    ]
+}
+
+/// Calls the first selector that the contract accepts. The error from the first selector is
+/// returned if none works.
+async fn call_with_fallback<P, const N: usize>(
+    provider: &P,
+    contract_address: FieldElement,
+    selectors: [FieldElement; N],
+    calldata: Vec<FieldElement>,
+    block_id: BlockId,
+) -> Result<Vec<FieldElement>>
+where
+    P: Provider,
+{
+    let mut first_err = None;
+
+    for entry_point_selector in selectors.into_iter() {
+        match provider
+            .call(
+                FunctionCall {
+                    contract_address,
+                    entry_point_selector,
+                    calldata: calldata.clone(),
+                },
+                block_id,
+            )
+            .await
+        {
+            Ok(result) => return Ok(result),
+            Err(err) => {
+                if first_err.is_none() {
+                    first_err = Some(provider_error_mapper(err));
+                }
+            }
+        }
+    }
+
+    Err(first_err.unwrap_or_else(|| anyhow::anyhow!("no selector to call")))
+}
+
+fn parse_u256(result: &[FieldElement]) -> Result<BigUint> {
+    if result.len() != 2 {
+        anyhow::bail!("unexpected call result size: {}", result.len());
+    }
+
+    let low = BigUint::from_bytes_be(&result[0].to_bytes_be());
+    let high = BigUint::from_bytes_be(&result[1].to_bytes_be());
+
+    Ok((high << 128) + low)
+}
@@ -1,32 +1,129 @@
+use std::fmt::Display;
+
 use anyhow::Result;
 use bigdecimal::BigDecimal;
-use clap::Parser;
-use starknet::{core::types::FieldElement, macros::felt};
+use clap::{Parser, ValueEnum};
+use colored::Colorize;
+use starknet::{
+    core::types::{FeeEstimate, FieldElement},
+    macros::felt,
+};
+
+use crate::{
+    erc20::{DEFAULT_ETH_ADDRESS, DEFAULT_STRK_ADDRESS},
+    output::{is_json_output, record},
+    profile::ProfileDefaults,
+    utils::{bigdecimal_to_felt, felt_to_u128},
+    v3::V3Fee,
+};
+
+/// Multiplier applied to estimated fees when not configured.
+pub const DEFAULT_FEE_MULTIPLIER: f64 = 1.5;
 
-use crate::utils::bigdecimal_to_felt;
+/// Multiplier applied to the estimated L1 gas price of V3 transactions, as the price can change
+/// before the transaction gets included.
+const GAS_PRICE_MULTIPLIER: f64 = 1.5;
+
+/// Number of decimal places kept when applying fee multipliers.
+const FEE_MULTIPLIER_PRECISION: u64 = 1_000_000;
 
 #[derive(Debug, Clone, Parser)]
 pub struct FeeArgs {
+    #[clap(
+        long,
+        value_enum,
+        default_value_t = FeeToken::Eth,
+        help = "Token for paying transaction fees. Fees in STRK are paid with V3 transactions"
+    )]
+    fee_token: FeeToken,
     #[clap(long, help = "Maximum transaction fee in Ether (18 decimals)")]
     max_fee: Option<BigDecimal>,
     #[clap(long, help = "Maximum transaction fee in Wei")]
     max_fee_raw: Option<FieldElement>,
+    #[clap(
+        long,
+        help = "Maximum amount of L1 gas for V3 transactions. Estimated if not specified"
+    )]
+    l1_gas: Option<u64>,
+    #[clap(
+        long,
+        help = "Maximum price per unit of L1 gas in Fri (10^-18 STRK) for V3 transactions. \
+        Estimated if not specified"
+    )]
+    l1_gas_price: Option<u128>,
+    #[clap(long, help = "Tip in Fri for V3 transactions")]
+    tip: Option<u64>,
     #[clap(
         long,
         help = "Only estimate transaction fee without sending transaction"
     )]
     estimate_only: bool,
+    #[clap(
+        long,
+        help = "Multiplier applied to the estimated fee (or the estimated L1 gas amount for V3 \
+        transactions) to get the max fee. Falls back to the default of the active profile"
+    )]
+    fee_multiplier: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FeeToken {
+    Eth,
+    Strk,
 }
 
 #[derive(Debug)]
 pub enum FeeSetting {
+    /// Legacy transactions paying fees in ETH.
+    Eth(EthFeeSetting),
+    /// V3 transactions paying fees in STRK.
+    Strk(StrkFeeSetting),
+}
+
+#[derive(Debug)]
+pub enum EthFeeSetting {
     Manual(FieldElement),
     EstimateOnly,
-    None,
+    /// Uses the estimated fee with a buffer. The multiplier is `None` when not configured, in
+    /// which case the command default applies.
+    Estimate {
+        multiplier: Option<f64>,
+    },
+}
+
+/// Resource bounds of V3 transactions. Values not specified are filled in from a fee estimate.
+#[derive(Debug)]
+pub struct StrkFeeSetting {
+    pub l1_gas: Option<u64>,
+    pub l1_gas_price: Option<u128>,
+    pub tip: u64,
+    pub estimate_only: bool,
+    pub multiplier: Option<f64>,
 }
 
 impl FeeArgs {
     pub fn into_setting(self) -> Result<FeeSetting> {
+        match self.fee_token {
+            FeeToken::Eth => self.into_eth_setting().map(FeeSetting::Eth),
+            FeeToken::Strk => self.into_strk_setting().map(FeeSetting::Strk),
+        }
+    }
+
+    fn into_eth_setting(self) -> Result<EthFeeSetting> {
+        if self.l1_gas.is_some() || self.l1_gas_price.is_some() || self.tip.is_some() {
+            anyhow::bail!(
+                "--l1-gas, --l1-gas-price, and --tip can only be used with --fee-token strk"
+            );
+        }
+
+        if self.fee_multiplier.is_some()
+            && (self.max_fee.is_some() || self.max_fee_raw.is_some() || self.estimate_only)
+        {
+            anyhow::bail!(
+                "--fee-multiplier cannot be used with --max-fee, --max-fee-raw, or --estimate-only"
+            );
+        }
+
         match (self.max_fee, self.max_fee_raw, self.estimate_only) {
             (Some(max_fee), None, false) => {
                 let max_fee_felt = bigdecimal_to_felt(&max_fee, 18)?;
@@ -41,21 +138,223 @@ impl FeeArgs {
                     )
                 }
 
-                Ok(FeeSetting::Manual(max_fee_felt))
+                Ok(EthFeeSetting::Manual(max_fee_felt))
             }
-            (None, Some(max_fee_raw), false) => Ok(FeeSetting::Manual(max_fee_raw)),
-            (None, None, true) => Ok(FeeSetting::EstimateOnly),
-            (None, None, false) => Ok(FeeSetting::None),
+            (None, Some(max_fee_raw), false) => Ok(EthFeeSetting::Manual(max_fee_raw)),
+            (None, None, true) => Ok(EthFeeSetting::EstimateOnly),
+            (None, None, false) => Ok(EthFeeSetting::Estimate {
+                multiplier: resolve_multiplier(self.fee_multiplier)?,
+            }),
             _ => Err(anyhow::anyhow!(
                 "invalid fee option. \
                 At most one of --max-fee, --max-fee-raw, and --estimate-only can be used."
             )),
         }
     }
+
+    fn into_strk_setting(self) -> Result<StrkFeeSetting> {
+        if self.max_fee.is_some() || self.max_fee_raw.is_some() {
+            anyhow::bail!(
+                "--max-fee and --max-fee-raw cannot be used with --fee-token strk. \
+                Use --l1-gas and --l1-gas-price instead."
+            );
+        }
+
+        let is_manual = self.l1_gas.is_some() && self.l1_gas_price.is_some();
+        if self.estimate_only && (self.l1_gas.is_some() || self.l1_gas_price.is_some()) {
+            anyhow::bail!("--estimate-only cannot be used with --l1-gas or --l1-gas-price");
+        }
+        if self.fee_multiplier.is_some() && (self.estimate_only || is_manual) {
+            anyhow::bail!(
+                "--fee-multiplier cannot be used with --estimate-only, or when both --l1-gas and \
+                --l1-gas-price are specified"
+            );
+        }
+
+        Ok(StrkFeeSetting {
+            l1_gas: self.l1_gas,
+            l1_gas_price: self.l1_gas_price,
+            tip: self.tip.unwrap_or_default(),
+            estimate_only: self.estimate_only,
+            multiplier: if is_manual {
+                None
+            } else {
+                resolve_multiplier(self.fee_multiplier)?
+            },
+        })
+    }
 }
 
 impl FeeSetting {
+    /// Estimates the fee with the default buffer, for transactions sent on behalf of the user
+    /// without fee options of their own.
+    pub fn estimate(fee_token: FeeToken) -> Self {
+        match fee_token {
+            FeeToken::Eth => FeeSetting::Eth(EthFeeSetting::Estimate { multiplier: None }),
+            FeeToken::Strk => FeeSetting::Strk(StrkFeeSetting {
+                l1_gas: None,
+                l1_gas_price: None,
+                tip: 0,
+                estimate_only: false,
+                multiplier: None,
+            }),
+        }
+    }
+
     pub fn is_estimate_only(&self) -> bool {
-        matches!(self, FeeSetting::EstimateOnly)
+        match self {
+            FeeSetting::Eth(setting) => matches!(setting, EthFeeSetting::EstimateOnly),
+            FeeSetting::Strk(setting) => setting.estimate_only,
+        }
     }
 }
+
+impl EthFeeSetting {
+    /// Applies the configured fee multiplier to an estimated fee, or `default_multiplier` if the
+    /// multiplier is not configured.
+    pub fn apply_multiplier(
+        &self,
+        estimated_fee: FieldElement,
+        default_multiplier: f64,
+    ) -> FieldElement {
+        let multiplier = match self {
+            EthFeeSetting::Estimate {
+                multiplier: Some(multiplier),
+            } => *multiplier,
+            _ => default_multiplier,
+        };
+
+        // Multipliers are always validated to be positive and finite
+        let scaled_multiplier = (multiplier * FEE_MULTIPLIER_PRECISION as f64).round() as u64;
+
+        (estimated_fee * FieldElement::from(scaled_multiplier))
+            .floor_div(FieldElement::from(FEE_MULTIPLIER_PRECISION))
+    }
+}
+
+impl StrkFeeSetting {
+    /// Whether a fee estimate is needed to determine the resource bounds.
+    pub fn needs_estimate(&self) -> bool {
+        self.l1_gas.is_none() || self.l1_gas_price.is_none()
+    }
+
+    /// Determines the resource bounds, filling in values not specified from `estimate` with
+    /// buffers applied. `estimate` must be provided if [needs_estimate](Self::needs_estimate)
+    /// returns `true`.
+    pub fn resolve(
+        &self,
+        estimate: Option<&FeeEstimate>,
+        default_multiplier: f64,
+    ) -> Result<V3Fee> {
+        let l1_gas = match (self.l1_gas, estimate) {
+            (Some(l1_gas), _) => l1_gas,
+            (None, Some(estimate)) => {
+                let gas_consumed = felt_to_u128(estimate.gas_consumed)?;
+                let multiplier = self.multiplier.unwrap_or(default_multiplier);
+
+                u64::try_from((gas_consumed as f64 * multiplier).ceil() as u128)
+                    .map_err(|_| anyhow::anyhow!("estimated L1 gas amount out of range"))?
+            }
+            (None, None) => anyhow::bail!("missing fee estimate for L1 gas amount"),
+        };
+
+        let l1_gas_price = match (self.l1_gas_price, estimate) {
+            (Some(l1_gas_price), _) => l1_gas_price,
+            (None, Some(estimate)) => {
+                let gas_price = felt_to_u128(estimate.gas_price)?;
+                let scaled_multiplier =
+                    (GAS_PRICE_MULTIPLIER * FEE_MULTIPLIER_PRECISION as f64).round() as u128;
+
+                gas_price.saturating_mul(scaled_multiplier) / FEE_MULTIPLIER_PRECISION as u128
+            }
+            (None, None) => anyhow::bail!("missing fee estimate for L1 gas price"),
+        };
+
+        Ok(V3Fee {
+            l1_gas,
+            l1_gas_price,
+            tip: self.tip,
+        })
+    }
+}
+
+impl FeeToken {
+    /// Symbol of the token.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            FeeToken::Eth => "ETH",
+            FeeToken::Strk => "STRK",
+        }
+    }
+
+    /// Name of the smallest denomination of the token.
+    pub fn base_unit(&self) -> &'static str {
+        match self {
+            FeeToken::Eth => "Wei",
+            FeeToken::Strk => "Fri",
+        }
+    }
+
+    /// Address of the token contract used for paying fees.
+    pub fn address(&self) -> FieldElement {
+        match self {
+            FeeToken::Eth => DEFAULT_ETH_ADDRESS,
+            FeeToken::Strk => DEFAULT_STRK_ADDRESS,
+        }
+    }
+}
+
+impl Display for FeeToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.symbol())
+    }
+}
+
+/// Prints the overall fee of an estimate to stdout so that it can be easily scripted, and the gas
+/// consumed and gas price separately to stderr.
+pub fn print_fee_estimate(estimate: &FeeEstimate, fee_token: FeeToken) {
+    eprintln!(
+        "Gas consumed: {}; gas price: {} {}",
+        format!("{}", estimate.gas_consumed).bright_yellow(),
+        format!("{}", estimate.gas_price).bright_yellow(),
+        fee_token.base_unit()
+    );
+
+    if is_json_output() {
+        record_fee_estimate(estimate, fee_token);
+    } else {
+        println!(
+            "{} {}",
+            format!("{}", estimate.overall_fee.to_big_decimal(18)).bright_yellow(),
+            fee_token
+        );
+    }
+}
+
+/// Records the fee estimate for JSON output, with the overall fee in token units.
+pub fn record_fee_estimate(estimate: &FeeEstimate, fee_token: FeeToken) {
+    record(
+        "estimated_fee",
+        serde_json::json!({
+            "gas_consumed": format!("{}", estimate.gas_consumed),
+            "gas_price": format!("{}", estimate.gas_price),
+            "overall_fee": format!("{}", estimate.overall_fee.to_big_decimal(18)),
+            "unit": fee_token.to_string(),
+        }),
+    );
+}
+
+fn resolve_multiplier(value: Option<f64>) -> Result<Option<f64>> {
+    let multiplier = match value {
+        Some(multiplier) => Some(multiplier),
+        None => ProfileDefaults::load()?.fee_multiplier,
+    };
+
+    if let Some(multiplier) = multiplier {
+        if !multiplier.is_finite() || multiplier <= 0.0 {
+            anyhow::bail!("invalid fee multiplier: {}", multiplier);
+        }
+    }
+
+    Ok(multiplier)
+}
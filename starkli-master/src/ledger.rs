@@ -0,0 +1,667 @@
+//! Signing with the Starknet app on Ledger hardware wallets.
+//!
+//! The app is driven with APDU commands, which are sent to the device over HID. Talking to real
+//! devices requires the `ledger` feature.
+
+use std::{fmt::Debug, str::FromStr};
+
+use anyhow::Result;
+use sha2::{Digest, Sha256};
+use starknet::{
+    core::{crypto::Signature, types::FieldElement},
+    signers::VerifyingKey,
+};
+
+const CLA_STARKNET: u8 = 0x5a;
+
+const INS_GET_PUBLIC_KEY: u8 = 0x01;
+const INS_SIGN_HASH: u8 = 0x02;
+
+/// The first `INS_SIGN_HASH` command carries the derivation path, and the second one the hash.
+const P1_SIGN_HASH_PATH: u8 = 0x00;
+const P1_SIGN_HASH_HASH: u8 = 0x01;
+
+const SW_OK: u16 = 0x9000;
+const SW_DENIED: u16 = 0x6985;
+
+const EIP_2645_PURPOSE: u32 = 2645;
+const EIP_2645_PATH_LENGTH: usize = 6;
+const HARDENED_OFFSET: u32 = 0x80000000;
+
+/// Sends APDU commands to the Starknet Ledger app.
+pub trait LedgerTransport: Debug + Send + Sync {
+    fn exchange(&self, command: &ApduCommand) -> Result<ApduAnswer, LedgerError>;
+}
+
+#[derive(Debug)]
+pub struct LedgerSigner {
+    transport: Box<dyn LedgerTransport>,
+    path: DerivationPath,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum LedgerError {
+    #[error(
+        "Ledger device not found. Make sure it's connected and unlocked, with the Starknet app open"
+    )]
+    DeviceNotFound,
+    #[error("Ledger transport error: {0}")]
+    Transport(String),
+    #[error("request denied on the Ledger device")]
+    Denied,
+    #[error("Ledger app returned error status {0:#06x}")]
+    Status(u16),
+    #[error("unexpected response from the Ledger app")]
+    UnexpectedResponse,
+    #[error("timed out waiting for the Ledger device")]
+    Timeout,
+    #[cfg(not(feature = "ledger"))]
+    #[error("Ledger support is not enabled in this build. Rebuild with the `ledger` feature")]
+    NotSupported,
+}
+
+#[derive(Debug, Clone)]
+pub struct ApduCommand {
+    pub cla: u8,
+    pub ins: u8,
+    pub p1: u8,
+    pub p2: u8,
+    pub data: Vec<u8>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ApduAnswer {
+    pub data: Vec<u8>,
+    pub status: u16,
+}
+
+/// An EIP-2645 derivation path, such as `m/2645'/starknet'/starkli'/0'/0'/0`. Segments that are
+/// not numbers are replaced by the 31 lowest bits of their SHA-256 hashes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DerivationPath(Vec<u32>);
+
+/// Opens the transport to the first connected Ledger device.
+#[cfg(feature = "ledger")]
+pub fn open_device() -> Result<Box<dyn LedgerTransport>, LedgerError> {
+    Ok(Box::new(hid::HidTransport::open()?))
+}
+
+/// Opens the transport to the first connected Ledger device.
+#[cfg(not(feature = "ledger"))]
+pub fn open_device() -> Result<Box<dyn LedgerTransport>, LedgerError> {
+    Err(LedgerError::NotSupported)
+}
+
+impl LedgerSigner {
+    pub fn new(transport: Box<dyn LedgerTransport>, path: DerivationPath) -> Self {
+        Self { transport, path }
+    }
+
+    pub fn get_public_key(&self) -> Result<VerifyingKey, LedgerError> {
+        let response = self.exchange(ApduCommand {
+            cla: CLA_STARKNET,
+            ins: INS_GET_PUBLIC_KEY,
+            p1: 0,
+            p2: 0,
+            data: self.path.to_bytes(),
+        })?;
+
+        // Uncompressed point: 0x04 | x | y
+        if response.len() != 65 || response[0] != 0x04 {
+            return Err(LedgerError::UnexpectedResponse);
+        }
+        let public_key = FieldElement::from_byte_slice_be(&response[1..33])
+            .map_err(|_| LedgerError::UnexpectedResponse)?;
+
+        Ok(VerifyingKey::from_scalar(public_key))
+    }
+
+    pub fn sign_hash(&self, hash: &FieldElement) -> Result<Signature, LedgerError> {
+        self.exchange(ApduCommand {
+            cla: CLA_STARKNET,
+            ins: INS_SIGN_HASH,
+            p1: P1_SIGN_HASH_PATH,
+            p2: 0,
+            data: self.path.to_bytes(),
+        })?;
+
+        let response = self.exchange(ApduCommand {
+            cla: CLA_STARKNET,
+            ins: INS_SIGN_HASH,
+            p1: P1_SIGN_HASH_HASH,
+            p2: 0,
+            data: encode_hash(hash),
+        })?;
+
+        // Length-prefixed signature: 0x41 | r | s | v
+        if response.len() != 66 || response[0] != 0x41 {
+            return Err(LedgerError::UnexpectedResponse);
+        }
+        let r = FieldElement::from_byte_slice_be(&response[1..33])
+            .map_err(|_| LedgerError::UnexpectedResponse)?;
+        let s = FieldElement::from_byte_slice_be(&response[33..65])
+            .map_err(|_| LedgerError::UnexpectedResponse)?;
+
+        Ok(Signature { r, s })
+    }
+
+    fn exchange(&self, command: ApduCommand) -> Result<Vec<u8>, LedgerError> {
+        let answer = self.transport.exchange(&command)?;
+
+        match answer.status {
+            SW_OK => Ok(answer.data),
+            SW_DENIED => Err(LedgerError::Denied),
+            status => Err(LedgerError::Status(status)),
+        }
+    }
+}
+
+/// Encodes a hash for `INS_SIGN_HASH`. The app expects the 252-bit hash shifted left by 4 bits to
+/// fill all 256 bits.
+fn encode_hash(hash: &FieldElement) -> Vec<u8> {
+    let bytes = hash.to_bytes_be();
+
+    (0..bytes.len())
+        .map(|ind| (bytes[ind] << 4) | bytes.get(ind + 1).map_or(0, |next| next >> 4))
+        .collect()
+}
+
+impl ApduCommand {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![self.cla, self.ins, self.p1, self.p2, self.data.len() as u8];
+        bytes.extend_from_slice(&self.data);
+        bytes
+    }
+}
+
+impl DerivationPath {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.0.iter().flat_map(|item| item.to_be_bytes()).collect()
+    }
+}
+
+impl FromStr for DerivationPath {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let segments = s
+            .strip_prefix("m/")
+            .ok_or_else(|| anyhow::anyhow!("derivation path must start with `m/`"))?
+            .split('/')
+            .map(|segment| {
+                let (segment, hardened) = match segment.strip_suffix('\'') {
+                    Some(segment) => (segment, true),
+                    None => (segment, false),
+                };
+
+                let value = if segment.is_empty() {
+                    anyhow::bail!("empty segment in derivation path: {}", s);
+                } else if segment.chars().all(|c| c.is_ascii_digit()) {
+                    let value: u32 = segment.parse()?;
+                    if value >= HARDENED_OFFSET {
+                        anyhow::bail!("derivation path segment out of range: {}", segment);
+                    }
+                    value
+                } else {
+                    let hash = Sha256::digest(segment.as_bytes());
+                    u32::from_be_bytes([hash[28], hash[29], hash[30], hash[31]]) & !HARDENED_OFFSET
+                };
+
+                Ok(if hardened {
+                    value | HARDENED_OFFSET
+                } else {
+                    value
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        if segments.len() != EIP_2645_PATH_LENGTH
+            || segments[0] != EIP_2645_PURPOSE | HARDENED_OFFSET
+        {
+            anyhow::bail!(
+                "invalid derivation path: {}. Expected an EIP-2645 path like \
+                m/2645'/starknet'/starkli'/0'/0'/0",
+                s
+            );
+        }
+
+        Ok(Self(segments))
+    }
+}
+
+#[cfg(feature = "ledger")]
+mod hid {
+    use std::{
+        fmt::Debug,
+        sync::Mutex,
+        time::{Duration, Instant},
+    };
+
+    use hidapi::{HidApi, HidDevice};
+
+    use super::{ApduAnswer, ApduCommand, LedgerError, LedgerTransport};
+
+    const LEDGER_VENDOR_ID: u16 = 0x2c97;
+    const LEDGER_USAGE_PAGE: u16 = 0xffa0;
+
+    const HID_PACKET_SIZE: usize = 64;
+    const HID_CHANNEL: u16 = 0x0101;
+    const HID_TAG_APDU: u8 = 0x05;
+
+    /// Reads are retried at this interval in milliseconds instead of blocking indefinitely.
+    const HID_READ_TIMEOUT_MS: i32 = 100;
+    /// Answers to signing requests only arrive after confirmation on the device, so this has to
+    /// leave enough time for the user.
+    const HID_ANSWER_TIMEOUT: Duration = Duration::from_secs(300);
+
+    /// Transport for Ledger devices connected over USB.
+    pub struct HidTransport {
+        device: Mutex<HidDevice>,
+    }
+
+    impl HidTransport {
+        /// Opens the first connected Ledger device.
+        pub fn open() -> Result<Self, LedgerError> {
+            let api = HidApi::new().map_err(|err| LedgerError::Transport(err.to_string()))?;
+
+            let device_info = api
+                .device_list()
+                .find(|device| {
+                    device.vendor_id() == LEDGER_VENDOR_ID
+                        && (device.usage_page() == LEDGER_USAGE_PAGE
+                            || device.interface_number() == 0)
+                })
+                .ok_or(LedgerError::DeviceNotFound)?;
+
+            let device = device_info
+                .open_device(&api)
+                .map_err(|err| LedgerError::Transport(err.to_string()))?;
+
+            Ok(Self {
+                device: Mutex::new(device),
+            })
+        }
+
+        fn write_apdu(device: &HidDevice, apdu: &[u8]) -> Result<(), LedgerError> {
+            let mut offset = 0;
+            let mut sequence: u16 = 0;
+
+            while offset < apdu.len() {
+                // The leading zero is the HID report ID
+                let mut packet = vec![0u8];
+                packet.extend_from_slice(&HID_CHANNEL.to_be_bytes());
+                packet.push(HID_TAG_APDU);
+                packet.extend_from_slice(&sequence.to_be_bytes());
+                if sequence == 0 {
+                    packet.extend_from_slice(&(apdu.len() as u16).to_be_bytes());
+                }
+
+                let chunk_size = (HID_PACKET_SIZE + 1 - packet.len()).min(apdu.len() - offset);
+                packet.extend_from_slice(&apdu[offset..(offset + chunk_size)]);
+                packet.resize(HID_PACKET_SIZE + 1, 0);
+
+                device
+                    .write(&packet)
+                    .map_err(|err| LedgerError::Transport(err.to_string()))?;
+
+                offset += chunk_size;
+                sequence += 1;
+            }
+
+            Ok(())
+        }
+
+        fn read_answer(device: &HidDevice) -> Result<Vec<u8>, LedgerError> {
+            let mut answer = vec![];
+            let mut expected_len = None;
+            let mut sequence: u16 = 0;
+
+            let deadline = Instant::now() + HID_ANSWER_TIMEOUT;
+
+            loop {
+                let mut packet = [0u8; HID_PACKET_SIZE];
+                let read_len = device
+                    .read_timeout(&mut packet, HID_READ_TIMEOUT_MS)
+                    .map_err(|err| LedgerError::Transport(err.to_string()))?;
+
+                // Nothing is read when the read times out
+                if read_len == 0 {
+                    if Instant::now() >= deadline {
+                        return Err(LedgerError::Timeout);
+                    }
+                    continue;
+                }
+
+                if read_len < 7
+                    || packet[0..2] != HID_CHANNEL.to_be_bytes()
+                    || packet[2] != HID_TAG_APDU
+                    || packet[3..5] != sequence.to_be_bytes()
+                {
+                    return Err(LedgerError::Transport("malformed HID packet".into()));
+                }
+
+                let data = if sequence == 0 {
+                    expected_len = Some(u16::from_be_bytes([packet[5], packet[6]]) as usize);
+                    &packet[7..read_len]
+                } else {
+                    &packet[5..read_len]
+                };
+                answer.extend_from_slice(data);
+
+                // Always set after the first packet
+                let expected_len = expected_len.unwrap();
+                if answer.len() >= expected_len {
+                    answer.truncate(expected_len);
+                    return Ok(answer);
+                }
+
+                sequence += 1;
+            }
+        }
+    }
+
+    impl Debug for HidTransport {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("HidTransport").finish_non_exhaustive()
+        }
+    }
+
+    impl LedgerTransport for HidTransport {
+        fn exchange(&self, command: &ApduCommand) -> Result<ApduAnswer, LedgerError> {
+            let device = self
+                .device
+                .lock()
+                .map_err(|_| LedgerError::Transport("device lock poisoned".into()))?;
+
+            Self::write_apdu(&device, &command.to_bytes())?;
+            let mut answer = Self::read_answer(&device)?;
+
+            if answer.len() < 2 {
+                return Err(LedgerError::UnexpectedResponse);
+            }
+            let status_bytes = answer.split_off(answer.len() - 2);
+
+            Ok(ApduAnswer {
+                data: answer,
+                status: u16::from_be_bytes([status_bytes[0], status_bytes[1]]),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use starknet::{macros::felt, signers::SigningKey};
+
+    use super::*;
+
+    const SW_WRONG_DATA: u16 = 0x6a80;
+    const SW_WRONG_P1_P2: u16 = 0x6b00;
+    const SW_INS_NOT_SUPPORTED: u16 = 0x6d00;
+    const SW_CLA_NOT_SUPPORTED: u16 = 0x6e00;
+
+    const TEST_PATH: &str = "m/2645'/starknet'/starkli'/0'/0'/0";
+
+    /// A stand-in for the Starknet Ledger app that answers APDU commands in-process with a local
+    /// key, which is used for all derivation paths.
+    #[derive(Debug)]
+    struct SimulatedTransport {
+        key: SigningKey,
+        pending_sign_path: Mutex<Option<DerivationPath>>,
+    }
+
+    impl SimulatedTransport {
+        fn new(key: SigningKey) -> Self {
+            Self {
+                key,
+                pending_sign_path: Mutex::new(None),
+            }
+        }
+    }
+
+    impl LedgerTransport for SimulatedTransport {
+        fn exchange(&self, command: &ApduCommand) -> Result<ApduAnswer, LedgerError> {
+            // Goes through the wire format to make sure commands are well-formed
+            let command = command_from_bytes(&command.to_bytes())
+                .ok_or_else(|| LedgerError::Transport("malformed APDU command".into()))?;
+
+            if command.cla != CLA_STARKNET {
+                return Ok(status_answer(SW_CLA_NOT_SUPPORTED));
+            }
+
+            let mut pending_sign_path = self
+                .pending_sign_path
+                .lock()
+                .map_err(|_| LedgerError::Transport("simulator lock poisoned".into()))?;
+
+            match (command.ins, command.p1) {
+                (INS_GET_PUBLIC_KEY, _) => {
+                    if path_from_bytes(&command.data).is_none() {
+                        return Ok(status_answer(SW_WRONG_DATA));
+                    }
+
+                    // Only the x coordinate is used, so the y coordinate is left as zeros
+                    let mut data = vec![0x04];
+                    data.extend_from_slice(&self.key.verifying_key().scalar().to_bytes_be());
+                    data.extend_from_slice(&[0u8; 32]);
+
+                    Ok(ApduAnswer {
+                        data,
+                        status: SW_OK,
+                    })
+                }
+                (INS_SIGN_HASH, P1_SIGN_HASH_PATH) => match path_from_bytes(&command.data) {
+                    Some(path) => {
+                        *pending_sign_path = Some(path);
+                        Ok(status_answer(SW_OK))
+                    }
+                    None => Ok(status_answer(SW_WRONG_DATA)),
+                },
+                (INS_SIGN_HASH, P1_SIGN_HASH_HASH) => {
+                    if pending_sign_path.take().is_none() || command.data.len() != 32 {
+                        return Ok(status_answer(SW_WRONG_DATA));
+                    }
+
+                    let hash = match decode_hash(&command.data) {
+                        Some(hash) => hash,
+                        None => return Ok(status_answer(SW_WRONG_DATA)),
+                    };
+                    let signature = self
+                        .key
+                        .sign(&hash)
+                        .map_err(|err| LedgerError::Transport(err.to_string()))?;
+
+                    let mut data = vec![0x41];
+                    data.extend_from_slice(&signature.r.to_bytes_be());
+                    data.extend_from_slice(&signature.s.to_bytes_be());
+                    data.push(0);
+
+                    Ok(ApduAnswer {
+                        data,
+                        status: SW_OK,
+                    })
+                }
+                (INS_SIGN_HASH, _) => Ok(status_answer(SW_WRONG_P1_P2)),
+                _ => Ok(status_answer(SW_INS_NOT_SUPPORTED)),
+            }
+        }
+    }
+
+    fn command_from_bytes(bytes: &[u8]) -> Option<ApduCommand> {
+        if bytes.len() < 5 || bytes.len() != 5 + bytes[4] as usize {
+            return None;
+        }
+
+        Some(ApduCommand {
+            cla: bytes[0],
+            ins: bytes[1],
+            p1: bytes[2],
+            p2: bytes[3],
+            data: bytes[5..].to_vec(),
+        })
+    }
+
+    fn path_from_bytes(bytes: &[u8]) -> Option<DerivationPath> {
+        if bytes.len() != EIP_2645_PATH_LENGTH * 4 {
+            return None;
+        }
+
+        let path = DerivationPath(
+            bytes
+                .chunks_exact(4)
+                .map(|chunk| u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+                .collect(),
+        );
+
+        if path.0[0] == EIP_2645_PURPOSE | HARDENED_OFFSET {
+            Some(path)
+        } else {
+            None
+        }
+    }
+
+    /// Reverses [encode_hash], rejecting data that is not shifted as the app expects.
+    fn decode_hash(data: &[u8]) -> Option<FieldElement> {
+        if data[data.len() - 1] & 0x0f != 0 {
+            return None;
+        }
+
+        let bytes = (0..data.len())
+            .map(|ind| {
+                (data[ind] >> 4)
+                    | match ind.checked_sub(1) {
+                        Some(prev) => data[prev] << 4,
+                        None => 0,
+                    }
+            })
+            .collect::<Vec<_>>();
+
+        FieldElement::from_byte_slice_be(&bytes).ok()
+    }
+
+    fn status_answer(status: u16) -> ApduAnswer {
+        ApduAnswer {
+            data: vec![],
+            status,
+        }
+    }
+
+    fn test_signer(secret_scalar: FieldElement) -> LedgerSigner {
+        LedgerSigner::new(
+            Box::new(SimulatedTransport::new(SigningKey::from_secret_scalar(
+                secret_scalar,
+            ))),
+            TEST_PATH.parse().unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_parse_derivation_path() {
+        let path: DerivationPath = TEST_PATH.parse().unwrap();
+
+        assert_eq!(
+            path,
+            DerivationPath(vec![
+                0x80000a55, 0xc741e9c9, 0xd7a55df5, 0x80000000, 0x80000000, 0x00000000
+            ])
+        );
+        assert_eq!(path_from_bytes(&path.to_bytes()), Some(path));
+    }
+
+    #[test]
+    fn test_parse_invalid_derivation_path() {
+        for raw in [
+            "2645'/starknet'/starkli'/0'/0'/0",
+            "m/2645'/starknet'/starkli'/0'/0'",
+            "m/44'/starknet'/starkli'/0'/0'/0",
+            "m/2645'/starknet'//0'/0'/0",
+            "m/2645'/starknet'/starkli'/0'/0'/2147483648",
+        ] {
+            assert!(
+                raw.parse::<DerivationPath>().is_err(),
+                "{} should be rejected",
+                raw
+            );
+        }
+    }
+
+    #[test]
+    fn test_get_public_key() {
+        let key = SigningKey::from_secret_scalar(felt!("0x1234"));
+
+        assert_eq!(
+            test_signer(felt!("0x1234"))
+                .get_public_key()
+                .unwrap()
+                .scalar(),
+            key.verifying_key().scalar()
+        );
+    }
+
+    #[test]
+    fn test_sign_hash() {
+        let key = SigningKey::from_secret_scalar(felt!("0x1234"));
+        let hash = felt!("0x5678");
+
+        let signature = test_signer(felt!("0x1234")).sign_hash(&hash).unwrap();
+
+        assert!(key.verifying_key().verify(&hash, &signature).unwrap());
+    }
+
+    #[test]
+    fn test_encode_hash() {
+        let mut expected = vec![0u8; 32];
+        expected[30] = 0xab;
+        expected[31] = 0xc0;
+        assert_eq!(encode_hash(&felt!("0xabc")), expected);
+
+        let hash = felt!("0x7ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff12");
+        assert_eq!(decode_hash(&encode_hash(&hash)), Some(hash));
+    }
+
+    #[test]
+    fn test_sign_hash_rejects_unshifted_hash() {
+        let transport = SimulatedTransport::new(SigningKey::from_secret_scalar(felt!("0x1234")));
+        let path: DerivationPath = TEST_PATH.parse().unwrap();
+
+        transport
+            .exchange(&ApduCommand {
+                cla: CLA_STARKNET,
+                ins: INS_SIGN_HASH,
+                p1: P1_SIGN_HASH_PATH,
+                p2: 0,
+                data: path.to_bytes(),
+            })
+            .unwrap();
+        let answer = transport
+            .exchange(&ApduCommand {
+                cla: CLA_STARKNET,
+                ins: INS_SIGN_HASH,
+                p1: P1_SIGN_HASH_HASH,
+                p2: 0,
+                data: felt!("0x5678").to_bytes_be().to_vec(),
+            })
+            .unwrap();
+
+        assert_eq!(answer.status, SW_WRONG_DATA);
+    }
+
+    #[test]
+    fn test_sign_hash_without_path() {
+        let transport = SimulatedTransport::new(SigningKey::from_secret_scalar(felt!("0x1234")));
+
+        let answer = transport
+            .exchange(&ApduCommand {
+                cla: CLA_STARKNET,
+                ins: INS_SIGN_HASH,
+                p1: P1_SIGN_HASH_HASH,
+                p2: 0,
+                data: felt!("0x5678").to_bytes_be().to_vec(),
+            })
+            .unwrap();
+
+        assert_eq!(answer.status, SW_WRONG_DATA);
+    }
+}
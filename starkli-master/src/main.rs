@@ -2,24 +2,35 @@ use anyhow::Result;
 use clap::{CommandFactory, Parser, Subcommand};
 use colored::Colorize;
 
-use crate::{provider::ProviderArgs, subcommands::*};
+use crate::{profile::Profiles, provider::ProviderArgs, subcommands::*};
 
+mod abi;
 mod account;
 mod account_factory;
 mod address_book;
+mod block;
+mod calls_file;
 mod casm;
 mod chain_id;
 mod compiler;
 mod decode;
+mod deploy_manifest;
+mod erc20;
 mod error;
 mod fee;
+mod ledger;
 mod network;
+mod offline;
+mod output;
 mod path;
 mod profile;
 mod provider;
+mod scarb;
 mod signer;
 mod subcommands;
+mod udc;
 mod utils;
+mod v3;
 mod verbosity;
 
 const VERSION_STRING: &str = concat!(env!("CARGO_PKG_VERSION"), " (", env!("VERGEN_GIT_SHA"), ")");
@@ -44,6 +55,19 @@ struct Cli {
         help = "Use verbose output (currently only applied to version)"
     )]
     verbose: bool,
+    #[clap(
+        long,
+        global = true,
+        env = "STARKLI_PROFILE",
+        help = "Profile to use instead of the default profile"
+    )]
+    profile: Option<String>,
+    #[clap(
+        long,
+        global = true,
+        help = "Print results as a single JSON document to stdout, including errors"
+    )]
+    json: bool,
 }
 
 #[derive(Debug, Subcommand)]
@@ -61,12 +85,17 @@ enum Subcommands {
     ParseCairoString(ParseCairoString),
     #[clap(about = "Print the montgomery representation of a field element")]
     Mont(Mont),
+    #[clap(about = "Contract address utilities")]
+    Address(Address),
     //
     // JSON-RPC query client
     //
     #[clap(about = "Call contract functions without sending transactions")]
     Call(Call),
-    #[clap(alias = "tx", about = "Get Starknet transaction by hash")]
+    #[clap(
+        alias = "tx",
+        about = "Get Starknet transaction by hash, or sign and send offline transactions"
+    )]
     Transaction(Transaction),
     #[clap(alias = "bn", about = "Get latest block number")]
     BlockNumber(BlockNumber),
@@ -85,11 +114,13 @@ enum Subcommands {
         about = "Get transaction receipt by hash"
     )]
     Receipt(TransactionReceipt),
+    #[clap(about = "Query events with filters on contract address, keys and block range")]
+    Events(Events),
     #[clap(about = "Get transaction trace by hash")]
     Trace(TransactionTrace),
     #[clap(about = "Get Starknet network ID")]
     ChainId(ChainId),
-    #[clap(about = "Get native gas token (currently ETH) balance")]
+    #[clap(about = "Get ERC20 token balances (ETH by default)")]
     Balance(Balance),
     #[clap(about = "Get nonce for a certain contract")]
     Nonce(Nonce),
@@ -116,6 +147,13 @@ enum Subcommands {
     #[clap(about = "Account management commands")]
     Account(Account),
     //
+    // Profile management
+    //
+    #[clap(about = "Profile and network management commands")]
+    Profile(Profile),
+    #[clap(about = "Address book management commands")]
+    AddressBook(AddressBook),
+    //
     // Sending out transactions
     //
     #[clap(about = "Send an invoke transaction from an account contract")]
@@ -124,6 +162,10 @@ enum Subcommands {
     Declare(Declare),
     #[clap(about = "Deploy contract via the Universal Deployer Contract")]
     Deploy(Deploy),
+    #[clap(about = "Transfer and approve ERC20 tokens")]
+    Erc20(Erc20),
+    #[clap(about = "Declare, deploy, and initialize contracts from a deployment manifest")]
+    Apply(Apply),
     //
     // Misc
     //
@@ -142,23 +184,42 @@ enum Subcommands {
 
 #[tokio::main]
 async fn main() {
-    if let Err(err) = run_command(Cli::parse()).await {
-        eprintln!("{}", format!("Error: {err}").red());
+    let cli = Cli::parse();
+    output::set_json_output(cli.json);
+
+    // Colors would otherwise end up in the recorded values
+    if cli.json {
+        colored::control::set_override(false);
+    }
+
+    let result = run_command(cli).await;
+    if output::is_json_output() {
+        output::print_document(&result);
+    }
+
+    if let Err(err) = result {
+        if !output::is_json_output() {
+            eprintln!("{}", format!("Error: {err}").red());
+        }
         std::process::exit(1);
     }
 }
 
 async fn run_command(cli: Cli) -> Result<()> {
+    if let Some(profile) = cli.profile {
+        Profiles::set_profile_override(profile);
+    }
+
     match (cli.version, cli.command) {
         (false, None) => Ok(Cli::command().print_help()?),
         (true, _) => {
-            println!(
-                "{}",
+            output::print_text(
+                "version",
                 if cli.verbose {
                     VERSION_STRING_VERBOSE
                 } else {
                     VERSION_STRING
-                }
+                },
             );
 
             Ok(())
@@ -169,6 +230,7 @@ async fn run_command(cli: Cli) -> Result<()> {
             Subcommands::ToCairoString(cmd) => cmd.run(),
             Subcommands::ParseCairoString(cmd) => cmd.run(),
             Subcommands::Mont(cmd) => cmd.run(),
+            Subcommands::Address(cmd) => cmd.run().await,
             Subcommands::Call(cmd) => cmd.run().await,
             Subcommands::Transaction(cmd) => cmd.run().await,
             Subcommands::BlockNumber(cmd) => cmd.run().await,
@@ -178,6 +240,7 @@ async fn run_command(cli: Cli) -> Result<()> {
             Subcommands::StateUpdate(cmd) => cmd.run().await,
             Subcommands::BlockTraces(cmd) => cmd.run().await,
             Subcommands::Receipt(cmd) => cmd.run().await,
+            Subcommands::Events(cmd) => cmd.run().await,
             Subcommands::Trace(cmd) => cmd.run().await,
             Subcommands::ChainId(cmd) => cmd.run().await,
             Subcommands::Balance(cmd) => cmd.run().await,
@@ -190,11 +253,15 @@ async fn run_command(cli: Cli) -> Result<()> {
             Subcommands::SpecVersion(cmd) => cmd.run().await,
             Subcommands::Signer(cmd) => cmd.run(),
             Subcommands::Account(cmd) => cmd.run().await,
+            Subcommands::Profile(cmd) => cmd.run().await,
+            Subcommands::AddressBook(cmd) => cmd.run().await,
             Subcommands::Invoke(cmd) => cmd.run().await,
             Subcommands::Declare(cmd) => cmd.run().await,
             Subcommands::Deploy(cmd) => cmd.run().await,
+            Subcommands::Erc20(cmd) => cmd.run().await,
+            Subcommands::Apply(cmd) => cmd.run().await,
             Subcommands::Completions(cmd) => cmd.run(),
-            Subcommands::Lab(cmd) => cmd.run(),
+            Subcommands::Lab(cmd) => cmd.run().await,
         },
     }
 }
@@ -0,0 +1,611 @@
+//! Transactions built, signed, and sent in separate steps, so that signing can happen on machines
+//! without network access.
+//!
+//! Transactions are built with `--build-only` into JSON files containing everything needed for
+//! computing the transaction hash, which are then signed with `starkli tx sign` and broadcast with
+//! `starkli tx send`.
+
+use std::{path::Path, sync::Arc};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use starknet::{
+    core::{
+        crypto::compute_hash_on_elements,
+        serde::unsigned_field_element::UfeHex,
+        types::{
+            BlockId, BlockTag, BroadcastedDeclareTransaction, BroadcastedDeclareTransactionV2,
+            BroadcastedInvokeTransaction, BroadcastedInvokeTransactionV1, BroadcastedTransaction,
+            FeeEstimate, FieldElement, FlattenedSierraClass, SimulationFlagForEstimateFee,
+        },
+    },
+    macros::felt,
+    providers::Provider,
+};
+
+use crate::{
+    account::ResolvedAccount,
+    error::provider_error_mapper,
+    fee::{EthFeeSetting, FeeSetting},
+    output::{is_json_output, record},
+    utils::{felt_to_u128, u128_to_felt},
+    v3::{TransactionSigner, V3Fee, V3Transaction, PREFIX_DECLARE, PREFIX_INVOKE},
+};
+
+/// Version used for transactions only meant for queries is `2^128` plus the actual version.
+const QUERY_VERSION_OFFSET: FieldElement = felt!("0x100000000000000000000000000000000");
+
+/// L1 gas added to estimates made with account validation skipped, for each signature verified by
+/// `__validate__`. This is a rough upper bound on the cost of verifying one ECDSA signature on the
+/// STARK curve, which dominates the validation cost of common accounts. It's scaled by the number
+/// of signatures so that multisig accounts and accounts with a guardian are not underestimated.
+const VALIDATION_GAS_PER_SIGNATURE: u64 = 1000;
+
+/// A transaction with nonce and fee resolved, optionally with a signature attached.
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+pub struct TransactionFile {
+    #[serde_as(as = "UfeHex")]
+    pub chain_id: FieldElement,
+    pub transaction: UnsignedTransaction,
+    #[serde_as(as = "Option<Vec<UfeHex>>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<Vec<FieldElement>>,
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum UnsignedTransaction {
+    Invoke {
+        #[serde_as(as = "UfeHex")]
+        sender_address: FieldElement,
+        #[serde_as(as = "UfeHex")]
+        nonce: FieldElement,
+        fee: TransactionFee,
+        #[serde_as(as = "Vec<UfeHex>")]
+        calldata: Vec<FieldElement>,
+    },
+    Declare {
+        #[serde_as(as = "UfeHex")]
+        sender_address: FieldElement,
+        #[serde_as(as = "UfeHex")]
+        nonce: FieldElement,
+        fee: TransactionFee,
+        #[serde_as(as = "UfeHex")]
+        compiled_class_hash: FieldElement,
+        contract_class: FlattenedSierraClass,
+    },
+}
+
+/// Fee of the transaction, which also determines the transaction version: legacy transactions
+/// for fees paid in ETH, and V3 transactions for fees paid in STRK.
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "token", rename_all = "snake_case")]
+pub enum TransactionFee {
+    Eth {
+        #[serde_as(as = "UfeHex")]
+        max_fee: FieldElement,
+    },
+    Strk {
+        l1_gas: u64,
+        #[serde_as(as = "UfeHex")]
+        l1_gas_price: FieldElement,
+        tip: u64,
+    },
+}
+
+impl TransactionFile {
+    /// Resolves the nonce and fee of `transaction`. Since no signer is available at this stage,
+    /// fees are estimated with account validation skipped, and a buffer for its cost is added
+    /// instead, based on the `num_signatures` the account verifies. Account deployments are not
+    /// supported.
+    pub async fn build<P>(
+        provider: &P,
+        chain_id: FieldElement,
+        nonce: Option<FieldElement>,
+        transaction: V3Transaction,
+        fee_setting: &FeeSetting,
+        default_multiplier: f64,
+        num_signatures: usize,
+    ) -> Result<Self>
+    where
+        P: Provider + Send + Sync,
+    {
+        let nonce = match nonce {
+            Some(nonce) => nonce,
+            None => provider
+                .get_nonce(
+                    BlockId::Tag(BlockTag::Pending),
+                    transaction.sender_address(),
+                )
+                .await
+                .map_err(provider_error_mapper)?,
+        };
+
+        let fee = match fee_setting {
+            FeeSetting::Eth(EthFeeSetting::Manual(max_fee)) => {
+                TransactionFee::Eth { max_fee: *max_fee }
+            }
+            FeeSetting::Eth(fee_setting) => {
+                let query = UnsignedTransaction::new(
+                    transaction.clone(),
+                    nonce,
+                    TransactionFee::Eth {
+                        max_fee: FieldElement::ZERO,
+                    },
+                )?;
+                let estimate = query.estimate_fee(provider, num_signatures).await?;
+
+                TransactionFee::Eth {
+                    max_fee: fee_setting.apply_multiplier(estimate.overall_fee, default_multiplier),
+                }
+            }
+            FeeSetting::Strk(fee_setting) => {
+                let estimate = if fee_setting.needs_estimate() {
+                    let query = UnsignedTransaction::new(
+                        transaction.clone(),
+                        nonce,
+                        TransactionFee::from(&V3Fee::default()),
+                    )?;
+                    Some(query.estimate_fee(provider, num_signatures).await?)
+                } else {
+                    None
+                };
+
+                TransactionFee::from(&fee_setting.resolve(estimate.as_ref(), default_multiplier)?)
+            }
+        };
+
+        Ok(Self {
+            chain_id,
+            transaction: UnsignedTransaction::new(transaction, nonce, fee)?,
+            signature: None,
+        })
+    }
+
+    /// Builds, signs, and sends a transaction with fees paid in ETH from an account whose guardian
+    /// co-signs. `SingleOwnerAccount` only produces owner signatures, so such transactions are
+    /// built and signed the same way offline transactions are instead. Returns the transaction
+    /// hash.
+    pub async fn send_co_signed<P>(
+        provider: &P,
+        account: &ResolvedAccount,
+        nonce: Option<FieldElement>,
+        transaction: V3Transaction,
+        fee_setting: EthFeeSetting,
+        default_multiplier: f64,
+        simulate: bool,
+    ) -> Result<FieldElement>
+    where
+        P: Provider + Send + Sync,
+    {
+        if simulate || matches!(fee_setting, EthFeeSetting::EstimateOnly) {
+            anyhow::bail!(
+                "--simulate and --estimate-only are not supported when co-signing with the \
+                guardian. Use --fee-token strk instead"
+            );
+        }
+
+        let mut transaction_file = Self::build(
+            provider,
+            account.chain_id,
+            nonce,
+            transaction,
+            &FeeSetting::Eth(fee_setting),
+            default_multiplier,
+            if account.guardian.is_some() { 2 } else { 1 },
+        )
+        .await?;
+        transaction_file.signature = Some(
+            account
+                .sign_transaction_hash(transaction_file.transaction_hash()?)
+                .await?,
+        );
+
+        transaction_file.send(provider).await
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let file = std::fs::File::open(path)?;
+        serde_json::from_reader(file)
+            .map_err(|err| anyhow::anyhow!("invalid transaction file: {}", err))
+    }
+
+    /// Prints the file content to stdout so that it can be redirected to a file. In JSON mode,
+    /// the content is recorded as the `transaction_file` field of the output document instead.
+    pub fn print(&self) -> Result<()> {
+        if is_json_output() {
+            record("transaction_file", self);
+        } else {
+            println!("{}", serde_json::to_string_pretty(self)?);
+        }
+        Ok(())
+    }
+
+    pub fn transaction_hash(&self) -> Result<FieldElement> {
+        self.transaction.transaction_hash(self.chain_id, false)
+    }
+
+    /// Broadcasts the signed transaction, returning the transaction hash.
+    pub async fn send<P>(&self, provider: &P) -> Result<FieldElement>
+    where
+        P: Provider + Send + Sync,
+    {
+        let signature = self.signature.clone().ok_or_else(|| {
+            anyhow::anyhow!("transaction not signed. Sign with `starkli tx sign`")
+        })?;
+
+        let transaction_hash = match self.transaction.to_broadcasted(signature, false)? {
+            BroadcastedTransaction::Invoke(tx) => {
+                provider
+                    .add_invoke_transaction(tx)
+                    .await
+                    .map_err(provider_error_mapper)?
+                    .transaction_hash
+            }
+            BroadcastedTransaction::Declare(tx) => {
+                provider
+                    .add_declare_transaction(tx)
+                    .await
+                    .map_err(provider_error_mapper)?
+                    .transaction_hash
+            }
+            BroadcastedTransaction::DeployAccount(_) => {
+                anyhow::bail!("unexpected account deployment transaction")
+            }
+        };
+
+        Ok(transaction_hash)
+    }
+}
+
+impl UnsignedTransaction {
+    fn new(transaction: V3Transaction, nonce: FieldElement, fee: TransactionFee) -> Result<Self> {
+        match transaction {
+            V3Transaction::Invoke {
+                sender_address,
+                calldata,
+            } => Ok(Self::Invoke {
+                sender_address,
+                nonce,
+                fee,
+                calldata,
+            }),
+            V3Transaction::Declare {
+                sender_address,
+                contract_class,
+                compiled_class_hash,
+            } => Ok(Self::Declare {
+                sender_address,
+                nonce,
+                fee,
+                compiled_class_hash,
+                contract_class: contract_class.as_ref().clone(),
+            }),
+            V3Transaction::DeployAccount { .. } => {
+                anyhow::bail!("account deployment transactions cannot be built for offline signing")
+            }
+        }
+    }
+
+    pub fn sender_address(&self) -> FieldElement {
+        match self {
+            Self::Invoke { sender_address, .. } | Self::Declare { sender_address, .. } => {
+                *sender_address
+            }
+        }
+    }
+
+    pub fn nonce(&self) -> FieldElement {
+        match self {
+            Self::Invoke { nonce, .. } | Self::Declare { nonce, .. } => *nonce,
+        }
+    }
+
+    pub fn fee(&self) -> &TransactionFee {
+        match self {
+            Self::Invoke { fee, .. } | Self::Declare { fee, .. } => fee,
+        }
+    }
+
+    pub fn transaction_hash(&self, chain_id: FieldElement, is_query: bool) -> Result<FieldElement> {
+        match self.fee() {
+            TransactionFee::Eth { max_fee } => {
+                Ok(self.legacy_transaction_hash(chain_id, *max_fee, is_query))
+            }
+            TransactionFee::Strk { .. } => Ok(self.to_v3().transaction_hash(
+                chain_id,
+                self.nonce(),
+                &self.fee().to_v3()?,
+                is_query,
+            )),
+        }
+    }
+
+    pub fn to_broadcasted(
+        &self,
+        signature: Vec<FieldElement>,
+        is_query: bool,
+    ) -> Result<BroadcastedTransaction> {
+        let max_fee = match self.fee() {
+            TransactionFee::Eth { max_fee } => *max_fee,
+            TransactionFee::Strk { .. } => {
+                return Ok(self.to_v3().to_broadcasted(
+                    self.nonce(),
+                    &self.fee().to_v3()?,
+                    signature,
+                    is_query,
+                ))
+            }
+        };
+
+        Ok(match self {
+            Self::Invoke {
+                sender_address,
+                nonce,
+                calldata,
+                ..
+            } => BroadcastedTransaction::Invoke(BroadcastedInvokeTransaction::V1(
+                BroadcastedInvokeTransactionV1 {
+                    sender_address: *sender_address,
+                    calldata: calldata.clone(),
+                    max_fee,
+                    signature,
+                    nonce: *nonce,
+                    is_query,
+                },
+            )),
+            Self::Declare {
+                sender_address,
+                nonce,
+                compiled_class_hash,
+                contract_class,
+                ..
+            } => BroadcastedTransaction::Declare(BroadcastedDeclareTransaction::V2(
+                BroadcastedDeclareTransactionV2 {
+                    sender_address: *sender_address,
+                    compiled_class_hash: *compiled_class_hash,
+                    max_fee,
+                    signature,
+                    nonce: *nonce,
+                    contract_class: Arc::new(contract_class.clone()),
+                    is_query,
+                },
+            )),
+        })
+    }
+
+    async fn estimate_fee<P>(&self, provider: &P, num_signatures: usize) -> Result<FeeEstimate>
+    where
+        P: Provider + Send + Sync,
+    {
+        // Validation is skipped as the transaction is not signed
+        let transaction = self.to_broadcasted(vec![], true)?;
+
+        let mut estimate = provider
+            .estimate_fee(
+                [transaction],
+                [SimulationFlagForEstimateFee::SkipValidate],
+                BlockId::Tag(BlockTag::Pending),
+            )
+            .await
+            .map_err(provider_error_mapper)?
+            .pop()
+            .ok_or_else(|| anyhow::anyhow!("no fee estimate returned"))?;
+
+        // Without the buffer, the fee would be underestimated by the cost of validation
+        let validation_gas =
+            FieldElement::from(VALIDATION_GAS_PER_SIGNATURE * num_signatures as u64);
+        estimate.gas_consumed += validation_gas;
+        estimate.overall_fee += validation_gas * estimate.gas_price;
+
+        Ok(estimate)
+    }
+
+    fn legacy_transaction_hash(
+        &self,
+        chain_id: FieldElement,
+        max_fee: FieldElement,
+        is_query: bool,
+    ) -> FieldElement {
+        let query_offset = if is_query {
+            QUERY_VERSION_OFFSET
+        } else {
+            FieldElement::ZERO
+        };
+
+        match self {
+            Self::Invoke {
+                sender_address,
+                nonce,
+                calldata,
+                ..
+            } => compute_hash_on_elements(&[
+                PREFIX_INVOKE,
+                FieldElement::ONE + query_offset,
+                *sender_address,
+                FieldElement::ZERO, // entry_point_selector
+                compute_hash_on_elements(calldata),
+                max_fee,
+                chain_id,
+                *nonce,
+            ]),
+            Self::Declare {
+                sender_address,
+                nonce,
+                compiled_class_hash,
+                contract_class,
+                ..
+            } => compute_hash_on_elements(&[
+                PREFIX_DECLARE,
+                FieldElement::TWO + query_offset,
+                *sender_address,
+                FieldElement::ZERO, // entry_point_selector
+                compute_hash_on_elements(&[contract_class.class_hash()]),
+                max_fee,
+                chain_id,
+                *nonce,
+                *compiled_class_hash,
+            ]),
+        }
+    }
+
+    fn to_v3(&self) -> V3Transaction {
+        match self {
+            Self::Invoke {
+                sender_address,
+                calldata,
+                ..
+            } => V3Transaction::Invoke {
+                sender_address: *sender_address,
+                calldata: calldata.clone(),
+            },
+            Self::Declare {
+                sender_address,
+                compiled_class_hash,
+                contract_class,
+                ..
+            } => V3Transaction::Declare {
+                sender_address: *sender_address,
+                contract_class: Arc::new(contract_class.clone()),
+                compiled_class_hash: *compiled_class_hash,
+            },
+        }
+    }
+}
+
+impl TransactionFee {
+    /// The maximum amount of fee the transaction can be charged, in Wei or Fri.
+    pub fn max_fee(&self) -> Result<FieldElement> {
+        match self {
+            Self::Eth { max_fee } => Ok(*max_fee),
+            Self::Strk { .. } => Ok(self.to_v3()?.max_fee()),
+        }
+    }
+
+    fn to_v3(&self) -> Result<V3Fee> {
+        match self {
+            Self::Eth { .. } => anyhow::bail!("not a V3 transaction fee"),
+            Self::Strk {
+                l1_gas,
+                l1_gas_price,
+                tip,
+            } => Ok(V3Fee {
+                l1_gas: *l1_gas,
+                l1_gas_price: felt_to_u128(*l1_gas_price)?,
+                tip: *tip,
+            }),
+        }
+    }
+}
+
+impl From<&V3Fee> for TransactionFee {
+    fn from(value: &V3Fee) -> Self {
+        Self::Strk {
+            l1_gas: value.l1_gas,
+            l1_gas_price: u128_to_felt(value.l1_gas_price),
+            tip: value.tip,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet::{
+        core::types::EntryPointsByType,
+        macros::{selector, short_string},
+    };
+
+    use super::*;
+
+    const CHAIN_ID: FieldElement = short_string!("SN_SEPOLIA");
+    const SENDER_ADDRESS: FieldElement =
+        felt!("0x04a9f57d1e0c2dd7e5f5c4ab8ea1f4f1a8b1bd5c20e5a0a0b3e33d6e7e1b1b2c");
+    const STRK_ADDRESS: FieldElement =
+        felt!("0x04718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d");
+    const MAX_FEE: FieldElement = felt!("0x2386f26fc10000");
+
+    // Expected hashes are computed independently from the transaction hash specification.
+
+    #[test]
+    fn test_invoke_v1_transaction_hash() {
+        let transaction = UnsignedTransaction::Invoke {
+            sender_address: SENDER_ADDRESS,
+            nonce: felt!("5"),
+            fee: TransactionFee::Eth { max_fee: MAX_FEE },
+            calldata: vec![
+                FieldElement::ONE,
+                STRK_ADDRESS,
+                selector!("transfer"),
+                felt!("3"),
+                felt!("0x1234"),
+                felt!("100"),
+                FieldElement::ZERO,
+            ],
+        };
+
+        assert_eq!(
+            transaction.transaction_hash(CHAIN_ID, false).unwrap(),
+            felt!("0x492630760dee624f05989a223f1aca0ad0eb426939c02fb9b4da83e4e315e5c")
+        );
+        assert_eq!(
+            transaction.transaction_hash(CHAIN_ID, true).unwrap(),
+            felt!("0x69c60e31ffc24a40a47ccd49311c57535e3872ec41a537137fa39e0178db4df")
+        );
+    }
+
+    #[test]
+    fn test_declare_v2_transaction_hash() {
+        let transaction = UnsignedTransaction::Declare {
+            sender_address: SENDER_ADDRESS,
+            nonce: felt!("5"),
+            fee: TransactionFee::Eth { max_fee: MAX_FEE },
+            compiled_class_hash: felt!("0x5678"),
+            contract_class: FlattenedSierraClass {
+                sierra_program: vec![felt!("1"), felt!("2"), felt!("3")],
+                contract_class_version: String::from("0.1.0"),
+                entry_points_by_type: EntryPointsByType {
+                    constructor: vec![],
+                    external: vec![],
+                    l1_handler: vec![],
+                },
+                abi: String::new(),
+            },
+        };
+
+        assert_eq!(
+            transaction.transaction_hash(CHAIN_ID, false).unwrap(),
+            felt!("0x756d8b13d3fc5d9ec4d8006fbdb2b94f9954f5818825f5b6ed48273e32df51a")
+        );
+    }
+
+    #[test]
+    fn test_strk_fee_uses_v3_hash() {
+        let transaction = UnsignedTransaction::Invoke {
+            sender_address: SENDER_ADDRESS,
+            nonce: felt!("5"),
+            fee: TransactionFee::Strk {
+                l1_gas: 0x1234,
+                l1_gas_price: felt!("0x5678"),
+                tip: 0,
+            },
+            calldata: vec![
+                FieldElement::ONE,
+                STRK_ADDRESS,
+                selector!("transfer"),
+                felt!("3"),
+                felt!("0x1234"),
+                felt!("100"),
+                FieldElement::ZERO,
+            ],
+        };
+
+        assert_eq!(
+            transaction.transaction_hash(CHAIN_ID, false).unwrap(),
+            felt!("0x12645c8c3055f15981300d4ee408fd9e92be98c166143d49976521bd13bb8a6")
+        );
+    }
+}
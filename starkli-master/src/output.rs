@@ -0,0 +1,83 @@
+//! Output handling for the global `--json` flag. In JSON mode, commands record their results into
+//! a single document that gets printed to stdout when the command finishes, instead of printing
+//! text. Messages on stderr are not affected.
+
+use std::{
+    fmt::Display,
+    sync::{Mutex, OnceLock},
+};
+
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use starknet::core::types::FieldElement;
+
+static JSON_OUTPUT: OnceLock<bool> = OnceLock::new();
+static DOCUMENT: Mutex<Vec<(String, Value)>> = Mutex::new(Vec::new());
+
+/// Enables JSON mode for the rest of the process. Only the first call takes effect.
+pub fn set_json_output(enabled: bool) {
+    let _ = JSON_OUTPUT.set(enabled);
+}
+
+pub fn is_json_output() -> bool {
+    JSON_OUTPUT.get().copied().unwrap_or(false)
+}
+
+/// Prints `text` to stdout, or records it as a string under `key` in JSON mode.
+pub fn print_text<T>(key: &str, text: T)
+where
+    T: Display,
+{
+    if is_json_output() {
+        record(key, text.to_string());
+    } else {
+        println!("{}", text);
+    }
+}
+
+/// Records a value under `key` in the JSON document, replacing any previous value. Does nothing
+/// outside of JSON mode, so it can be used for information that's only shown on stderr as text.
+pub fn record<T>(key: &str, value: T)
+where
+    T: Serialize,
+{
+    if !is_json_output() {
+        return;
+    }
+
+    // Values that fail to serialize are recorded as `null` instead of aborting the command
+    let value = serde_json::to_value(value).unwrap_or(Value::Null);
+
+    let mut document = DOCUMENT.lock().unwrap();
+    match document.iter_mut().find(|(existing, _)| existing == key) {
+        Some((_, existing)) => *existing = value,
+        None => document.push((key.to_owned(), value)),
+    }
+}
+
+/// Records the hash of a transaction that has been sent. The status gets updated if the
+/// transaction is later watched until confirmation.
+pub fn record_transaction(transaction_hash: FieldElement) {
+    record("transaction_hash", format!("{:#064x}", transaction_hash));
+    record("status", "SUBMITTED");
+}
+
+/// Prints the JSON document along with the error, if any, to stdout.
+pub fn print_document(result: &Result<()>) {
+    let mut document = DOCUMENT
+        .lock()
+        .unwrap()
+        .drain(..)
+        .collect::<Map<String, Value>>();
+
+    if let Err(err) = result {
+        document.insert(String::from("error"), Value::String(format!("{err}")));
+    }
+
+    // Serializing a `Value` never fails
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&Value::Object(document)).unwrap()
+    );
+}
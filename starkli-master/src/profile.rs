@@ -2,31 +2,76 @@ use std::{
     fmt::Display,
     io::{Read, Write},
     path::PathBuf,
+    sync::OnceLock,
+    time::Duration,
 };
 
 use anyhow::Result;
 use etcetera::{choose_base_strategy, BaseStrategy};
 use indexmap::IndexMap;
 use serde::{de::Visitor, Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
 use starknet::core::{
+    serde::unsigned_field_element::UfeHex,
     types::FieldElement,
     utils::{cairo_short_string_to_felt, parse_cairo_short_string},
 };
 use url::Url;
 
+use crate::compiler::CompilerVersion;
+
 pub(crate) const DEFAULT_PROFILE_NAME: &str = "default";
 
+/// Transaction poll interval in milliseconds when not configured anywhere.
+const DEFAULT_POLL_INTERVAL: u64 = 5000;
+
+/// Top-level key in the profiles file for choosing the profile to use by default. Profiles cannot
+/// be named after this key.
+const DEFAULT_PROFILE_KEY: &str = "default_profile";
+
+/// Profile selected via `--profile` or `STARKLI_PROFILE` for the current process.
+static PROFILE_OVERRIDE: OnceLock<String> = OnceLock::new();
+
 #[derive(Debug, Default)]
 pub struct Profiles {
+    /// Profile to use when none is explicitly selected. Falls back to [DEFAULT_PROFILE_NAME].
+    pub default_profile: Option<String>,
     pub profiles: IndexMap<String, Profile>,
 }
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Profile {
+    #[serde(default, skip_serializing_if = "ProfileDefaults::is_empty")]
+    pub defaults: ProfileDefaults,
+    #[serde(default)]
     pub networks: IndexMap<String, Network>,
 }
 
+/// Values used by commands when the corresponding options are not supplied from the command line
+/// or environment variables.
+#[serde_as]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileDefaults {
+    /// Path to the account config file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+    /// Path to the keystore file used as signer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keystore: Option<String>,
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compiler_version: Option<CompilerVersion>,
+    /// Transaction poll interval in milliseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub poll_interval: Option<u64>,
+    /// Multiplier applied to estimated fees to get the max fee.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_multiplier: Option<f64>,
+}
+
+#[serde_as]
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Network {
@@ -40,6 +85,10 @@ pub struct Network {
     #[serde(default, skip_serializing_if = "is_false")]
     pub is_integration: bool,
     pub provider: NetworkProvider,
+    /// Address of the Universal Deployer Contract, for networks with their own deployer.
+    #[serde_as(as = "Option<UfeHex>")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub udc: Option<FieldElement>,
 }
 
 #[derive(Debug)]
@@ -72,17 +121,67 @@ impl Profiles {
             Self::default()
         };
 
-        // Custom profile to be supported in the future
-        if loaded_profiles.profiles.len() > 1
-            || (loaded_profiles.profiles.len() == 1
-                && !loaded_profiles.profiles.contains_key(DEFAULT_PROFILE_NAME))
+        Ok(loaded_profiles)
+    }
+
+    /// Sets the profile to use for the rest of the process, overriding the default profile. Only
+    /// the first call takes effect.
+    pub fn set_profile_override(name: String) {
+        let _ = PROFILE_OVERRIDE.set(name);
+    }
+
+    /// Name of the profile in effect, which is the one explicitly selected via `--profile` or
+    /// `STARKLI_PROFILE` if any, or otherwise the default profile.
+    pub fn active_profile_name(&self) -> &str {
+        match PROFILE_OVERRIDE.get() {
+            Some(name) => name,
+            None => self
+                .default_profile
+                .as_deref()
+                .unwrap_or(DEFAULT_PROFILE_NAME),
+        }
+    }
+
+    pub fn get_profile(&self, name: &str) -> Result<&Profile> {
+        self.profiles
+            .get(name)
+            .ok_or_else(|| profile_not_found_error(name))
+    }
+
+    pub fn get_profile_mut(&mut self, name: &str) -> Result<&mut Profile> {
+        self.profiles
+            .get_mut(name)
+            .ok_or_else(|| profile_not_found_error(name))
+    }
+
+    /// Gets the active profile for modification. The `default` profile is created on demand, while
+    /// any other profile must have been created explicitly.
+    pub fn active_profile_mut(&mut self) -> Result<&mut Profile> {
+        let name = self.active_profile_name().to_owned();
+
+        if name == DEFAULT_PROFILE_NAME {
+            Ok(self.profiles.entry(name).or_default())
+        } else {
+            self.get_profile_mut(&name)
+        }
+    }
+
+    pub fn validate_profile_name(name: &str) -> Result<()> {
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
         {
             anyhow::bail!(
-                "invalid profiles: only the `default` profile is supported at the moment"
+                "invalid profile name `{}`: only alphanumeric characters, `-` and `_` are allowed",
+                name
             );
         }
+        if name == DEFAULT_PROFILE_KEY {
+            anyhow::bail!("`{}` is a reserved name", name);
+        }
 
-        Ok(loaded_profiles)
+        Ok(())
     }
 
     pub fn save(&self) -> Result<()> {
@@ -101,7 +200,7 @@ impl Profiles {
         Ok(())
     }
 
-    fn get_config_folder() -> Result<PathBuf> {
+    pub(crate) fn get_config_folder() -> Result<PathBuf> {
         let strategy = choose_base_strategy()
             .map_err(|_| anyhow::anyhow!("unable to find the config directory"))?;
         let mut path = strategy.config_dir();
@@ -116,16 +215,57 @@ impl Profiles {
     }
 }
 
+impl ProfileDefaults {
+    /// Loads the defaults of the active profile. Empty defaults are returned if the profile does not
+    /// exist yet.
+    pub fn load() -> Result<Self> {
+        let profiles = Profiles::load()?;
+
+        Ok(profiles
+            .profiles
+            .get(profiles.active_profile_name())
+            .map(|profile| profile.defaults.clone())
+            .unwrap_or_default())
+    }
+
+    /// Resolves the transaction poll interval, with the value from the command line or environment
+    /// variable taking precedence over the profile default.
+    pub fn resolve_poll_interval(value: Option<u64>) -> Result<Duration> {
+        let poll_interval = match value {
+            Some(value) => value,
+            None => Self::load()?.poll_interval.unwrap_or(DEFAULT_POLL_INTERVAL),
+        };
+
+        Ok(Duration::from_millis(poll_interval))
+    }
+
+    fn is_empty(&self) -> bool {
+        self.account.is_none()
+            && self.keystore.is_none()
+            && self.compiler_version.is_none()
+            && self.poll_interval.is_none()
+            && self.fee_multiplier.is_none()
+    }
+}
+
 impl Serialize for Profiles {
     fn serialize<S>(&self, serializer: S) -> std::prelude::v1::Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
         #[derive(Serialize)]
-        #[serde(transparent)]
-        struct Transparent<'a>(&'a IndexMap<String, Profile>);
+        struct Raw<'a> {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            default_profile: Option<&'a str>,
+            #[serde(flatten)]
+            profiles: &'a IndexMap<String, Profile>,
+        }
 
-        Transparent(&self.profiles).serialize(serializer)
+        Raw {
+            default_profile: self.default_profile.as_deref(),
+            profiles: &self.profiles,
+        }
+        .serialize(serializer)
     }
 }
 
@@ -134,12 +274,20 @@ impl<'de> Deserialize<'de> for Profiles {
     where
         D: serde::Deserializer<'de>,
     {
+        // Unknown fields are still rejected as all remaining keys must be valid profiles
         #[derive(Deserialize)]
-        #[serde(deny_unknown_fields, transparent)]
-        struct Transparent(IndexMap<String, Profile>);
+        struct Raw {
+            #[serde(default)]
+            default_profile: Option<String>,
+            #[serde(flatten)]
+            profiles: IndexMap<String, Profile>,
+        }
+
+        let raw = Raw::deserialize(deserializer)?;
 
         Ok(Self {
-            profiles: Transparent::deserialize(deserializer)?.0,
+            default_profile: raw.default_profile,
+            profiles: raw.profiles,
         })
     }
 }
@@ -283,6 +431,14 @@ where
     deserializer.deserialize_str(UrlVisitor)
 }
 
+fn profile_not_found_error(name: &str) -> anyhow::Error {
+    anyhow::anyhow!(
+        "profile `{}` not found. Use `starkli profile create {}` to create it",
+        name,
+        name
+    )
+}
+
 fn is_false(value: &bool) -> bool {
     value == &false
 }
@@ -4,7 +4,6 @@ use anyhow::Result;
 use async_trait::async_trait;
 use clap::Parser;
 use colored::Colorize;
-use indexmap::map::Entry;
 use rand::{rngs::StdRng, Rng, SeedableRng};
 use starknet::{
     core::types::*,
@@ -15,7 +14,7 @@ use url::Url;
 
 use crate::{
     network::Network,
-    profile::{FreeProviderVendor, NetworkProvider, Profile, Profiles, DEFAULT_PROFILE_NAME},
+    profile::{FreeProviderVendor, NetworkProvider, Profiles},
 };
 
 const CHAIN_ID_MAINNET: FieldElement = short_string!("SN_MAIN");
@@ -41,6 +40,8 @@ pub struct ProviderArgs {
 pub struct ExtendedProvider {
     provider: AnyProvider,
     is_integration: bool,
+    /// UDC address configured for the network in the active profile.
+    udc_address: Option<FieldElement>,
 }
 
 impl ProviderArgs {
@@ -86,18 +87,13 @@ impl ProviderArgs {
         // We save the profiles only when changes are made
         let mut made_changes = false;
 
-        // The only profile supported right now is the `default` profile. We create it if it
-        // doesn't exist.
-        let matched_profile = match profiles.profiles.entry(DEFAULT_PROFILE_NAME.to_owned()) {
-            Entry::Occupied(entry) => entry.into_mut(),
-            Entry::Vacant(entry) => {
-                made_changes = true;
-
-                entry.insert(Profile {
-                    networks: Default::default(),
-                })
-            }
-        };
+        // The `default` profile is created if it doesn't exist, while other profiles must be
+        // created explicitly.
+        let profile_name = profiles.active_profile_name().to_owned();
+        if !profiles.profiles.contains_key(&profile_name) {
+            made_changes = true;
+        }
+        let matched_profile = profiles.active_profile_mut()?;
 
         let matched_network = match matched_profile.networks.get(network) {
             Some(network) => {
@@ -159,6 +155,7 @@ impl ProviderArgs {
                                         provider: NetworkProvider::Free(choose_vendor(
                                             &builtin_network,
                                         )),
+                                        udc: None,
                                     },
                                     Network::Goerli => crate::profile::Network {
                                         name: Some("Starknet Goerli Testnet".into()),
@@ -167,6 +164,7 @@ impl ProviderArgs {
                                         provider: NetworkProvider::Free(choose_vendor(
                                             &builtin_network,
                                         )),
+                                        udc: None,
                                     },
                                     Network::Sepolia => crate::profile::Network {
                                         name: Some("Starknet Sepolia Testnet".into()),
@@ -175,6 +173,7 @@ impl ProviderArgs {
                                         provider: NetworkProvider::Free(choose_vendor(
                                             &builtin_network,
                                         )),
+                                        udc: None,
                                     },
                                     Network::GoerliIntegration | Network::SepoliaIntegration => {
                                         anyhow::bail!(
@@ -197,9 +196,10 @@ impl ProviderArgs {
                     }
                     Err(_) => {
                         anyhow::bail!(
-                            "network `{}` is not configured in the active profile, and it's not a \
-                            well-known network",
-                            network
+                            "network `{}` is not configured in the active profile `{}`, and it's \
+                            not a well-known network",
+                            network,
+                            profile_name
                         );
                     }
                 }
@@ -254,7 +254,8 @@ impl ProviderArgs {
         let provider = ExtendedProvider::new(
             AnyProvider::JsonRpcHttp(JsonRpcClient::new(HttpTransport::new(rpc_url))),
             matched_network.is_integration,
-        );
+        )
+        .with_udc_address(matched_network.udc);
 
         if made_changes {
             profiles.save()?;
@@ -269,9 +270,15 @@ impl ExtendedProvider {
         Self {
             provider,
             is_integration,
+            udc_address: None,
         }
     }
 
+    pub fn with_udc_address(mut self, udc_address: Option<FieldElement>) -> Self {
+        self.udc_address = udc_address;
+        self
+    }
+
     pub fn is_rpc(&self) -> bool {
         matches!(self.provider, AnyProvider::JsonRpcHttp(_))
     }
@@ -279,6 +286,10 @@ impl ExtendedProvider {
     pub fn is_integration(&self) -> bool {
         self.is_integration
     }
+
+    pub fn udc_address(&self) -> Option<FieldElement> {
+        self.udc_address
+    }
 }
 
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
@@ -0,0 +1,142 @@
+//! Locating contract artifacts built by Scarb, so that contracts can be declared by name instead of
+//! by artifact path.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde::Deserialize;
+
+/// Profile Scarb builds with when none is specified.
+pub const DEFAULT_SCARB_PROFILE: &str = "dev";
+
+const ARTIFACTS_FILE_SUFFIX: &str = ".starknet_artifacts.json";
+
+/// A contract built by the `starknet-contract` target of a Scarb package.
+#[derive(Debug)]
+pub struct ScarbContract {
+    pub package_name: String,
+    pub contract_name: String,
+    pub module_path: String,
+    /// Path to the `.contract_class.json` Sierra artifact.
+    pub sierra_path: PathBuf,
+    /// Path to the `.compiled_contract_class.json` CASM artifact, which only exists when CASM
+    /// output is enabled for the target.
+    pub casm_path: Option<PathBuf>,
+}
+
+/// Content of a `<package>.starknet_artifacts.json` file.
+#[derive(Deserialize)]
+struct ArtifactsFile {
+    contracts: Vec<ArtifactsFileContract>,
+}
+
+#[derive(Deserialize)]
+struct ArtifactsFileContract {
+    package_name: String,
+    contract_name: String,
+    module_path: String,
+    artifacts: ArtifactsFileContractArtifacts,
+}
+
+#[derive(Deserialize)]
+struct ArtifactsFileContractArtifacts {
+    sierra: Option<PathBuf>,
+    casm: Option<PathBuf>,
+}
+
+impl ScarbContract {
+    /// Loads all contracts built for the project or workspace at `manifest_path` under `profile`.
+    /// Artifacts are looked up in `SCARB_TARGET_DIR` if set, or otherwise the closest `target`
+    /// folder, since packages in a workspace share the target folder at the workspace root.
+    pub fn load_all(manifest_path: &Path, profile: &str) -> Result<Vec<Self>> {
+        if !manifest_path.exists() {
+            anyhow::bail!("Scarb manifest not found: {}", manifest_path.display());
+        }
+
+        let target_dir = match std::env::var_os("SCARB_TARGET_DIR") {
+            Some(target_dir) => PathBuf::from(target_dir),
+            None => {
+                let manifest_path = std::fs::canonicalize(manifest_path)?;
+                manifest_path
+                    .ancestors()
+                    .skip(1)
+                    .map(|dir| dir.join("target"))
+                    .find(|target_dir| target_dir.join(profile).is_dir())
+                    .ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Scarb build output for profile `{}` not found. Run `scarb build` first",
+                            profile
+                        )
+                    })?
+            }
+        };
+        let profile_dir = target_dir.join(profile);
+
+        let mut contracts = vec![];
+        for entry in std::fs::read_dir(&profile_dir)? {
+            let path = entry?.path();
+            let is_artifacts_file = path
+                .file_name()
+                .and_then(|file_name| file_name.to_str())
+                .is_some_and(|file_name| file_name.ends_with(ARTIFACTS_FILE_SUFFIX));
+            if !is_artifacts_file {
+                continue;
+            }
+
+            let artifacts_file: ArtifactsFile =
+                serde_json::from_reader(std::fs::File::open(&path)?).map_err(|err| {
+                    anyhow::anyhow!("invalid Scarb artifacts file {}: {}", path.display(), err)
+                })?;
+
+            for contract in artifacts_file.contracts.into_iter() {
+                let sierra = contract.artifacts.sierra.ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Sierra output is not enabled for contract `{}`",
+                        contract.module_path
+                    )
+                })?;
+
+                contracts.push(Self {
+                    package_name: contract.package_name,
+                    contract_name: contract.contract_name,
+                    module_path: contract.module_path,
+                    sierra_path: profile_dir.join(sierra),
+                    casm_path: contract.artifacts.casm.map(|casm| profile_dir.join(casm)),
+                });
+            }
+        }
+
+        if contracts.is_empty() {
+            anyhow::bail!(
+                "no contract artifacts found in {}. Make sure the `starknet-contract` target is \
+                enabled in Scarb.toml",
+                profile_dir.display()
+            );
+        }
+
+        Ok(contracts)
+    }
+
+    /// Picks the contract matching `name`, which can either be the contract name or the full
+    /// module path when contract names are ambiguous across packages.
+    pub fn find(contracts: Vec<Self>, name: &str) -> Result<Self> {
+        let mut matches = contracts
+            .into_iter()
+            .filter(|contract| contract.contract_name == name || contract.module_path == name)
+            .collect::<Vec<_>>();
+
+        match matches.len() {
+            0 => anyhow::bail!("contract `{}` not found in Scarb artifacts", name),
+            1 => Ok(matches.remove(0)),
+            _ => anyhow::bail!(
+                "contract name `{}` is ambiguous. Use the full module path instead: {}",
+                name,
+                matches
+                    .iter()
+                    .map(|contract| contract.module_path.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
@@ -9,21 +9,29 @@ use starknet::{
     signers::{LocalWallet, Signer, SigningKey, VerifyingKey},
 };
 
+use crate::{
+    ledger::{open_device, DerivationPath, LedgerError, LedgerSigner},
+    profile::ProfileDefaults,
+};
+
 #[derive(Debug)]
 pub enum AnySigner {
     LocalWallet(LocalWallet),
+    Ledger(LedgerSigner),
 }
 
 #[derive(Debug, thiserror::Error)]
 #[error(transparent)]
 pub enum AnySignerGetPublicKeyError {
     LocalWallet(<LocalWallet as Signer>::GetPublicKeyError),
+    Ledger(LedgerError),
 }
 
 #[derive(Debug, thiserror::Error)]
 #[error(transparent)]
 pub enum AnySignerSignError {
     LocalWallet(<LocalWallet as Signer>::SignError),
+    Ledger(LedgerError),
 }
 
 #[derive(Debug, Clone, Parser)]
@@ -37,13 +45,19 @@ pub struct SignerArgs {
     keystore_password: Option<String>,
     #[clap(long, help = private_key_help())]
     private_key: Option<String>,
+    #[clap(
+        long,
+        help = "Use the Starknet app on a Ledger device with the EIP-2645 derivation path (e.g. \
+        m/2645'/starknet'/starkli'/0'/0'/0)"
+    )]
+    ledger_path: Option<String>,
 }
 
 #[derive(Debug)]
 pub enum SignerResolutionTask {
     /// The user explicitly requested to use a signer, usually from the command line.
     Strong(SignerResolutionTaskContent),
-    /// The signer comes from a global default or environment variable.
+    /// The signer comes from a global default, environment variable, or profile default.
     Weak(SignerResolutionTaskContent),
     /// No signer option is provided at all.
     None,
@@ -53,6 +67,7 @@ pub enum SignerResolutionTask {
 pub enum SignerResolutionTaskContent {
     Keystore(KeystoreTaskContent),
     PrivateKey(PrivateKeyTaskContent),
+    Ledger(LedgerTaskContent),
 }
 
 #[derive(Debug)]
@@ -66,9 +81,15 @@ pub struct PrivateKeyTaskContent {
     key: String,
 }
 
+#[derive(Debug)]
+pub struct LedgerTaskContent {
+    path: String,
+}
+
 enum StringValue {
     FromCommandLine(String),
     FromEnvVar(String),
+    FromProfile(String),
 }
 
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
@@ -82,6 +103,9 @@ impl Signer for AnySigner {
             Self::LocalWallet(inner) => Ok(<LocalWallet as Signer>::get_public_key(inner)
                 .await
                 .map_err(Self::GetPublicKeyError::LocalWallet)?),
+            // Talking to the device blocks, so other tasks are moved off this worker thread
+            Self::Ledger(inner) => Ok(tokio::task::block_in_place(|| inner.get_public_key())
+                .map_err(Self::GetPublicKeyError::Ledger)?),
         }
     }
 
@@ -90,11 +114,31 @@ impl Signer for AnySigner {
             Self::LocalWallet(inner) => Ok(<LocalWallet as Signer>::sign_hash(inner, hash)
                 .await
                 .map_err(Self::SignError::LocalWallet)?),
+            Self::Ledger(inner) => {
+                eprintln!("Confirm the signing request on your Ledger device...");
+                Ok(tokio::task::block_in_place(|| inner.sign_hash(hash))
+                    .map_err(Self::SignError::Ledger)?)
+            }
         }
     }
 }
 
 impl SignerArgs {
+    /// Creates signer options from values other than the standard signer options, such as ones
+    /// for a secondary account.
+    pub fn new(
+        keystore: Option<String>,
+        private_key: Option<String>,
+        ledger_path: Option<String>,
+    ) -> Self {
+        Self {
+            keystore,
+            keystore_password: None,
+            private_key,
+            ledger_path,
+        }
+    }
+
     pub fn into_signer(self) -> Result<AnySigner> {
         self.into_task()?.resolve()
     }
@@ -103,6 +147,21 @@ impl SignerArgs {
     /// This method allows callers to defer resolution to a later stage while still performing some
     /// initial validations.
     pub fn into_task(self) -> Result<SignerResolutionTask> {
+        // Ledger paths can only be supplied from the command line, so there's no need to consider
+        // conflicts from env vars or profiles.
+        if let Some(path) = self.ledger_path {
+            if self.keystore.is_some() || self.private_key.is_some() {
+                anyhow::bail!(
+                    "invalid signer option combination. \
+                    Do not mix options of different signer sources."
+                );
+            }
+
+            return Ok(SignerResolutionTask::Strong(
+                SignerResolutionTaskContent::Ledger(LedgerTaskContent { path }),
+            ));
+        }
+
         // We're not using the `env` derive from `clap` because we need to distinguish between
         // whether the value is supplied from the command line or the environment variable.
         //
@@ -124,6 +183,15 @@ impl SignerArgs {
             },
         };
 
+        // The keystore from the active profile has the lowest precedence, and is only used when no
+        // other signer source is supplied at all.
+        let keystore = match (keystore, &private_key) {
+            (None, None) => ProfileDefaults::load()?
+                .keystore
+                .map(StringValue::FromProfile),
+            (keystore, _) => keystore,
+        };
+
         let task = match (keystore, self.keystore_password, private_key) {
             // Options:
             //   Keystore: from command line
@@ -153,17 +221,19 @@ impl SignerArgs {
                 PrivateKeyTaskContent { key: private_key },
             )),
             // Options:
-            //   Keystore: from env var
+            //   Keystore: from env var or profile
             //   Private key: not supplied at all
             // Resolution: use keystore (weak)
-            (Some(StringValue::FromEnvVar(keystore)), keystore_password, None) => {
-                SignerResolutionTask::Weak(SignerResolutionTaskContent::Keystore(
-                    KeystoreTaskContent {
-                        keystore,
-                        keystore_password,
-                    },
-                ))
-            }
+            (
+                Some(StringValue::FromEnvVar(keystore) | StringValue::FromProfile(keystore)),
+                keystore_password,
+                None,
+            ) => SignerResolutionTask::Weak(SignerResolutionTaskContent::Keystore(
+                KeystoreTaskContent {
+                    keystore,
+                    keystore_password,
+                },
+            )),
             // Options:
             //   Keystore: not supplied at all
             //   Private key: from env var
@@ -201,6 +271,7 @@ impl SignerResolutionTask {
             Self::Strong(task) | Self::Weak(task) => match task {
                 SignerResolutionTaskContent::Keystore(inner) => inner.resolve(),
                 SignerResolutionTaskContent::PrivateKey(inner) => inner.resolve(),
+                SignerResolutionTaskContent::Ledger(inner) => inner.resolve(),
             },
             Self::None => Err(anyhow::anyhow!(
                 "no valid signer option provided. \
@@ -252,12 +323,12 @@ impl PrivateKeyTaskContent {
             Err(_) => true,
         };
 
-        // TODO: change to recommend hardware wallets when they become available
         if print_warning {
             eprintln!(
                 "{}",
                 "WARNING: using private key in plain text is highly insecure, and you should \
-                ONLY do this for development. Consider using an encrypted keystore instead. \
+                ONLY do this for development. Consider using an encrypted keystore or a Ledger \
+                hardware wallet instead. \
                 (Check out https://book.starkli.rs/signers on how to suppress this warning)"
                     .bright_magenta()
             );
@@ -270,6 +341,14 @@ impl PrivateKeyTaskContent {
     }
 }
 
+impl LedgerTaskContent {
+    pub fn resolve(self) -> Result<AnySigner> {
+        let path: DerivationPath = self.path.parse()?;
+
+        Ok(AnySigner::Ledger(LedgerSigner::new(open_device()?, path)))
+    }
+}
+
 fn keystore_help() -> String {
     format!(
         "Path to keystore JSON file [env: STARKNET_KEYSTORE={}]",
@@ -0,0 +1,101 @@
+use anyhow::Result;
+use clap::{Parser, ValueEnum};
+use colored::Colorize;
+use starknet::{core::types::FieldElement, macros::selector};
+
+use crate::subcommands::account::argent::AccountCallArgs;
+
+#[derive(Debug, Parser)]
+pub struct TriggerEscape {
+    #[clap(flatten)]
+    call: AccountCallArgs,
+    #[clap(value_enum, help = "The signer to escape (i.e. replace)")]
+    role: EscapeRole,
+    #[clap(help = "Public key of the new signer")]
+    new_signer: FieldElement,
+}
+
+#[derive(Debug, Parser)]
+pub struct Escape {
+    #[clap(flatten)]
+    call: AccountCallArgs,
+    #[clap(value_enum, help = "The signer being escaped")]
+    role: EscapeRole,
+}
+
+#[derive(Debug, Parser)]
+pub struct CancelEscape {
+    #[clap(flatten)]
+    call: AccountCallArgs,
+}
+
+/// Escaping the owner is done by the guardian alone, and vice versa.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum EscapeRole {
+    Owner,
+    Guardian,
+}
+
+impl TriggerEscape {
+    pub async fn run(self) -> Result<()> {
+        let selector = match self.role {
+            EscapeRole::Owner => selector!("trigger_escape_owner"),
+            EscapeRole::Guardian => selector!("trigger_escape_guardian"),
+        };
+
+        if self
+            .call
+            .single_signer()
+            .execute(selector, vec![self.new_signer])
+            .await?
+        {
+            eprintln!(
+                "Escape triggered. Once the security period is over, finalize it by running:\n    {}",
+                format!("starkli account argent escape {}", self.role.name()).bright_yellow()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl Escape {
+    pub async fn run(self) -> Result<()> {
+        let selector = match self.role {
+            EscapeRole::Owner => selector!("escape_owner"),
+            EscapeRole::Guardian => selector!("escape_guardian"),
+        };
+
+        if self.call.single_signer().execute(selector, vec![]).await? {
+            eprintln!(
+                "Escape finalized. Update the account file by running:\n    {}",
+                "starkli account fetch".bright_yellow()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl CancelEscape {
+    pub async fn run(self) -> Result<()> {
+        if self
+            .call
+            .execute(selector!("cancel_escape"), vec![])
+            .await?
+        {
+            eprintln!("Escape cancelled.");
+        }
+
+        Ok(())
+    }
+}
+
+impl EscapeRole {
+    fn name(&self) -> &'static str {
+        match self {
+            EscapeRole::Owner => "owner",
+            EscapeRole::Guardian => "guardian",
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::{core::types::FieldElement, macros::selector};
+
+use crate::subcommands::account::argent::AccountCallArgs;
+
+#[derive(Debug, Parser)]
+pub struct ChangeGuardian {
+    #[clap(flatten)]
+    call: AccountCallArgs,
+    #[clap(help = "Public key of the new guardian")]
+    guardian: FieldElement,
+}
+
+#[derive(Debug, Parser)]
+pub struct RemoveGuardian {
+    #[clap(flatten)]
+    call: AccountCallArgs,
+}
+
+impl ChangeGuardian {
+    pub async fn run(self) -> Result<()> {
+        if self.guardian == FieldElement::ZERO {
+            anyhow::bail!("use `starkli account argent remove-guardian` to remove the guardian");
+        }
+
+        if self
+            .call
+            .execute(selector!("change_guardian"), vec![self.guardian])
+            .await?
+        {
+            eprintln!(
+                "Guardian changed to {}.",
+                format!("{:#064x}", self.guardian).bright_yellow()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl RemoveGuardian {
+    pub async fn run(self) -> Result<()> {
+        // Setting the guardian to zero removes it
+        if self
+            .call
+            .execute(selector!("change_guardian"), vec![FieldElement::ZERO])
+            .await?
+        {
+            eprintln!("Guardian removed.");
+        }
+
+        Ok(())
+    }
+}
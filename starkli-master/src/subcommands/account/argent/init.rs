@@ -3,18 +3,15 @@ use std::{io::Write, path::PathBuf};
 use anyhow::Result;
 use clap::Parser;
 use colored::Colorize;
-use starknet::{
-    core::types::FieldElement,
-    macros::felt,
-    signers::{Signer, SigningKey},
-};
+use starknet::{core::types::FieldElement, macros::felt};
 
 use crate::{
     account::{
         AccountConfig, AccountVariant, ArgentAccountConfig, DeploymentStatus, UndeployedStatus,
     },
+    output::record,
     path::ExpandedPathbufParser,
-    signer::SignerArgs,
+    subcommands::account::init::InitArgs,
 };
 
 /// Official hashes used as of extension version 5.7.0
@@ -23,9 +20,13 @@ const ARGENT_CLASS_HASH: FieldElement =
 
 #[derive(Debug, Parser)]
 pub struct Init {
-    // TODO: allow manually specifying public key without using a signer
     #[clap(flatten)]
-    signer: SignerArgs,
+    init: InitArgs,
+    #[clap(
+        long,
+        help = "Public key of the guardian, which co-signs transactions along with the owner"
+    )]
+    guardian: Option<FieldElement>,
     #[clap(
         long,
         short,
@@ -45,22 +46,19 @@ impl Init {
             anyhow::bail!("account config file already exists");
         }
 
-        let signer = self.signer.into_signer()?;
-
-        // Too lazy to write random salt generation
-        let salt = SigningKey::from_random().secret_scalar();
+        let init = self.init.resolve(ARGENT_CLASS_HASH).await?;
 
         let account_config = AccountConfig {
             version: 1,
             variant: AccountVariant::Argent(ArgentAccountConfig {
                 version: 1,
                 implementation: None,
-                owner: signer.get_public_key().await?.scalar(),
-                guardian: FieldElement::ZERO,
+                owner: init.public_key,
+                guardian: self.guardian.unwrap_or(FieldElement::ZERO),
             }),
             deployment: DeploymentStatus::Undeployed(UndeployedStatus {
-                class_hash: ARGENT_CLASS_HASH,
-                salt,
+                class_hash: init.class_hash,
+                salt: init.salt,
                 context: None,
             }),
         };
@@ -71,6 +69,9 @@ impl Init {
         serde_json::to_writer_pretty(&mut file, &account_config)?;
         file.write_all(b"\n")?;
 
+        record("file", std::fs::canonicalize(&self.output)?);
+        record("address", format!("{:#064x}", deployed_address));
+
         eprintln!(
             "Created new account config file: {}",
             std::fs::canonicalize(&self.output)?.display()
@@ -1,9 +1,23 @@
+use std::sync::Arc;
+
 use anyhow::Result;
 use clap::{Parser, Subcommand};
+use starknet::{accounts::Call, core::types::FieldElement};
+
+use crate::{
+    account::AccountArgs, fee::FeeArgs, subcommands::invoke::ExecutionArgs,
+    verbosity::VerbosityArgs, ProviderArgs,
+};
 
 mod init;
 use init::Init;
 
+mod guardian;
+use guardian::{ChangeGuardian, RemoveGuardian};
+
+mod escape;
+use escape::{CancelEscape, Escape, TriggerEscape};
+
 #[derive(Debug, Parser)]
 pub struct Argent {
     #[clap(subcommand)]
@@ -14,12 +28,90 @@ pub struct Argent {
 enum Subcommands {
     #[clap(about = "Create a new account configuration without actually deploying")]
     Init(Init),
+    #[clap(about = "Change the guardian, co-signing with the owner and the current guardian")]
+    ChangeGuardian(ChangeGuardian),
+    #[clap(about = "Remove the guardian, co-signing with the owner and the current guardian")]
+    RemoveGuardian(RemoveGuardian),
+    #[clap(about = "Start replacing the owner or the guardian without the other's signature")]
+    TriggerEscape(TriggerEscape),
+    #[clap(about = "Finalize an escape once its security period is over")]
+    Escape(Escape),
+    #[clap(about = "Cancel an ongoing escape, co-signing with the owner and the guardian")]
+    CancelEscape(CancelEscape),
+}
+
+/// Options for sending a call from an Argent account to itself.
+///
+/// Transactions are signed by the signer and, if supplied, the guardian. Calls to be made by the
+/// guardian alone, such as escaping the owner, are signed with the guardian key supplied as the
+/// signer.
+#[derive(Debug, Parser)]
+struct AccountCallArgs {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(flatten)]
+    account: AccountArgs,
+    #[clap(flatten)]
+    fee: FeeArgs,
+    #[clap(flatten)]
+    execution: ExecutionArgs,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
 }
 
 impl Argent {
     pub async fn run(self) -> Result<()> {
         match self.command {
             Subcommands::Init(cmd) => cmd.run().await,
+            Subcommands::ChangeGuardian(cmd) => cmd.run().await,
+            Subcommands::RemoveGuardian(cmd) => cmd.run().await,
+            Subcommands::TriggerEscape(cmd) => cmd.run().await,
+            Subcommands::Escape(cmd) => cmd.run().await,
+            Subcommands::CancelEscape(cmd) => cmd.run().await,
+        }
+    }
+}
+
+impl AccountCallArgs {
+    /// Signs the call with the signer alone, even if the account has a guardian.
+    fn single_signer(self) -> Self {
+        Self {
+            account: self.account.single_signer(),
+            ..self
         }
     }
+
+    /// Sends the call to the account itself, returning whether the transaction has been sent.
+    async fn execute(self, selector: FieldElement, calldata: Vec<FieldElement>) -> Result<bool> {
+        self.verbosity.setup_logging();
+
+        let fee_setting = self.fee.into_setting()?;
+        self.execution.validate(&fee_setting)?;
+
+        let provider = Arc::new(self.provider.into_provider()?);
+
+        // The account address is known without the signer
+        let address = self
+            .account
+            .clone()
+            .resolve_unsigned(&provider)
+            .await?
+            .address;
+
+        let transaction_hash = self
+            .execution
+            .execute(
+                provider,
+                self.account,
+                fee_setting,
+                vec![Call {
+                    to: address,
+                    selector,
+                    calldata,
+                }],
+            )
+            .await?;
+
+        Ok(transaction_hash.is_some())
+    }
 }
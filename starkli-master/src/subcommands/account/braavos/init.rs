@@ -3,11 +3,7 @@ use std::{io::Write, path::PathBuf};
 use anyhow::Result;
 use clap::Parser;
 use colored::Colorize;
-use starknet::{
-    core::types::FieldElement,
-    macros::felt,
-    signers::{Signer, SigningKey},
-};
+use starknet::{core::types::FieldElement, macros::felt};
 
 use crate::{
     account::{
@@ -15,8 +11,9 @@ use crate::{
         BraavosMultisigConfig, BraavosSigner, BraavosStarkSigner, DeploymentContext,
         DeploymentStatus, UndeployedStatus,
     },
+    output::record,
     path::ExpandedPathbufParser,
-    signer::SignerArgs,
+    subcommands::account::init::InitArgs,
 };
 
 /// Official hashes used as of extension version 3.21.10
@@ -29,9 +26,19 @@ const BRAAVOS_IMPL_CLASS_HASH: FieldElement =
 
 #[derive(Debug, Parser)]
 pub struct Init {
-    // TODO: allow manually specifying public key without using a signer
     #[clap(flatten)]
-    signer: SignerArgs,
+    init: InitArgs,
+    #[clap(
+        long,
+        help = "Class hash of the account implementation, instead of the official one"
+    )]
+    implementation: Option<FieldElement>,
+    #[clap(
+        long,
+        help = "Class hash of the mock implementation used during deployment, instead of the \
+        official one"
+    )]
+    mock_implementation: Option<FieldElement>,
     #[clap(
         long,
         short,
@@ -51,26 +58,25 @@ impl Init {
             anyhow::bail!("account config file already exists");
         }
 
-        let signer = self.signer.into_signer()?;
-
-        // Too lazy to write random salt generation
-        let salt = SigningKey::from_random().secret_scalar();
+        let init = self.init.resolve(BRAAVOS_PROXY_CLASS_HASH).await?;
 
         let account_config = AccountConfig {
             version: 1,
             variant: AccountVariant::Braavos(BraavosAccountConfig {
                 version: 1,
-                implementation: BRAAVOS_IMPL_CLASS_HASH,
+                implementation: self.implementation.unwrap_or(BRAAVOS_IMPL_CLASS_HASH),
                 multisig: BraavosMultisigConfig::Off,
                 signers: vec![BraavosSigner::Stark(BraavosStarkSigner {
-                    public_key: signer.get_public_key().await?.scalar(),
+                    public_key: init.public_key,
                 })],
             }),
             deployment: DeploymentStatus::Undeployed(UndeployedStatus {
-                class_hash: BRAAVOS_PROXY_CLASS_HASH,
-                salt,
+                class_hash: init.class_hash,
+                salt: init.salt,
                 context: Some(DeploymentContext::Braavos(BraavosDeploymentContext {
-                    mock_implementation: BRAAVOS_MOCK_IMPL_CLASS_HASH,
+                    mock_implementation: self
+                        .mock_implementation
+                        .unwrap_or(BRAAVOS_MOCK_IMPL_CLASS_HASH),
                 })),
             }),
         };
@@ -81,6 +87,9 @@ impl Init {
         serde_json::to_writer_pretty(&mut file, &account_config)?;
         file.write_all(b"\n")?;
 
+        record("file", std::fs::canonicalize(&self.output)?);
+        record("address", format!("{:#064x}", deployed_address));
+
         eprintln!(
             "Created new account config file: {}",
             std::fs::canonicalize(&self.output)?.display()
@@ -1,28 +1,45 @@
-use std::{io::Write, path::PathBuf, sync::Arc, time::Duration};
+use std::{
+    io::Write,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use anyhow::Result;
+use bigdecimal::BigDecimal;
 use clap::Parser;
 use colored::Colorize;
-use colored_json::{ColorMode, Output};
+use num_bigint::BigUint;
 use starknet::{
-    accounts::{AccountFactory, ArgentAccountFactory, OpenZeppelinAccountFactory},
+    accounts::{AccountFactory, Call, OpenZeppelinAccountFactory},
     core::types::{BlockId, BlockTag, FieldElement},
-    macros::felt,
+    macros::selector,
     providers::Provider,
     signers::Signer,
 };
 
 use crate::{
     account::{
-        AccountConfig, AccountVariant, BraavosMultisigConfig, BraavosSigner, DeployedStatus,
-        DeploymentContext, DeploymentStatus,
+        is_builtin_account, AccountArgs, AccountConfig, AccountVariant, BraavosMultisigConfig,
+        DeployedStatus, DeploymentContext, DeploymentStatus,
+    },
+    account_factory::{
+        AnyAccountFactory, ArgentAccountFactory, BraavosAccountFactory, V3DeploymentSigner,
     },
-    account_factory::{AnyAccountFactory, BraavosAccountFactory},
+    erc20::{amount_to_u256, format_amount, get_balance},
     error::account_factory_error_mapper,
-    fee::{FeeArgs, FeeSetting},
+    fee::{
+        print_fee_estimate, record_fee_estimate, EthFeeSetting, FeeArgs, FeeSetting, FeeToken,
+        DEFAULT_FEE_MULTIPLIER,
+    },
+    output::{record, record_transaction},
     path::ExpandedPathbufParser,
+    profile::ProfileDefaults,
+    provider::ExtendedProvider,
     signer::SignerArgs,
-    utils::watch_tx,
+    subcommands::invoke::ExecutionArgs,
+    utils::{bigdecimal_to_felt, print_colored_json, watch_tx},
+    v3::{V3Sender, V3Transaction},
     verbosity::VerbosityArgs,
     ProviderArgs,
 };
@@ -33,6 +50,19 @@ pub struct Deploy {
     provider: ProviderArgs,
     #[clap(flatten)]
     signer: SignerArgs,
+    #[clap(
+        long,
+        help = "Path to keystore JSON file of the Argent account guardian, for co-signing the \
+        deployment along with the owner"
+    )]
+    guardian_keystore: Option<String>,
+    #[clap(
+        long,
+        conflicts_with = "guardian_keystore",
+        help = "Private key of the Argent account guardian in hex, for co-signing the deployment \
+        along with the owner (WARNING: insecure)"
+    )]
+    guardian_private_key: Option<String>,
     #[clap(flatten)]
     fee: FeeArgs,
     #[clap(long, help = "Simulate the transaction only")]
@@ -42,10 +72,33 @@ pub struct Deploy {
     #[clap(
         long,
         env = "STARKNET_POLL_INTERVAL",
-        default_value = "5000",
-        help = "Transaction result poll interval in milliseconds"
+        help = "Transaction result poll interval in milliseconds. Falls back to the default of the \
+        active profile, or 5000 if not set"
     )]
-    poll_interval: u64,
+    poll_interval: Option<u64>,
+    #[clap(
+        long,
+        short,
+        help = "Skip the funding confirmation prompt. The deployment fails immediately if the \
+        address has not been funded with enough tokens for the max fee"
+    )]
+    yes: bool,
+    #[clap(
+        long,
+        conflicts_with = "yes",
+        help = "Instead of asking for confirmation, wait until the address has been funded with \
+        enough tokens for the max fee by polling its balance"
+    )]
+    wait_for_funds: bool,
+    #[clap(
+        long,
+        requires = "wait_for_funds",
+        default_value = "600",
+        help = "Maximum number of seconds to wait for funds when --wait-for-funds is used"
+    )]
+    funding_timeout: u64,
+    #[clap(flatten)]
+    funding: FundingArgs,
     #[clap(
         value_parser = ExpandedPathbufParser,
         help = "Path to the account config file"
@@ -55,14 +108,42 @@ pub struct Deploy {
     verbosity: VerbosityArgs,
 }
 
-enum MaxFeeType {
-    Manual {
-        max_fee: FieldElement,
-    },
-    Estimated {
-        estimate: FieldElement,
-        estimate_with_buffer: FieldElement,
-    },
+#[derive(Debug, Parser)]
+struct FundingArgs {
+    #[clap(
+        long,
+        conflicts_with_all = ["yes", "wait_for_funds"],
+        help = "Fund the address before deployment by transferring the fee token from this \
+        already deployed account. Accepts the same values as --account"
+    )]
+    fund_from: Option<String>,
+    #[clap(
+        long,
+        requires = "fund_from",
+        help = "Amount to transfer to the address in fee token units (e.g. 0.01). Defaults to the \
+        max fee of the deployment"
+    )]
+    fund_amount: Option<BigDecimal>,
+    #[clap(
+        long,
+        requires = "fund_from",
+        help = "Path to keystore JSON file of the funding account"
+    )]
+    fund_from_keystore: Option<String>,
+    #[clap(
+        long,
+        requires = "fund_from",
+        conflicts_with = "fund_from_keystore",
+        help = "Private key of the funding account in hex (WARNING: insecure)"
+    )]
+    fund_from_private_key: Option<String>,
+    #[clap(
+        long,
+        requires = "fund_from",
+        conflicts_with_all = ["fund_from_keystore", "fund_from_private_key"],
+        help = "EIP-2645 derivation path of the funding account on a Ledger device"
+    )]
+    fund_from_ledger_path: Option<String>,
 }
 
 impl Deploy {
@@ -74,6 +155,43 @@ impl Deploy {
             anyhow::bail!("--simulate cannot be used with --estimate-only");
         }
 
+        let funding_mode = if let Some(fund_from) = self.funding.fund_from {
+            // Without an explicit signer, the funding account would fall back to the signer from
+            // env vars or the profile, which is usually the one of the account being deployed.
+            if !is_builtin_account(&fund_from)
+                && self.funding.fund_from_keystore.is_none()
+                && self.funding.fund_from_private_key.is_none()
+                && self.funding.fund_from_ledger_path.is_none()
+            {
+                anyhow::bail!(
+                    "the signer of the funding account must be supplied with \
+                    --fund-from-keystore, --fund-from-private-key, or --fund-from-ledger-path"
+                );
+            }
+
+            FundingMode::Transfer {
+                account: AccountArgs::new(
+                    fund_from,
+                    SignerArgs::new(
+                        self.funding.fund_from_keystore,
+                        self.funding.fund_from_private_key,
+                        self.funding.fund_from_ledger_path,
+                    ),
+                ),
+                amount: self.funding.fund_amount,
+                poll_interval: self.poll_interval,
+            }
+        } else if self.wait_for_funds {
+            FundingMode::Wait {
+                poll_interval: ProfileDefaults::resolve_poll_interval(self.poll_interval)?,
+                timeout: Duration::from_secs(self.funding_timeout),
+            }
+        } else if self.yes {
+            FundingMode::Check
+        } else {
+            FundingMode::Prompt
+        };
+
         let provider = Arc::new(self.provider.into_provider()?);
         let signer = Arc::new(self.signer.into_signer()?);
 
@@ -135,11 +253,43 @@ impl Deploy {
                     );
                 }
 
+                // Accounts with guardians validate deployments against both signatures
+                let guardian = match (self.guardian_keystore, self.guardian_private_key) {
+                    (None, None) => None,
+                    (keystore, private_key) => Some(Arc::new(
+                        SignerArgs::new(keystore, private_key, None).into_signer()?,
+                    )),
+                };
+                match &guardian {
+                    Some(_) if argent_config.guardian == FieldElement::ZERO => {
+                        anyhow::bail!("the account does not have a guardian");
+                    }
+                    Some(guardian) => {
+                        let guardian_public_key = guardian.get_public_key().await?.scalar();
+                        if guardian_public_key != argent_config.guardian {
+                            anyhow::bail!(
+                                "guardian public key mismatch. \
+                                Expected: {:#064x}; actual: {:#064x}.",
+                                argent_config.guardian,
+                                guardian_public_key
+                            );
+                        }
+                    }
+                    None if argent_config.guardian != FieldElement::ZERO => {
+                        anyhow::bail!(
+                            "the account has a guardian, which must co-sign the deployment. \
+                            Use --guardian-keystore or --guardian-private-key"
+                        );
+                    }
+                    None => {}
+                }
+
                 let mut factory = ArgentAccountFactory::new(
                     undeployed_status.class_hash,
                     chain_id,
-                    FieldElement::ZERO,
+                    argent_config.guardian,
                     signer.clone(),
+                    guardian,
                     provider.clone(),
                 )
                 .await?;
@@ -148,140 +298,190 @@ impl Deploy {
                 AnyAccountFactory::Argent(factory)
             }
             AccountVariant::Braavos(braavos_config) => {
-                if !matches!(braavos_config.multisig, BraavosMultisigConfig::Off) {
-                    anyhow::bail!("Braavos accounts cannot be deployed with multisig on");
-                }
-                if braavos_config.signers.len() != 1 {
-                    anyhow::bail!("Braavos accounts can only be deployed with one seed signer");
+                // The deployment only sets up the seed signer, which would leave the account file
+                // out of sync with the deployed account.
+                if !matches!(braavos_config.multisig, BraavosMultisigConfig::Off)
+                    || braavos_config.signers.len() != 1
+                {
+                    anyhow::bail!(
+                        "Braavos accounts can only be deployed with the seed signer. Deploy the \
+                        account with multisig off first, then add signers and enable multisig \
+                        before running `starkli account fetch`"
+                    );
                 }
 
+                let seed_signer = braavos_config.seed_signer()?;
+
                 match &undeployed_status.context {
                     Some(DeploymentContext::Braavos(context)) => {
-                        // Safe to unwrap as we already checked for length
-                        match braavos_config.signers.first().unwrap() {
-                            BraavosSigner::Stark(stark_signer) => {
-                                // Makes sure we're using the right key
-                                if signer_public_key != stark_signer.public_key {
-                                    anyhow::bail!(
-                                        "public key mismatch. \
-                                        Expected: {:#064x}; actual: {:#064x}.",
-                                        stark_signer.public_key,
-                                        signer_public_key
-                                    );
-                                }
-
-                                let mut factory = BraavosAccountFactory::new(
-                                    undeployed_status.class_hash,
-                                    context.mock_implementation,
-                                    braavos_config.implementation,
-                                    chain_id,
-                                    signer.clone(),
-                                    provider.clone(),
-                                )
-                                .await?;
-                                factory.set_block_id(BlockId::Tag(BlockTag::Pending));
-
-                                AnyAccountFactory::Braavos(factory)
-                            } // Reject other variants as we add more types
+                        // Makes sure we're using the right key
+                        if signer_public_key != seed_signer.public_key {
+                            anyhow::bail!(
+                                "public key mismatch. Expected: {:#064x}; actual: {:#064x}.",
+                                seed_signer.public_key,
+                                signer_public_key
+                            );
                         }
+
+                        let mut factory = BraavosAccountFactory::new(
+                            undeployed_status.class_hash,
+                            context.mock_implementation,
+                            braavos_config.implementation,
+                            chain_id,
+                            signer.clone(),
+                            provider.clone(),
+                        )
+                        .await?;
+                        factory.set_block_id(BlockId::Tag(BlockTag::Pending));
+
+                        AnyAccountFactory::Braavos(factory)
                     }
                     _ => anyhow::bail!("missing Braavos deployment context"),
                 }
             }
         };
 
-        let account_deployment = factory.deploy(undeployed_status.salt);
-
         let target_deployment_address = account.deploy_account_address()?;
 
-        // Sanity check. We don't really need to check again here actually
-        if account_deployment.address() != target_deployment_address {
-            panic!("Unexpected account deployment address mismatch");
-        }
+        let account_deployment_tx = match fee_setting {
+            FeeSetting::Eth(fee_setting) => {
+                let account_deployment = factory.deploy(undeployed_status.salt);
 
-        let max_fee = match fee_setting {
-            FeeSetting::Manual(fee) => MaxFeeType::Manual { max_fee: fee },
-            FeeSetting::EstimateOnly | FeeSetting::None => {
-                let estimated_fee = account_deployment
-                    .estimate_fee()
-                    .await
-                    .map_err(account_factory_error_mapper)?
-                    .overall_fee;
-
-                // TODO: make buffer configurable
-                let estimated_fee_with_buffer = (estimated_fee * felt!("3")).floor_div(felt!("2"));
-
-                if fee_setting.is_estimate_only() {
-                    println!(
-                        "{} ETH",
-                        format!("{}", estimated_fee.to_big_decimal(18)).bright_yellow(),
-                    );
-                    return Ok(());
+                // Sanity check. We don't really need to check again here actually
+                if account_deployment.address() != target_deployment_address {
+                    panic!("Unexpected account deployment address mismatch");
                 }
 
-                MaxFeeType::Estimated {
-                    estimate: estimated_fee,
-                    estimate_with_buffer: estimated_fee_with_buffer,
+                let max_fee = match fee_setting {
+                    EthFeeSetting::Manual(fee) => {
+                        if !self.simulate {
+                            wait_for_funding(
+                                &provider,
+                                &funding_mode,
+                                None,
+                                fee,
+                                FeeToken::Eth,
+                                target_deployment_address,
+                            )
+                            .await?;
+                        }
+                        fee
+                    }
+                    EthFeeSetting::EstimateOnly | EthFeeSetting::Estimate { .. } => {
+                        let estimate = account_deployment
+                            .estimate_fee()
+                            .await
+                            .map_err(account_factory_error_mapper)?;
+                        record_fee_estimate(&estimate, FeeToken::Eth);
+
+                        if matches!(fee_setting, EthFeeSetting::EstimateOnly) {
+                            print_fee_estimate(&estimate, FeeToken::Eth);
+                            return Ok(());
+                        }
+
+                        let estimated_fee_with_buffer = fee_setting
+                            .apply_multiplier(estimate.overall_fee, DEFAULT_FEE_MULTIPLIER);
+
+                        if !self.simulate {
+                            wait_for_funding(
+                                &provider,
+                                &funding_mode,
+                                Some(estimate.overall_fee),
+                                estimated_fee_with_buffer,
+                                FeeToken::Eth,
+                                target_deployment_address,
+                            )
+                            .await?;
+                        }
+
+                        estimated_fee_with_buffer
+                    }
+                };
+
+                let account_deployment = match self.nonce {
+                    Some(nonce) => account_deployment.nonce(nonce),
+                    None => account_deployment,
+                };
+                let account_deployment = account_deployment.max_fee(max_fee);
+
+                if self.simulate {
+                    let simulation = account_deployment.simulate(false, false).await?;
+                    print_colored_json(&simulation)?;
+                    return Ok(());
                 }
+
+                account_deployment.send().await?.transaction_hash
             }
-        };
+            FeeSetting::Strk(fee_setting) => {
+                let deployment_signer = V3DeploymentSigner {
+                    factory: &factory,
+                    signer: signer.as_ref(),
+                };
+
+                let sender = V3Sender::new(
+                    &provider,
+                    &deployment_signer,
+                    chain_id,
+                    self.nonce,
+                    V3Transaction::DeployAccount {
+                        class_hash: factory.class_hash(),
+                        salt: undeployed_status.salt,
+                        constructor_calldata: factory.calldata(),
+                    },
+                )
+                .await?;
 
-        if !self.simulate {
-            match max_fee {
-                MaxFeeType::Manual { max_fee } => {
-                    eprintln!(
-                        "You've manually specified the account deployment fee to be {}. \
-                        Therefore, fund at least:\n    {}",
-                        format!("{} ETH", max_fee.to_big_decimal(18)).bright_yellow(),
-                        format!("{} ETH", max_fee.to_big_decimal(18)).bright_yellow(),
-                    );
+                // Sanity check. We don't really need to check again here actually
+                if sender.transaction().sender_address() != target_deployment_address {
+                    panic!("Unexpected account deployment address mismatch");
                 }
-                MaxFeeType::Estimated {
-                    estimate,
-                    estimate_with_buffer,
-                } => {
-                    eprintln!(
-                        "The estimated account deployment fee is {}. \
-                        However, to avoid failure, fund at least:\n    {}",
-                        format!("{} ETH", estimate.to_big_decimal(18)).bright_yellow(),
-                        format!("{} ETH", estimate_with_buffer.to_big_decimal(18)).bright_yellow()
-                    );
+
+                let estimate = if fee_setting.needs_estimate() {
+                    Some(sender.estimate_fee().await?)
+                } else {
+                    None
+                };
+                if let Some(estimate) = &estimate {
+                    record_fee_estimate(estimate, FeeToken::Strk);
                 }
-            }
 
-            eprintln!(
-                "to the following address:\n    {}",
-                format!("{:#064x}", target_deployment_address).bright_yellow()
-            );
+                if fee_setting.estimate_only {
+                    if let Some(estimate) = &estimate {
+                        print_fee_estimate(estimate, FeeToken::Strk);
+                    }
+                    return Ok(());
+                }
 
-            // TODO: add flag for skipping this manual confirmation step
-            eprint!("Press [ENTER] once you've funded the address.");
-            std::io::stdin().read_line(&mut String::new())?;
-        }
+                let fee = fee_setting.resolve(estimate.as_ref(), DEFAULT_FEE_MULTIPLIER)?;
 
-        let account_deployment = match self.nonce {
-            Some(nonce) => account_deployment.nonce(nonce),
-            None => account_deployment,
-        };
-        let account_deployment = account_deployment.max_fee(max_fee.max_fee());
+                if self.simulate {
+                    let simulation = sender.simulate(&fee).await?;
+                    print_colored_json(&simulation)?;
+                    return Ok(());
+                }
 
-        if self.simulate {
-            let simulation = account_deployment.simulate(false, false).await?;
-            let simulation_json = serde_json::to_value(simulation)?;
+                wait_for_funding(
+                    &provider,
+                    &funding_mode,
+                    estimate.map(|estimate| estimate.overall_fee),
+                    fee.max_fee(),
+                    FeeToken::Strk,
+                    target_deployment_address,
+                )
+                .await?;
 
-            let simulation_json =
-                colored_json::to_colored_json(&simulation_json, ColorMode::Auto(Output::StdOut))?;
-            println!("{simulation_json}");
-            return Ok(());
-        }
+                sender.send(&fee).await?
+            }
+        };
 
-        // TODO: add option to check ETH balance before sending out tx
-        let account_deployment_tx = account_deployment.send().await?.transaction_hash;
         eprintln!(
             "Account deployment transaction: {}",
             format!("{:#064x}", account_deployment_tx).bright_yellow()
         );
 
+        record_transaction(account_deployment_tx);
+        record("address", format!("{:#064x}", target_deployment_address));
+
         // By default we wait for the tx to confirm so that we don't incorrectly mark the account
         // as deployed
         eprintln!(
@@ -293,7 +493,7 @@ impl Deploy {
         watch_tx(
             &provider,
             account_deployment_tx,
-            Duration::from_millis(self.poll_interval),
+            ProfileDefaults::resolve_poll_interval(self.poll_interval)?,
         )
         .await?;
 
@@ -321,14 +521,173 @@ impl Deploy {
     }
 }
 
-impl MaxFeeType {
-    pub fn max_fee(&self) -> FieldElement {
-        match self {
-            Self::Manual { max_fee } => *max_fee,
-            Self::Estimated {
-                estimate_with_buffer,
-                ..
-            } => *estimate_with_buffer,
+/// How to make sure the account to be deployed has been funded before sending the transaction.
+enum FundingMode {
+    /// Asks the user to confirm funding interactively.
+    Prompt,
+    /// Checks the balance once without asking.
+    Check,
+    /// Polls the balance until it's enough, or until `timeout` has passed.
+    Wait {
+        poll_interval: Duration,
+        timeout: Duration,
+    },
+    /// Transfers the fee token from another account, with the amount defaulting to the max fee.
+    Transfer {
+        account: AccountArgs,
+        amount: Option<BigDecimal>,
+        poll_interval: Option<u64>,
+    },
+}
+
+/// Asks the user to fund the account to be deployed, with `estimated_fee` being `None` if the fee
+/// is manually specified. The balance is always checked before returning so that a deployment
+/// doomed to fail is never sent.
+async fn wait_for_funding(
+    provider: &Arc<ExtendedProvider>,
+    funding_mode: &FundingMode,
+    estimated_fee: Option<FieldElement>,
+    max_fee: FieldElement,
+    fee_token: FeeToken,
+    address: FieldElement,
+) -> Result<()> {
+    match estimated_fee {
+        Some(estimated_fee) => {
+            eprintln!(
+                "The estimated account deployment fee is {}. \
+                However, to avoid failure, fund at least:\n    {}",
+                format!("{} {}", estimated_fee.to_big_decimal(18), fee_token).bright_yellow(),
+                format!("{} {}", max_fee.to_big_decimal(18), fee_token).bright_yellow()
+            );
+        }
+        None => {
+            eprintln!(
+                "You've manually specified the account deployment fee to be {}. \
+                Therefore, fund at least:\n    {}",
+                format!("{} {}", max_fee.to_big_decimal(18), fee_token).bright_yellow(),
+                format!("{} {}", max_fee.to_big_decimal(18), fee_token).bright_yellow(),
+            );
         }
     }
+
+    eprintln!(
+        "to the following address:\n    {}",
+        format!("{:#064x}", address).bright_yellow()
+    );
+
+    let required = BigUint::from_bytes_be(&max_fee.to_bytes_be());
+
+    match funding_mode {
+        FundingMode::Prompt => {
+            eprint!("Press [ENTER] once you've funded the address.");
+            std::io::stdin().read_line(&mut String::new())?;
+        }
+        FundingMode::Check => {}
+        FundingMode::Wait {
+            poll_interval,
+            timeout,
+        } => {
+            eprintln!(
+                "Waiting for the address to be funded (timeout: {})...",
+                format!("{}s", timeout.as_secs()).bright_yellow()
+            );
+
+            let start_time = Instant::now();
+            loop {
+                let balance = get_fee_token_balance(provider.as_ref(), fee_token, address).await?;
+                if balance >= required {
+                    break;
+                }
+
+                if start_time.elapsed() >= *timeout {
+                    anyhow::bail!(
+                        "timed out waiting for funds: balance of {:#064x} is {} {}, \
+                        but at least {} {} is needed",
+                        address,
+                        format_amount(&balance, 18),
+                        fee_token,
+                        max_fee.to_big_decimal(18),
+                        fee_token
+                    );
+                }
+
+                tokio::time::sleep(*poll_interval).await;
+            }
+        }
+        FundingMode::Transfer {
+            account,
+            amount,
+            poll_interval,
+        } => {
+            let amount = match amount {
+                Some(amount) => bigdecimal_to_felt(amount, 18)?,
+                None => max_fee,
+            };
+            if amount < max_fee {
+                anyhow::bail!(
+                    "--fund-amount is too low: at least {} {} is needed",
+                    max_fee.to_big_decimal(18),
+                    fee_token
+                );
+            }
+
+            eprintln!(
+                "Funding the address with {}...",
+                format!("{} {}", amount.to_big_decimal(18), fee_token).bright_yellow()
+            );
+
+            let mut calldata = vec![address];
+            calldata.extend_from_slice(&amount_to_u256(amount));
+
+            // The transfer must be confirmed before the deployment can use the funds
+            ExecutionArgs::watching(*poll_interval)
+                .execute(
+                    provider.clone(),
+                    account.clone(),
+                    FeeSetting::estimate(fee_token),
+                    vec![Call {
+                        to: fee_token.address(),
+                        selector: selector!("transfer"),
+                        calldata,
+                    }],
+                )
+                .await?;
+        }
+    }
+
+    let balance = get_fee_token_balance(provider.as_ref(), fee_token, address).await?;
+    if balance < required {
+        anyhow::bail!(
+            "insufficient funds: balance of {:#064x} is {} {}, but at least {} {} is needed",
+            address,
+            format_amount(&balance, 18),
+            fee_token,
+            max_fee.to_big_decimal(18),
+            fee_token
+        );
+    }
+
+    eprintln!(
+        "Address funded with {}.",
+        format!("{} {}", format_amount(&balance, 18), fee_token).bright_yellow()
+    );
+
+    Ok(())
+}
+
+async fn get_fee_token_balance<P>(
+    provider: &P,
+    fee_token: FeeToken,
+    address: FieldElement,
+) -> Result<BigUint>
+where
+    P: Provider,
+{
+    get_balance(
+        provider,
+        fee_token.address(),
+        address,
+        BlockId::Tag(BlockTag::Pending),
+    )
+    .await
 }
@@ -4,7 +4,7 @@ use anyhow::Result;
 use clap::Parser;
 use colored::Colorize;
 use starknet::{
-    core::types::{BlockId, BlockTag, FieldElement, FunctionCall},
+    core::types::{FieldElement, FunctionCall},
     macros::selector,
     providers::Provider,
 };
@@ -15,6 +15,7 @@ use crate::{
         BraavosMultisigConfig, BraavosSigner, DeployedStatus, DeploymentStatus, OzAccountConfig,
         KNOWN_ACCOUNT_CLASSES,
     },
+    block::BlockArgs,
     verbosity::VerbosityArgs,
     ProviderArgs,
 };
@@ -30,6 +31,8 @@ pub struct Fetch {
     #[clap(help = "Contract address")]
     address: String,
     #[clap(flatten)]
+    block: BlockArgs,
+    #[clap(flatten)]
     verbosity: VerbosityArgs,
 }
 
@@ -51,11 +54,10 @@ impl Fetch {
         }
 
         let provider = self.provider.into_provider()?;
+        let block_id = self.block.resolve(&provider).await?;
         let address = FieldElement::from_hex_be(&self.address)?;
 
-        let class_hash = provider
-            .get_class_hash_at(BlockId::Tag(BlockTag::Pending), address)
-            .await?;
+        let class_hash = provider.get_class_hash_at(block_id, address).await?;
 
         let known_class = match KNOWN_ACCOUNT_CLASSES
             .iter()
@@ -94,7 +96,7 @@ impl Fetch {
                             entry_point_selector: selector!("getPublicKey"),
                             calldata: vec![],
                         },
-                        BlockId::Tag(BlockTag::Pending),
+                        block_id,
                     )
                     .await?[0];
 
@@ -112,7 +114,7 @@ impl Fetch {
                             entry_point_selector: selector!("get_implementation"),
                             calldata: vec![],
                         },
-                        BlockId::Tag(BlockTag::Pending),
+                        block_id,
                     )
                     .await?[0];
                 let signer = provider
@@ -122,7 +124,7 @@ impl Fetch {
                             entry_point_selector: selector!("getSigner"),
                             calldata: vec![],
                         },
-                        BlockId::Tag(BlockTag::Pending),
+                        block_id,
                     )
                     .await?[0];
                 let guardian = provider
@@ -132,7 +134,7 @@ impl Fetch {
                             entry_point_selector: selector!("getGuardian"),
                             calldata: vec![],
                         },
-                        BlockId::Tag(BlockTag::Pending),
+                        block_id,
                     )
                     .await?[0];
 
@@ -151,7 +153,7 @@ impl Fetch {
                             entry_point_selector: selector!("get_implementation"),
                             calldata: vec![],
                         },
-                        BlockId::Tag(BlockTag::Pending),
+                        block_id,
                     )
                     .await?[0];
                 let signers = provider
@@ -161,7 +163,7 @@ impl Fetch {
                             entry_point_selector: selector!("get_signers"),
                             calldata: vec![],
                         },
-                        BlockId::Tag(BlockTag::Pending),
+                        block_id,
                     )
                     .await?;
                 let multisig = provider
@@ -171,7 +173,7 @@ impl Fetch {
                             entry_point_selector: selector!("get_multisig"),
                             calldata: vec![],
                         },
-                        BlockId::Tag(BlockTag::Pending),
+                        block_id,
                     )
                     .await?[0];
 
@@ -187,8 +189,10 @@ impl Fetch {
                             anyhow::bail!("unable to decode Braavos signers: index mismatch");
                         }
 
-                        let signer =
-                            BraavosSigner::decode(&signers[(base_offset + 1)..(base_offset + 8)])?;
+                        let signer = BraavosSigner::decode(
+                            ind_signer as u64,
+                            &signers[(base_offset + 1)..(base_offset + 8)],
+                        )?;
 
                         buffer.push(signer);
                     }
@@ -219,7 +223,7 @@ impl Fetch {
                             entry_point_selector: selector!("get_owner"),
                             calldata: vec![],
                         },
-                        BlockId::Tag(BlockTag::Pending),
+                        block_id,
                     )
                     .await?[0];
                 let guardian = provider
@@ -229,7 +233,7 @@ impl Fetch {
                             entry_point_selector: selector!("get_guardian"),
                             calldata: vec![],
                         },
-                        BlockId::Tag(BlockTag::Pending),
+                        block_id,
                     )
                     .await?[0];
 
@@ -248,7 +252,7 @@ impl Fetch {
                             entry_point_selector: selector!("get_public_key"),
                             calldata: vec![],
                         },
-                        BlockId::Tag(BlockTag::Pending),
+                        block_id,
                     )
                     .await?[0];
 
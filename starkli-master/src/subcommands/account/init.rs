@@ -0,0 +1,61 @@
+use anyhow::Result;
+use clap::Parser;
+use starknet::{
+    core::types::FieldElement,
+    signers::{Signer, SigningKey},
+};
+
+use crate::signer::SignerArgs;
+
+/// Options shared by the `init` commands of all account variants.
+#[derive(Debug, Parser)]
+pub struct InitArgs {
+    #[clap(flatten)]
+    signer: SignerArgs,
+    #[clap(
+        long,
+        conflicts_with_all = ["keystore", "private_key", "ledger_path"],
+        help = "Public key of the account signer, so that the account config file can be created \
+        without access to the signer"
+    )]
+    public_key: Option<FieldElement>,
+    #[clap(
+        long,
+        help = "Salt for deriving the account address. Randomly generated if not specified"
+    )]
+    salt: Option<FieldElement>,
+    #[clap(
+        long,
+        help = "Class hash of the account contract to deploy, instead of the default one for the \
+        account variant"
+    )]
+    class_hash: Option<FieldElement>,
+}
+
+/// Values resolved from [InitArgs].
+pub struct ResolvedInit {
+    pub public_key: FieldElement,
+    pub salt: FieldElement,
+    pub class_hash: FieldElement,
+}
+
+impl InitArgs {
+    /// Resolves the options, only using the signer when the public key is not supplied directly.
+    pub async fn resolve(self, default_class_hash: FieldElement) -> Result<ResolvedInit> {
+        let public_key = match self.public_key {
+            Some(public_key) => public_key,
+            None => self.signer.into_signer()?.get_public_key().await?.scalar(),
+        };
+
+        // Too lazy to write random salt generation
+        let salt = self
+            .salt
+            .unwrap_or_else(|| SigningKey::from_random().secret_scalar());
+
+        Ok(ResolvedInit {
+            public_key,
+            salt,
+            class_hash: self.class_hash.unwrap_or(default_class_hash),
+        })
+    }
+}
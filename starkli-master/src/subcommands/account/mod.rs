@@ -16,6 +16,8 @@ use argent::Argent;
 mod braavos;
 use braavos::Braavos;
 
+mod init;
+
 #[derive(Debug, Parser)]
 pub struct Account {
     #[clap(subcommand)]
@@ -3,16 +3,13 @@ use std::{io::Write, path::PathBuf};
 use anyhow::Result;
 use clap::Parser;
 use colored::Colorize;
-use starknet::{
-    core::types::FieldElement,
-    macros::felt,
-    signers::{Signer, SigningKey},
-};
+use starknet::{core::types::FieldElement, macros::felt};
 
 use crate::{
     account::{AccountConfig, AccountVariant, DeploymentStatus, OzAccountConfig, UndeployedStatus},
+    output::record,
     path::ExpandedPathbufParser,
-    signer::SignerArgs,
+    subcommands::account::init::InitArgs,
 };
 
 /// OpenZeppelin account contract v0.8.0 compiled with cairo v2.3.1
@@ -21,9 +18,8 @@ const OZ_ACCOUNT_CLASS_HASH: FieldElement =
 
 #[derive(Debug, Parser)]
 pub struct Init {
-    // TODO: allow manually specifying public key without using a signer
     #[clap(flatten)]
-    signer: SignerArgs,
+    init: InitArgs,
     #[clap(
         long,
         short,
@@ -43,21 +39,18 @@ impl Init {
             anyhow::bail!("account config file already exists");
         }
 
-        let signer = self.signer.into_signer()?;
-
-        // Too lazy to write random salt generation
-        let salt = SigningKey::from_random().secret_scalar();
+        let init = self.init.resolve(OZ_ACCOUNT_CLASS_HASH).await?;
 
         let account_config = AccountConfig {
             version: 1,
             variant: AccountVariant::OpenZeppelin(OzAccountConfig {
                 version: 1,
-                public_key: signer.get_public_key().await?.scalar(),
+                public_key: init.public_key,
                 legacy: false,
             }),
             deployment: DeploymentStatus::Undeployed(UndeployedStatus {
-                class_hash: OZ_ACCOUNT_CLASS_HASH,
-                salt,
+                class_hash: init.class_hash,
+                salt: init.salt,
                 context: None,
             }),
         };
@@ -68,6 +61,9 @@ impl Init {
         serde_json::to_writer_pretty(&mut file, &account_config)?;
         file.write_all(b"\n")?;
 
+        record("file", std::fs::canonicalize(&self.output)?);
+        record("address", format!("{:#064x}", deployed_address));
+
         eprintln!(
             "Created new account config file: {}",
             std::fs::canonicalize(&self.output)?.display()
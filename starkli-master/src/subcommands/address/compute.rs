@@ -0,0 +1,126 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::core::{
+    types::{BlockId, BlockTag, FieldElement},
+    utils::{get_contract_address, get_udc_deployed_address, UdcUniqueSettings, UdcUniqueness},
+};
+
+use crate::{
+    abi::AbiArgs, address_book::AddressBookResolver, decode::FeltDecoder, output::print_text,
+    udc::UdcArgs, verbosity::VerbosityArgs, ProviderArgs,
+};
+
+#[derive(Debug, Parser)]
+pub struct Compute {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(flatten)]
+    abi: AbiArgs,
+    #[clap(flatten)]
+    udc: UdcArgs,
+    #[clap(long, help = "Deployment salt")]
+    salt: FieldElement,
+    #[clap(
+        long,
+        conflicts_with = "direct",
+        help = "Compute the address of a UDC deployment not derived from the deployer address"
+    )]
+    not_unique: bool,
+    #[clap(
+        long,
+        help = "Compute the address of a contract deployed without the UDC, via the deploy \
+        syscall or a DEPLOY_ACCOUNT transaction"
+    )]
+    direct: bool,
+    #[clap(
+        long,
+        help = "Deployer address. Required for unique UDC deployments. Defaults to 0 for direct \
+        deployments, as used by DEPLOY_ACCOUNT transactions"
+    )]
+    deployer: Option<String>,
+    #[clap(help = "Class hash")]
+    class_hash: String,
+    #[clap(help = "Constructor arguments (raw field elements unless --typed or --abi is used)")]
+    ctor_args: Vec<String>,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+impl Compute {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let abi_source = self.abi.into_source()?;
+
+        // The network is only needed for address book entries, fetching ABIs, and the UDC
+        // configured in the profile. No transaction is sent.
+        let provider = Arc::new(self.provider.into_provider()?);
+        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+
+        let class_hash = FieldElement::from_hex_be(&self.class_hash)?;
+        let ctor_args = match &abi_source {
+            Some(abi_source) => {
+                abi_source
+                    .abi_of_class(&provider, class_hash, BlockId::Tag(BlockTag::Pending))
+                    .await?
+                    .encode_constructor_call(&self.ctor_args, &felt_decoder)
+                    .await?
+            }
+            None => {
+                let mut ctor_args = vec![];
+                for element in self.ctor_args.iter() {
+                    ctor_args.append(&mut felt_decoder.decode(element).await?);
+                }
+                ctor_args
+            }
+        };
+
+        let deployer = match &self.deployer {
+            Some(deployer) => Some(
+                felt_decoder
+                    .decode_single_with_addr_fallback(deployer)
+                    .await?,
+            ),
+            None => None,
+        };
+
+        let address = if self.direct {
+            get_contract_address(
+                self.salt,
+                class_hash,
+                &ctor_args,
+                deployer.unwrap_or(FieldElement::ZERO),
+            )
+        } else {
+            let udc_uniqueness = match (self.not_unique, deployer) {
+                (true, Some(_)) => {
+                    anyhow::bail!("--deployer must not be used when --not-unique is on");
+                }
+                (false, None) => {
+                    anyhow::bail!("--deployer must be used for unique UDC deployments");
+                }
+                (true, None) => UdcUniqueness::NotUnique,
+                (false, Some(deployer_address)) => UdcUniqueness::Unique(UdcUniqueSettings {
+                    deployer_address,
+                    udc_contract_address: self.udc.resolve(provider.udc_address()),
+                }),
+            };
+
+            get_udc_deployed_address(self.salt, class_hash, &udc_uniqueness, &ctor_args)
+        };
+
+        eprintln!(
+            "Contract address for class {} with salt {}:",
+            format!("{:#064x}", class_hash).bright_yellow(),
+            format!("{:#064x}", self.salt).bright_yellow()
+        );
+
+        // Only the address goes to stdout so this can be easily scripted
+        print_text("address", format!("{:#064x}", address).bright_yellow());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,25 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+mod compute;
+use compute::Compute;
+
+#[derive(Debug, Parser)]
+pub struct Address {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(about = "Compute the address of a contract before deploying it")]
+    Compute(Compute),
+}
+
+impl Address {
+    pub async fn run(self) -> Result<()> {
+        match self.command {
+            Subcommands::Compute(cmd) => cmd.run().await,
+        }
+    }
+}
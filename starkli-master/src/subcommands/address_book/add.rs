@@ -0,0 +1,49 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::core::types::FieldElement;
+
+use crate::address_book::{AddressBook, ChainIdArgs};
+
+#[derive(Debug, Parser)]
+pub struct Add {
+    #[clap(flatten)]
+    chain_id: ChainIdArgs,
+    #[clap(long, help = "Overwrite the entry if it already exists")]
+    force: bool,
+    #[clap(help = "Name of the entry")]
+    name: String,
+    #[clap(help = "Contract address")]
+    address: FieldElement,
+}
+
+impl Add {
+    pub async fn run(self) -> Result<()> {
+        AddressBook::validate_name(&self.name)?;
+
+        let chain_id = self.chain_id.resolve().await?;
+
+        let mut address_book = AddressBook::load()?;
+        let entries = address_book.chains.entry(chain_id.clone()).or_default();
+
+        if entries.contains_key(&self.name) && !self.force {
+            anyhow::bail!(
+                "entry `{}` already exists for chain {}. Use --force to overwrite",
+                self.name,
+                chain_id
+            );
+        }
+
+        entries.insert(self.name.clone(), self.address);
+        address_book.save()?;
+
+        eprintln!(
+            "Entry {} added for chain {}: {}",
+            self.name.bright_yellow(),
+            chain_id.bright_yellow(),
+            format!("{:#064x}", self.address).bright_yellow()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,100 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use indexmap::IndexMap;
+use serde::Deserialize;
+use starknet::core::{types::FieldElement, utils::cairo_short_string_to_felt};
+
+use crate::address_book::{AddressBook, ChainIdArgs};
+
+#[derive(Debug, Parser)]
+pub struct Import {
+    #[clap(flatten)]
+    chain_id: ChainIdArgs,
+    #[clap(long, help = "Overwrite existing entries with the same names")]
+    force: bool,
+    #[clap(
+        help = "Path to a TOML or JSON file containing either a map of names to addresses for a \
+        single chain, or maps of names to addresses keyed by chain ID"
+    )]
+    file: PathBuf,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ImportFile {
+    /// Same layout as the address book file.
+    Chains(IndexMap<String, IndexMap<String, FieldElement>>),
+    /// Entries for the chain specified via --chain-id or the network.
+    Entries(IndexMap<String, FieldElement>),
+}
+
+impl Import {
+    pub async fn run(self) -> Result<()> {
+        let content = std::fs::read_to_string(&self.file)?;
+
+        let is_json = self
+            .file
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+        let import_file: ImportFile = if is_json {
+            serde_json::from_str(&content)?
+        } else {
+            toml::from_str(&content)?
+        };
+
+        let chains = match import_file {
+            ImportFile::Chains(chains) => chains,
+            ImportFile::Entries(entries) => {
+                let mut chains = IndexMap::new();
+                chains.insert(self.chain_id.resolve().await?, entries);
+                chains
+            }
+        };
+
+        let mut address_book = AddressBook::load()?;
+
+        // Validates everything before making any change
+        for (chain_id, entries) in chains.iter() {
+            cairo_short_string_to_felt(chain_id)
+                .map_err(|_| anyhow::anyhow!("invalid chain ID: {}", chain_id))?;
+
+            for name in entries.keys() {
+                AddressBook::validate_name(name)?;
+
+                let exists = address_book
+                    .chains
+                    .get(chain_id)
+                    .is_some_and(|existing| existing.contains_key(name));
+                if exists && !self.force {
+                    anyhow::bail!(
+                        "entry `{}` already exists for chain {}. Use --force to overwrite",
+                        name,
+                        chain_id
+                    );
+                }
+            }
+        }
+
+        let mut count = 0;
+        for (chain_id, entries) in chains.into_iter() {
+            let existing = address_book.chains.entry(chain_id).or_default();
+            for (name, address) in entries.into_iter() {
+                existing.insert(name, address);
+                count += 1;
+            }
+        }
+
+        address_book.save()?;
+
+        eprintln!(
+            "{} entries imported from {}.",
+            count.to_string().bright_yellow(),
+            self.file.display().to_string().bright_yellow()
+        );
+
+        Ok(())
+    }
+}
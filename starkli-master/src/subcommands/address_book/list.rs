@@ -0,0 +1,95 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use indexmap::IndexMap;
+use starknet::core::{types::FieldElement, utils::parse_cairo_short_string};
+
+use crate::{
+    address_book::{AddressBook, BUILTIN_ADDRESS_BOOK},
+    output::{is_json_output, record},
+};
+
+#[derive(Debug, Parser)]
+pub struct List {
+    #[clap(
+        long,
+        help = "Only list entries for this chain ID (e.g. SN_SEPOLIA). Entries for all chains are \
+        listed if not specified"
+    )]
+    chain_id: Option<String>,
+}
+
+impl List {
+    pub fn run(self) -> Result<()> {
+        let address_book = AddressBook::load()?;
+
+        // Chain ID -> name -> (address, is built-in)
+        let mut chains: IndexMap<String, IndexMap<String, (FieldElement, bool)>> = IndexMap::new();
+
+        for (chain_id, entries) in address_book.chains.iter() {
+            let chain_entries = chains.entry(chain_id.to_owned()).or_default();
+            for (name, address) in entries.iter() {
+                chain_entries.insert(name.to_owned(), (*address, false));
+            }
+        }
+
+        for entry in BUILTIN_ADDRESS_BOOK.iter() {
+            let chain_id = parse_cairo_short_string(&entry.chain_id)?;
+            let chain_entries = chains.entry(chain_id).or_default();
+
+            // Entries from the file take precedence
+            if !chain_entries.contains_key(entry.name) {
+                chain_entries.insert(entry.name.to_owned(), (entry.address, true));
+            }
+        }
+
+        if let Some(chain_id) = &self.chain_id {
+            chains.retain(|key, _| key == chain_id);
+        }
+
+        if is_json_output() {
+            let chains = chains
+                .iter()
+                .map(|(chain_id, entries)| {
+                    let entries = entries
+                        .iter()
+                        .map(|(name, (address, is_builtin))| {
+                            (
+                                name.to_owned(),
+                                serde_json::json!({
+                                    "address": format!("{:#064x}", address),
+                                    "builtin": is_builtin,
+                                }),
+                            )
+                        })
+                        .collect::<IndexMap<_, _>>();
+                    (chain_id.to_owned(), entries)
+                })
+                .collect::<IndexMap<_, _>>();
+            record("entries", chains);
+            return Ok(());
+        }
+
+        if chains.is_empty() {
+            eprintln!("No address book entries found.");
+            return Ok(());
+        }
+
+        for (ind, (chain_id, entries)) in chains.iter().enumerate() {
+            if ind > 0 {
+                println!();
+            }
+            println!("{}", format!("[{}]", chain_id).bright_green());
+
+            for (name, (address, is_builtin)) in entries.iter() {
+                if *is_builtin {
+                    println!("{}: {:#064x} (built-in)", name.bright_yellow(), address);
+                } else {
+                    println!("{}: {:#064x}", name.bright_yellow(), address);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
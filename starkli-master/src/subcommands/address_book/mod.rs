@@ -0,0 +1,43 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+mod add;
+use add::Add;
+
+mod remove;
+use remove::Remove;
+
+mod list;
+use list::List;
+
+mod import;
+use import::Import;
+
+#[derive(Debug, Parser)]
+pub struct AddressBook {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(about = "Add an entry to the address book")]
+    Add(Add),
+    #[clap(about = "Remove an entry from the address book")]
+    Remove(Remove),
+    #[clap(about = "List address book entries")]
+    List(List),
+    #[clap(about = "Import entries from a TOML or JSON file")]
+    Import(Import),
+}
+
+impl AddressBook {
+    pub async fn run(self) -> Result<()> {
+        match self.command {
+            Subcommands::Add(cmd) => cmd.run().await,
+            Subcommands::Remove(cmd) => cmd.run().await,
+            Subcommands::List(cmd) => cmd.run(),
+            Subcommands::Import(cmd) => cmd.run().await,
+        }
+    }
+}
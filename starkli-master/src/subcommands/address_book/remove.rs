@@ -0,0 +1,47 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use crate::address_book::{AddressBook, ChainIdArgs};
+
+#[derive(Debug, Parser)]
+pub struct Remove {
+    #[clap(flatten)]
+    chain_id: ChainIdArgs,
+    #[clap(help = "Name of the entry to remove")]
+    name: String,
+}
+
+impl Remove {
+    pub async fn run(self) -> Result<()> {
+        let chain_id = self.chain_id.resolve().await?;
+
+        let mut address_book = AddressBook::load()?;
+        let entries = address_book
+            .chains
+            .get_mut(&chain_id)
+            .ok_or_else(|| anyhow::anyhow!("no address book entries for chain {}", chain_id))?;
+
+        if entries.shift_remove(&self.name).is_none() {
+            anyhow::bail!(
+                "entry `{}` not found for chain {}. Built-in entries cannot be removed",
+                self.name,
+                chain_id
+            );
+        }
+
+        if entries.is_empty() {
+            address_book.chains.shift_remove(&chain_id);
+        }
+
+        address_book.save()?;
+
+        eprintln!(
+            "Entry {} removed for chain {}.",
+            self.name.bright_yellow(),
+            chain_id.bright_yellow()
+        );
+
+        Ok(())
+    }
+}
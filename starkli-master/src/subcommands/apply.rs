@@ -0,0 +1,468 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use indexmap::IndexMap;
+use starknet::{
+    accounts::Call,
+    core::{
+        types::{
+            contract::SierraClass, BlockId, BlockTag, FieldElement, FlattenedSierraClass,
+            StarknetError,
+        },
+        utils::{
+            get_udc_deployed_address, parse_cairo_short_string, UdcUniqueSettings, UdcUniqueness,
+        },
+    },
+    providers::{Provider, ProviderError},
+};
+
+use crate::{
+    abi::AbiSource,
+    account::AccountArgs,
+    address_book::AddressBookResolver,
+    calls_file::{parse_variable, substitute_variables},
+    casm::CasmArgs,
+    decode::FeltDecoder,
+    deploy_manifest::{
+        DeclaredClassState, DeployManifest, DeployState, DeployedContractState, ManifestClass,
+        SentCallState,
+    },
+    fee::FeeArgs,
+    output::{print_text, record, record_transaction},
+    path::ExpandedPathbufParser,
+    profile::ProfileDefaults,
+    provider::ExtendedProvider,
+    scarb::{ScarbContract, DEFAULT_SCARB_PROFILE},
+    subcommands::{declare::declare_sierra_class, invoke::ExecutionArgs},
+    udc::{udc_deploy_call, UdcArgs},
+    utils::{is_class_declared, parse_felt_value, watch_tx},
+    verbosity::VerbosityArgs,
+    ProviderArgs,
+};
+
+#[derive(Debug, Parser)]
+pub struct Apply {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(flatten)]
+    account: AccountArgs,
+    #[clap(flatten)]
+    fee: FeeArgs,
+    #[clap(flatten)]
+    udc: UdcArgs,
+    #[clap(
+        long,
+        value_parser = ExpandedPathbufParser,
+        help = "Path to the state file recording applied steps. Defaults to the manifest path with \
+        the `.state.json` extension"
+    )]
+    state: Option<PathBuf>,
+    #[clap(
+        long = "var",
+        value_parser = parse_variable,
+        help = "Set a variable used in the manifest, in the format of `name=value`. Overrides \
+        variables defined in the file"
+    )]
+    vars: Vec<(String, String)>,
+    #[clap(
+        long,
+        env = "STARKNET_POLL_INTERVAL",
+        help = "Transaction result poll interval in milliseconds. Falls back to the default of the \
+        active profile, or 5000 if not set"
+    )]
+    poll_interval: Option<u64>,
+    #[clap(
+        value_parser = ExpandedPathbufParser,
+        help = "Path to the TOML deployment manifest"
+    )]
+    manifest: PathBuf,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+impl Apply {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        // Each step waits for the previous one to confirm, so transactions are always sent
+        if self.fee.clone().into_setting()?.is_estimate_only() {
+            anyhow::bail!("--estimate-only is not supported when applying manifests");
+        }
+
+        let manifest = DeployManifest::load(&self.manifest)?;
+        let manifest_dir = self
+            .manifest
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+
+        let state_path = self
+            .state
+            .clone()
+            .unwrap_or_else(|| self.manifest.with_extension("state.json"));
+        let mut state = DeployState::load(&state_path)?;
+
+        let provider = Arc::new(self.provider.clone().into_provider()?);
+        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+        let udc_address = self.udc.clone().resolve(provider.udc_address());
+
+        let account = self.account.clone().resolve_unsigned(&provider).await?;
+        let account_address = account.address;
+        let chain_id = parse_cairo_short_string(&account.chain_id)
+            .unwrap_or_else(|_| format!("{:#x}", account.chain_id));
+
+        let mut variables = manifest.variables.clone();
+        for (name, value) in self.vars.iter() {
+            variables.insert(name.to_owned(), value.to_owned());
+        }
+        check_reserved_variables(&variables)?;
+        variables.insert(
+            String::from("account"),
+            format!("{:#064x}", account_address),
+        );
+
+        for (name, class_entry) in manifest.classes.iter() {
+            let (sierra_path, casm_path) = resolve_class_files(&manifest_dir, name, class_entry)?;
+
+            let class: SierraClass = serde_json::from_reader(std::fs::File::open(&sierra_path)?)
+                .map_err(|err| {
+                    anyhow::anyhow!("class `{}`: invalid Sierra class: {}", name, err)
+                })?;
+            let class_hash = class.class_hash()?;
+
+            let transaction_hash = if is_class_declared(&provider, class_hash).await? {
+                eprintln!(
+                    "Class {} already declared: {}",
+                    name.bright_yellow(),
+                    format!("{:#064x}", class_hash).bright_yellow()
+                );
+
+                // Keeps the transaction from a previous run if any
+                state
+                    .chain(&chain_id)
+                    .classes
+                    .get(name)
+                    .filter(|existing| existing.class_hash == class_hash)
+                    .and_then(|existing| existing.transaction_hash)
+            } else {
+                eprintln!(
+                    "Declaring class {}: {}",
+                    name.bright_yellow(),
+                    format!("{:#064x}", class_hash).bright_yellow()
+                );
+
+                let casm_args = match casm_path {
+                    Some(casm_path) => CasmArgs::from_casm_file(casm_path),
+                    None => CasmArgs::default(),
+                };
+                let casm_class_hash = casm_args
+                    .into_casm_hash_source(&provider)
+                    .await?
+                    .get_casm_hash(&class)?;
+
+                Some(
+                    self.declare(&provider, Arc::new(class.flatten()?), casm_class_hash)
+                        .await?,
+                )
+            };
+
+            variables.insert(format!("classes.{}", name), format!("{:#064x}", class_hash));
+
+            state.chain(&chain_id).classes.insert(
+                name.to_owned(),
+                DeclaredClassState {
+                    class_hash,
+                    transaction_hash,
+                },
+            );
+            state.save(&state_path)?;
+        }
+
+        for (name, contract) in manifest.contracts.iter() {
+            let substitute = |raw: &str| {
+                substitute_variables(raw, &variables)
+                    .map_err(|err| anyhow::anyhow!("contract `{}`: {}", name, err))
+            };
+
+            let class = substitute(&contract.class)?;
+            let class_hash = match variables.get(&format!("classes.{}", class)) {
+                Some(class_hash) => parse_felt_value(class_hash)?,
+                None => parse_felt_value(&class).map_err(|_| {
+                    anyhow::anyhow!(
+                        "contract `{}`: `{}` is neither a class in the manifest nor a class hash",
+                        name,
+                        class
+                    )
+                })?,
+            };
+            let salt = parse_felt_value(&substitute(&contract.salt)?)?;
+
+            let ctor_args = contract
+                .constructor_args
+                .iter()
+                .map(|arg| substitute(&arg.to_raw()))
+                .collect::<Result<Vec<_>>>()?;
+            let ctor_args = AbiSource::Network
+                .abi_of_class(&provider, class_hash, BlockId::Tag(BlockTag::Pending))
+                .await?
+                .encode_constructor_call(&ctor_args, &felt_decoder)
+                .await?;
+
+            let udc_uniqueness = if contract.unique {
+                UdcUniqueness::Unique(UdcUniqueSettings {
+                    deployer_address: account_address,
+                    udc_contract_address: udc_address,
+                })
+            } else {
+                UdcUniqueness::NotUnique
+            };
+            let address = get_udc_deployed_address(salt, class_hash, &udc_uniqueness, &ctor_args);
+
+            let transaction_hash = match provider
+                .get_class_hash_at(BlockId::Tag(BlockTag::Pending), address)
+                .await
+            {
+                Ok(deployed_class_hash) => {
+                    if deployed_class_hash != class_hash {
+                        anyhow::bail!(
+                            "contract `{}` at {:#064x} has class {:#064x} instead of {:#064x}",
+                            name,
+                            address,
+                            deployed_class_hash,
+                            class_hash
+                        );
+                    }
+
+                    eprintln!(
+                        "Contract {} already deployed at {}",
+                        name.bright_yellow(),
+                        format!("{:#064x}", address).bright_yellow()
+                    );
+
+                    state
+                        .chain(&chain_id)
+                        .contracts
+                        .get(name)
+                        .filter(|existing| existing.address == address)
+                        .and_then(|existing| existing.transaction_hash)
+                }
+                Err(ProviderError::StarknetError(StarknetError::ContractNotFound)) => {
+                    eprintln!(
+                        "Deploying contract {} at {}",
+                        name.bright_yellow(),
+                        format!("{:#064x}", address).bright_yellow()
+                    );
+
+                    Some(
+                        self.execute(
+                            &provider,
+                            udc_deploy_call(
+                                udc_address,
+                                class_hash,
+                                salt,
+                                contract.unique,
+                                &ctor_args,
+                            ),
+                        )
+                        .await?,
+                    )
+                }
+                Err(err) => return Err(err.into()),
+            };
+
+            variables.insert(format!("contracts.{}", name), format!("{:#064x}", address));
+
+            state.chain(&chain_id).contracts.insert(
+                name.to_owned(),
+                DeployedContractState {
+                    address,
+                    class_hash,
+                    transaction_hash,
+                },
+            );
+            state.save(&state_path)?;
+        }
+
+        for (name, call) in manifest.calls.iter() {
+            // Unlike declarations and deployments, there's no way to tell from the chain whether a
+            // call has been made, so the state file is the only source of truth here
+            if let Some(sent) = state.chain(&chain_id).calls.get(name) {
+                eprintln!(
+                    "Call {} already sent in transaction {}",
+                    name.bright_yellow(),
+                    format!("{:#064x}", sent.transaction_hash).bright_yellow()
+                );
+                continue;
+            }
+
+            let substitute = |raw: &str| {
+                substitute_variables(raw, &variables)
+                    .map_err(|err| anyhow::anyhow!("call `{}`: {}", name, err))
+            };
+
+            let to = felt_decoder
+                .decode_single_with_addr_fallback(&substitute(&call.to)?)
+                .await?;
+            let selector = felt_decoder
+                .decode_single_with_selector_fallback(&substitute(&call.function)?)
+                .await?;
+            let args = call
+                .args
+                .iter()
+                .map(|arg| substitute(&arg.to_raw()))
+                .collect::<Result<Vec<_>>>()?;
+            let calldata = AbiSource::Network
+                .abi_at(&provider, to, BlockId::Tag(BlockTag::Pending))
+                .await?
+                .encode_function_call(selector, &args, &felt_decoder)
+                .await?;
+
+            eprintln!(
+                "Calling {} on {}",
+                call.function.bright_yellow(),
+                format!("{:#064x}", to).bright_yellow()
+            );
+
+            let transaction_hash = self
+                .execute(
+                    &provider,
+                    Call {
+                        to,
+                        selector,
+                        calldata,
+                    },
+                )
+                .await?;
+
+            state
+                .chain(&chain_id)
+                .calls
+                .insert(name.to_owned(), SentCallState { transaction_hash });
+            state.save(&state_path)?;
+        }
+
+        eprintln!("Manifest applied. State written to:");
+
+        // The state for the current chain replaces the results of the individual transactions
+        record("state", state.chain(&chain_id));
+        print_text("state_file", state_path.display());
+
+        Ok(())
+    }
+
+    /// Sends a single call and waits for it to confirm.
+    async fn execute(&self, provider: &Arc<ExtendedProvider>, call: Call) -> Result<FieldElement> {
+        ExecutionArgs::watching(self.poll_interval)
+            .execute(
+                provider.clone(),
+                self.account.clone(),
+                self.fee.clone().into_setting()?,
+                vec![call],
+            )
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("transaction not sent"))
+    }
+
+    /// Declares a Sierra class and waits for the declaration to confirm.
+    async fn declare(
+        &self,
+        provider: &Arc<ExtendedProvider>,
+        class: Arc<FlattenedSierraClass>,
+        compiled_class_hash: FieldElement,
+    ) -> Result<FieldElement> {
+        let account = self.account.clone().resolve(provider).await?;
+
+        let transaction_hash = declare_sierra_class(
+            provider,
+            account,
+            class,
+            compiled_class_hash,
+            None,
+            self.fee.clone().into_setting()?,
+            false,
+        )
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("transaction not sent"))?;
+
+        eprintln!(
+            "Contract declaration transaction: {}",
+            format!("{:#064x}", transaction_hash).bright_yellow()
+        );
+        record_transaction(transaction_hash);
+
+        eprintln!(
+            "Waiting for transaction {} to confirm...",
+            format!("{:#064x}", transaction_hash).bright_yellow(),
+        );
+        watch_tx(
+            provider,
+            transaction_hash,
+            ProfileDefaults::resolve_poll_interval(self.poll_interval)?,
+        )
+        .await?;
+
+        Ok(transaction_hash)
+    }
+}
+
+/// Finds the Sierra artifact of a manifest class, along with its CASM artifact if available.
+fn resolve_class_files(
+    manifest_dir: &Path,
+    name: &str,
+    class: &ManifestClass,
+) -> Result<(PathBuf, Option<PathBuf>)> {
+    match (&class.file, &class.contract) {
+        (Some(file), None) => Ok((
+            manifest_dir.join(file),
+            class
+                .casm_file
+                .as_ref()
+                .map(|casm_file| manifest_dir.join(casm_file)),
+        )),
+        (None, Some(contract)) => {
+            if class.casm_file.is_some() {
+                anyhow::bail!(
+                    "class `{}`: `casm_file` cannot be used with Scarb contracts",
+                    name
+                );
+            }
+
+            let scarb_manifest = manifest_dir.join(
+                class
+                    .scarb
+                    .as_deref()
+                    .unwrap_or_else(|| Path::new("Scarb.toml")),
+            );
+            let contracts = ScarbContract::load_all(
+                &scarb_manifest,
+                class
+                    .scarb_profile
+                    .as_deref()
+                    .unwrap_or(DEFAULT_SCARB_PROFILE),
+            )?;
+            let contract = ScarbContract::find(contracts, contract)?;
+
+            Ok((contract.sierra_path, contract.casm_path))
+        }
+        _ => Err(anyhow::anyhow!(
+            "class `{}`: exactly one of `file` and `contract` must be set",
+            name
+        )),
+    }
+}
+
+/// Makes sure the manifest variables are not shadowed by step outputs, which would be confusing.
+fn check_reserved_variables(variables: &IndexMap<String, String>) -> Result<()> {
+    for name in variables.keys() {
+        if name == "account" || name.starts_with("classes.") || name.starts_with("contracts.") {
+            anyhow::bail!("variable name `{}` is reserved", name);
+        }
+    }
+
+    Ok(())
+}
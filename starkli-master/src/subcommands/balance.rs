@@ -1,33 +1,30 @@
 use std::sync::Arc;
 
 use anyhow::Result;
-use bigdecimal::BigDecimal;
 use clap::Parser;
-use num_bigint::{BigUint, ToBigInt};
-use starknet::{
-    core::types::{BlockId, BlockTag, FieldElement, FunctionCall},
-    macros::selector,
-    providers::Provider,
-};
+use num_bigint::BigUint;
+use starknet::core::types::FieldElement;
 
 use crate::{
-    address_book::AddressBookResolver, decode::FeltDecoder, verbosity::VerbosityArgs, ProviderArgs,
+    address_book::AddressBookResolver,
+    block::BlockArgs,
+    decode::FeltDecoder,
+    erc20::{format_amount, get_balance, get_decimals, get_symbol, DEFAULT_ETH_ADDRESS},
+    output::{is_json_output, print_text, record},
+    verbosity::VerbosityArgs,
+    ProviderArgs,
 };
 
-/// The default ETH address: 0x049d36570d4e46f48e99674bd3fcc84644ddd6b96f7c741b1562b82f9e004dc7.
-const DEFAULT_ETH_ADDRESS: FieldElement = FieldElement::from_mont([
-    4380532846569209554,
-    17839402928228694863,
-    17240401758547432026,
-    418961398025637529,
-]);
-
 #[derive(Debug, Parser)]
 pub struct Balance {
     #[clap(flatten)]
     provider: ProviderArgs,
-    #[clap(help = "Account address")]
-    account_address: String,
+    #[clap(
+        long = "token",
+        help = "Token contract address or address book entry name (e.g. strk). Can be used \
+        multiple times. Defaults to ETH"
+    )]
+    tokens: Vec<String>,
     #[clap(
         long,
         conflicts_with = "hex",
@@ -40,51 +37,183 @@ pub struct Balance {
         help = "Display balance amount in hexadecimal representation"
     )]
     hex: bool,
+    #[clap(
+        required = true,
+        help = "Account addresses. A table is shown when more than one account or token is queried"
+    )]
+    account_addresses: Vec<String>,
+    #[clap(flatten)]
+    block: BlockArgs,
     #[clap(flatten)]
     verbosity: VerbosityArgs,
 }
 
+struct Token {
+    address: FieldElement,
+    /// Only fetched when formatting balances in token units.
+    decimals: Option<u8>,
+}
+
 impl Balance {
     pub async fn run(self) -> Result<()> {
         self.verbosity.setup_logging();
 
         let provider = Arc::new(self.provider.into_provider()?);
+        let block_id = self.block.resolve(provider.as_ref()).await?;
         let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
 
-        let account_address = felt_decoder
-            .decode_single_with_addr_fallback(&self.account_address)
-            .await?;
+        let token_addresses = if self.tokens.is_empty() {
+            vec![DEFAULT_ETH_ADDRESS]
+        } else {
+            let mut token_addresses = vec![];
+            for token in self.tokens.iter() {
+                token_addresses.push(felt_decoder.decode_single_with_addr_fallback(token).await?);
+            }
+            token_addresses
+        };
+
+        let mut tokens = vec![];
+        for address in token_addresses.into_iter() {
+            let decimals = if self.raw || self.hex {
+                None
+            } else {
+                Some(get_decimals(provider.as_ref(), address, block_id).await?)
+            };
+
+            tokens.push(Token { address, decimals });
+        }
+
+        let mut account_addresses = vec![];
+        for account_address in self.account_addresses.iter() {
+            account_addresses.push(
+                felt_decoder
+                    .decode_single_with_addr_fallback(account_address)
+                    .await?,
+            );
+        }
 
-        let result = provider
-            .call(
-                FunctionCall {
-                    contract_address: DEFAULT_ETH_ADDRESS,
-                    entry_point_selector: selector!("balanceOf"),
-                    calldata: vec![account_address],
-                },
-                BlockId::Tag(BlockTag::Pending),
+        if tokens.len() == 1 && account_addresses.len() == 1 {
+            let token = &tokens[0];
+            let balance = get_balance(
+                provider.as_ref(),
+                token.address,
+                account_addresses[0],
+                block_id,
             )
             .await?;
 
-        if result.len() != 2 {
-            anyhow::bail!("unexpected call result size: {}", result.len());
+            print_text("balance", self.format_balance(balance, token.decimals));
+
+            return Ok(());
         }
 
-        let low = BigUint::from_bytes_be(&result[0].to_bytes_be());
-        let high = BigUint::from_bytes_be(&result[1].to_bytes_be());
+        let mut symbols = vec![];
+        for token in tokens.iter() {
+            symbols.push(
+                get_symbol(provider.as_ref(), token.address, block_id)
+                    .await
+                    .ok(),
+            );
+        }
 
-        let raw_balance: BigUint = (high << 128) + low;
+        let mut rows = vec![];
+        for account_address in account_addresses.into_iter() {
+            let mut row = vec![format!("{:#064x}", account_address)];
+            for token in tokens.iter() {
+                let balance =
+                    get_balance(provider.as_ref(), token.address, account_address, block_id)
+                        .await?;
+                row.push(self.format_balance(balance, token.decimals));
+            }
+            rows.push(row);
+        }
 
-        if self.raw {
-            println!("{}", raw_balance);
-        } else if self.hex {
-            println!("{:#x}", raw_balance);
+        if is_json_output() {
+            // Balances are keyed by token address, as symbols are neither unique nor always
+            // available
+            let balances = rows
+                .into_iter()
+                .map(|row| {
+                    let mut row = row.into_iter();
+                    // Safe to unwrap as each row starts with the account address
+                    let account = row.next().unwrap();
+                    let balances = tokens
+                        .iter()
+                        .zip(symbols.iter())
+                        .zip(row)
+                        .map(|((token, symbol), balance)| {
+                            (
+                                format!("{:#064x}", token.address),
+                                serde_json::json!({
+                                    "symbol": symbol,
+                                    "balance": balance,
+                                }),
+                            )
+                        })
+                        .collect::<serde_json::Map<_, _>>();
+                    serde_json::json!({
+                        "account": account,
+                        "balances": balances,
+                    })
+                })
+                .collect::<Vec<_>>();
+            record("balances", balances);
         } else {
-            // `to_bigint()` from `BigUint` always returns `Some`.
-            let balance_dec = BigDecimal::new(raw_balance.to_bigint().unwrap(), 18);
-            println!("{}", balance_dec);
+            let mut headers = vec![String::from("Account")];
+            for (token, symbol) in tokens.iter().zip(symbols.into_iter()) {
+                // Symbols are only used for display so it's fine to fall back to addresses
+                headers.push(symbol.unwrap_or_else(|| format!("{:#064x}", token.address)));
+            }
+
+            print_table(&headers, &rows);
         }
 
         Ok(())
     }
+
+    fn format_balance(&self, raw_balance: BigUint, decimals: Option<u8>) -> String {
+        match decimals {
+            Some(decimals) if !self.raw && !self.hex => format_amount(&raw_balance, decimals),
+            _ => {
+                if self.hex {
+                    format!("{:#x}", raw_balance)
+                } else {
+                    raw_balance.to_string()
+                }
+            }
+        }
+    }
+}
+
+/// Prints the table to stdout, with balance columns aligned to the right.
+fn print_table(headers: &[String], rows: &[Vec<String>]) {
+    let mut widths = headers
+        .iter()
+        .map(|header| header.len())
+        .collect::<Vec<_>>();
+    for row in rows.iter() {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.len());
+        }
+    }
+
+    let format_row = |row: &[String]| {
+        row.iter()
+            .zip(widths.iter())
+            .enumerate()
+            .map(|(ind, (cell, width))| {
+                if ind == 0 {
+                    format!("{:<width$}", cell, width = width)
+                } else {
+                    format!("{:>width$}", cell, width = width)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+    };
+
+    println!("{}", format_row(headers));
+    for row in rows.iter() {
+        println!("{}", format_row(row));
+    }
 }
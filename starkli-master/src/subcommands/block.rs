@@ -1,9 +1,12 @@
 use anyhow::Result;
 use clap::Parser;
-use colored_json::{ColorMode, Output};
 use starknet::providers::Provider;
 
-use crate::{utils::parse_block_id, verbosity::VerbosityArgs, ProviderArgs};
+use crate::{
+    utils::{parse_block_id, print_colored_json},
+    verbosity::VerbosityArgs,
+    ProviderArgs,
+};
 
 #[derive(Debug, Parser)]
 pub struct Block {
@@ -34,9 +37,7 @@ impl Block {
             serde_json::to_value(provider.get_block_with_tx_hashes(block_id).await?)?
         };
 
-        let block_json =
-            colored_json::to_colored_json(&block_json, ColorMode::Auto(Output::StdOut))?;
-        println!("{block_json}");
+        print_colored_json(&block_json)?;
 
         Ok(())
     }
@@ -2,7 +2,7 @@ use anyhow::Result;
 use clap::Parser;
 use starknet::providers::Provider;
 
-use crate::{verbosity::VerbosityArgs, ProviderArgs};
+use crate::{output::print_text, verbosity::VerbosityArgs, ProviderArgs};
 
 #[derive(Debug, Parser)]
 pub struct BlockHash {
@@ -20,7 +20,7 @@ impl BlockHash {
 
         let block = provider.block_hash_and_number().await?;
 
-        println!("{:#064x}", block.block_hash);
+        print_text("block_hash", format!("{:#064x}", block.block_hash));
 
         Ok(())
     }
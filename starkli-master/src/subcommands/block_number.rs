@@ -2,7 +2,7 @@ use anyhow::Result;
 use clap::Parser;
 use starknet::providers::Provider;
 
-use crate::{verbosity::VerbosityArgs, ProviderArgs};
+use crate::{output::print_text, verbosity::VerbosityArgs, ProviderArgs};
 
 #[derive(Debug, Parser)]
 pub struct BlockNumber {
@@ -20,7 +20,7 @@ impl BlockNumber {
 
         let block = provider.block_hash_and_number().await?;
 
-        println!("{}", block.block_number);
+        print_text("block_number", block.block_number);
 
         Ok(())
     }
@@ -3,7 +3,7 @@ use chrono::{TimeZone, Utc};
 use clap::Parser;
 use starknet::{core::types::MaybePendingBlockWithTxHashes, providers::Provider};
 
-use crate::{utils::parse_block_id, verbosity::VerbosityArgs, ProviderArgs};
+use crate::{output::print_text, utils::parse_block_id, verbosity::VerbosityArgs, ProviderArgs};
 
 #[derive(Debug, Parser)]
 pub struct BlockTime {
@@ -45,7 +45,7 @@ impl BlockTime {
         };
 
         if self.unix {
-            println!("{timestamp}");
+            print_text("timestamp", timestamp);
         } else {
             let timestamp = Utc
                 .timestamp_opt(
@@ -56,9 +56,9 @@ impl BlockTime {
                 )
                 .unwrap();
             if self.rfc2822 {
-                println!("{}", timestamp.to_rfc2822())
+                print_text("timestamp", timestamp.to_rfc2822())
             } else {
-                println!("{}", timestamp.to_rfc3339())
+                print_text("timestamp", timestamp.to_rfc3339())
             }
         }
 
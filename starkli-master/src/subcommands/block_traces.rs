@@ -1,9 +1,12 @@
 use anyhow::Result;
 use clap::Parser;
-use colored_json::{ColorMode, Output};
 use starknet::providers::Provider;
 
-use crate::{utils::parse_block_id, verbosity::VerbosityArgs, ProviderArgs};
+use crate::{
+    utils::{parse_block_id, print_colored_json},
+    verbosity::VerbosityArgs,
+    ProviderArgs,
+};
 
 #[derive(Debug, Parser)]
 pub struct BlockTraces {
@@ -28,9 +31,7 @@ impl BlockTraces {
 
         let traces_json = serde_json::to_value(provider.trace_block_transactions(block_id).await?)?;
 
-        let traces_json =
-            colored_json::to_colored_json(&traces_json, ColorMode::Auto(Output::StdOut))?;
-        println!("{traces_json}");
+        print_colored_json(&traces_json)?;
 
         Ok(())
     }
@@ -2,27 +2,46 @@ use std::sync::Arc;
 
 use anyhow::Result;
 use clap::Parser;
-use starknet::{
-    core::types::{BlockId, BlockTag, FunctionCall},
-    providers::Provider,
-};
+use starknet::{core::types::FunctionCall, providers::Provider};
 
 use crate::{
-    address_book::AddressBookResolver, decode::FeltDecoder, error::provider_error_mapper,
-    verbosity::VerbosityArgs, ProviderArgs,
+    abi::{AbiArgs, AbiSource},
+    address_book::AddressBookResolver,
+    block::BlockArgs,
+    decode::FeltDecoder,
+    error::provider_error_mapper,
+    output::{is_json_output, record},
+    utils::print_colored_json,
+    verbosity::VerbosityArgs,
+    ProviderArgs,
 };
 
 #[derive(Debug, Parser)]
 pub struct Call {
     #[clap(flatten)]
     provider: ProviderArgs,
+    #[clap(flatten)]
+    abi: AbiArgs,
+    #[clap(
+        long,
+        help = "Decode the call result into typed values using the contract ABI"
+    )]
+    decode: bool,
+    #[clap(
+        long,
+        requires = "decode",
+        help = "Show felt252 values that are valid Cairo short strings as text"
+    )]
+    short_strings: bool,
     #[clap(help = "Contract address")]
     contract_address: String,
     #[clap(help = "Name of the function being called")]
     selector: String,
-    #[clap(help = "Raw function call arguments")]
+    #[clap(help = "Function call arguments (raw field elements unless --typed or --abi is used)")]
     calldata: Vec<String>,
     #[clap(flatten)]
+    block: BlockArgs,
+    #[clap(flatten)]
     verbosity: VerbosityArgs,
 }
 
@@ -30,7 +49,10 @@ impl Call {
     pub async fn run(self) -> Result<()> {
         self.verbosity.setup_logging();
 
+        let abi_source = self.abi.into_source()?;
+
         let provider = Arc::new(self.provider.into_provider()?);
+        let block_id = self.block.resolve(provider.as_ref()).await?;
         let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
 
         let contract_address = felt_decoder
@@ -40,10 +62,36 @@ impl Call {
             .decode_single_with_selector_fallback(&self.selector)
             .await?;
 
-        let mut calldata = vec![];
-        for element in self.calldata.iter() {
-            calldata.append(&mut felt_decoder.decode(element).await?);
-        }
+        // The ABI is needed for either encoding the arguments or decoding the result
+        let abi = match (&abi_source, self.decode) {
+            (Some(abi_source), _) => Some(
+                abi_source
+                    .abi_at(&provider, contract_address, block_id)
+                    .await?
+                    .into_owned(),
+            ),
+            (None, true) => Some(
+                AbiSource::Network
+                    .abi_at(&provider, contract_address, block_id)
+                    .await?
+                    .into_owned(),
+            ),
+            (None, false) => None,
+        };
+
+        let calldata = match (&abi_source, &abi) {
+            (Some(_), Some(abi)) => {
+                abi.encode_function_call(selector, &self.calldata, &felt_decoder)
+                    .await?
+            }
+            _ => {
+                let mut calldata = vec![];
+                for element in self.calldata.iter() {
+                    calldata.append(&mut felt_decoder.decode(element).await?);
+                }
+                calldata
+            }
+        };
 
         let result = provider
             .call(
@@ -52,12 +100,23 @@ impl Call {
                     entry_point_selector: selector,
                     calldata,
                 },
-                BlockId::Tag(BlockTag::Pending),
+                block_id,
             )
             .await
             .map_err(provider_error_mapper)?;
 
-        if result.is_empty() {
+        if let (true, Some(abi)) = (self.decode, &abi) {
+            let decoded = abi.decode_function_outputs(selector, &result, self.short_strings)?;
+            print_colored_json(&decoded)?;
+        } else if is_json_output() {
+            record(
+                "result",
+                result
+                    .iter()
+                    .map(|element| format!("{:#064x}", element))
+                    .collect::<Vec<_>>(),
+            );
+        } else if result.is_empty() {
             println!("[]");
         } else {
             println!("[");
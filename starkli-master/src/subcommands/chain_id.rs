@@ -2,7 +2,7 @@ use anyhow::Result;
 use clap::Parser;
 use starknet::{core::utils::parse_cairo_short_string, providers::Provider};
 
-use crate::{verbosity::VerbosityArgs, ProviderArgs};
+use crate::{output::print_text, verbosity::VerbosityArgs, ProviderArgs};
 
 #[derive(Debug, Parser)]
 pub struct ChainId {
@@ -32,19 +32,22 @@ impl ChainId {
 
         let raw_chain_id = provider.chain_id().await?;
 
-        println!(
-            "{}{}",
-            if self.dec {
-                format!("{raw_chain_id}")
-            } else {
-                format!("{raw_chain_id:#x}")
-            },
-            if self.no_decode {
-                "".into()
-            } else {
-                let decoded = parse_cairo_short_string(&raw_chain_id)?;
-                format!(" ({decoded})")
-            }
+        print_text(
+            "chain_id",
+            format!(
+                "{}{}",
+                if self.dec {
+                    format!("{raw_chain_id}")
+                } else {
+                    format!("{raw_chain_id:#x}")
+                },
+                if self.no_decode {
+                    "".into()
+                } else {
+                    let decoded = parse_cairo_short_string(&raw_chain_id)?;
+                    format!(" ({decoded})")
+                }
+            ),
         );
 
         Ok(())
@@ -1,11 +1,12 @@
 use anyhow::Result;
 use clap::Parser;
 use starknet::{
-    core::types::{BlockId, BlockTag, ContractClass, FieldElement},
+    core::types::{ContractClass, FieldElement},
     providers::Provider,
 };
 
 use crate::{
+    block::BlockArgs,
     utils::{parse_compressed_legacy_class, parse_flattened_sierra_class, print_colored_json},
     verbosity::VerbosityArgs,
     ProviderArgs,
@@ -23,6 +24,8 @@ pub struct ClassAt {
     #[clap(help = "Contract address")]
     address: String,
     #[clap(flatten)]
+    block: BlockArgs,
+    #[clap(flatten)]
     verbosity: VerbosityArgs,
 }
 
@@ -31,12 +34,10 @@ impl ClassAt {
         self.verbosity.setup_logging();
 
         let provider = self.provider.into_provider()?;
+        let block_id = self.block.resolve(&provider).await?;
         let address = FieldElement::from_hex_be(&self.address)?;
 
-        // TODO: allow custom block
-        let class = provider
-            .get_class_at(BlockId::Tag(BlockTag::Pending), address)
-            .await?;
+        let class = provider.get_class_at(block_id, address).await?;
 
         if self.parse {
             match class {
@@ -1,11 +1,12 @@
 use anyhow::Result;
 use clap::Parser;
 use starknet::{
-    core::types::{BlockId, BlockTag, ContractClass, FieldElement},
+    core::types::{ContractClass, FieldElement},
     providers::Provider,
 };
 
 use crate::{
+    block::BlockArgs,
     utils::{parse_compressed_legacy_class, parse_flattened_sierra_class, print_colored_json},
     verbosity::VerbosityArgs,
     ProviderArgs,
@@ -23,6 +24,8 @@ pub struct ClassByHash {
     #[clap(help = "Class hash")]
     hash: String,
     #[clap(flatten)]
+    block: BlockArgs,
+    #[clap(flatten)]
     verbosity: VerbosityArgs,
 }
 
@@ -31,12 +34,10 @@ impl ClassByHash {
         self.verbosity.setup_logging();
 
         let provider = self.provider.into_provider()?;
+        let block_id = self.block.resolve(&provider).await?;
         let class_hash = FieldElement::from_hex_be(&self.hash)?;
 
-        // TODO: allow custom block
-        let class = provider
-            .get_class(BlockId::Tag(BlockTag::Pending), class_hash)
-            .await?;
+        let class = provider.get_class(block_id, class_hash).await?;
 
         if self.parse {
             match class {
@@ -4,7 +4,7 @@ use anyhow::Result;
 use clap::Parser;
 use starknet::core::types::contract::{legacy::LegacyContractClass, CompiledClass, SierraClass};
 
-use crate::path::ExpandedPathbufParser;
+use crate::{output::print_text, path::ExpandedPathbufParser};
 
 #[derive(Debug, Parser)]
 pub struct ClassHash {
@@ -36,7 +36,7 @@ impl ClassHash {
             anyhow::bail!("failed to parse contract artifact");
         };
 
-        println!("{class_hash:#064x}");
+        print_text("class_hash", format!("{class_hash:#064x}"));
 
         Ok(())
     }
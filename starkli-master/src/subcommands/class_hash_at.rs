@@ -1,11 +1,8 @@
 use anyhow::Result;
 use clap::Parser;
-use starknet::{
-    core::types::{BlockId, BlockTag, FieldElement},
-    providers::Provider,
-};
+use starknet::{core::types::FieldElement, providers::Provider};
 
-use crate::{verbosity::VerbosityArgs, ProviderArgs};
+use crate::{block::BlockArgs, output::print_text, verbosity::VerbosityArgs, ProviderArgs};
 
 #[derive(Debug, Parser)]
 pub struct ClassHashAt {
@@ -14,6 +11,8 @@ pub struct ClassHashAt {
     #[clap(help = "Contract address")]
     address: String,
     #[clap(flatten)]
+    block: BlockArgs,
+    #[clap(flatten)]
     verbosity: VerbosityArgs,
 }
 
@@ -22,14 +21,12 @@ impl ClassHashAt {
         self.verbosity.setup_logging();
 
         let provider = self.provider.into_provider()?;
+        let block_id = self.block.resolve(&provider).await?;
         let address = FieldElement::from_hex_be(&self.address)?;
 
-        // TODO: allow custom block
-        let class_hash = provider
-            .get_class_hash_at(BlockId::Tag(BlockTag::Pending), address)
-            .await?;
+        let class_hash = provider.get_class_hash_at(block_id, address).await?;
 
-        println!("{:#064x}", class_hash);
+        print_text("class_hash", format!("{:#064x}", class_hash));
 
         Ok(())
     }
@@ -1,31 +1,38 @@
-use std::{path::PathBuf, sync::Arc, time::Duration};
+use std::{path::PathBuf, sync::Arc};
 
 use anyhow::Result;
 use clap::Parser;
 use colored::Colorize;
-use colored_json::{ColorMode, Output};
 use starknet::{
     accounts::Account,
     core::types::{
         contract::{legacy::LegacyContractClass, CompiledClass, SierraClass},
-        BlockId, BlockTag, FieldElement, StarknetError,
+        FieldElement, FlattenedSierraClass,
     },
-    macros::felt,
-    providers::{Provider, ProviderError},
+    providers::Provider,
 };
 
 use crate::{
-    account::AccountArgs,
+    account::{AccountArgs, ResolvedAccount},
     casm::{CasmArgs, CasmHashSource},
     error::account_error_mapper,
-    fee::{FeeArgs, FeeSetting},
+    fee::{
+        print_fee_estimate, record_fee_estimate, EthFeeSetting, FeeArgs, FeeSetting, FeeToken,
+        DEFAULT_FEE_MULTIPLIER,
+    },
+    offline::TransactionFile,
+    output::{is_json_output, print_text, record, record_transaction},
     path::ExpandedPathbufParser,
-    utils::watch_tx,
+    profile::ProfileDefaults,
+    provider::ExtendedProvider,
+    scarb::{ScarbContract, DEFAULT_SCARB_PROFILE},
+    utils::{is_class_declared, print_colored_json, watch_tx},
+    v3::{V3Sender, V3Transaction},
     verbosity::VerbosityArgs,
     ProviderArgs,
 };
 
-#[derive(Debug, Parser)]
+#[derive(Debug, Clone, Parser)]
 pub struct Declare {
     #[clap(flatten)]
     provider: ProviderArgs,
@@ -37,6 +44,13 @@ pub struct Declare {
     fee: FeeArgs,
     #[clap(long, help = "Simulate the transaction only")]
     simulate: bool,
+    #[clap(
+        long,
+        conflicts_with_all = ["simulate", "estimate_only", "watch"],
+        help = "Build the transaction without signing or sending it, and print it as JSON to be \
+        signed with `starkli tx sign`"
+    )]
+    build_only: bool,
     #[clap(long, help = "Provide transaction nonce manually")]
     nonce: Option<FieldElement>,
     #[clap(long, short, help = "Wait for the transaction to confirm")]
@@ -44,23 +58,178 @@ pub struct Declare {
     #[clap(
         long,
         env = "STARKNET_POLL_INTERVAL",
-        default_value = "5000",
-        help = "Transaction result poll interval in milliseconds"
+        help = "Transaction result poll interval in milliseconds. Falls back to the default of the \
+        active profile, or 5000 if not set"
     )]
-    poll_interval: u64,
+    poll_interval: Option<u64>,
     #[clap(
+        long,
+        num_args = 0..=1,
+        default_missing_value = "Scarb.toml",
         value_parser = ExpandedPathbufParser,
+        conflicts_with = "file",
+        help = "Declare contracts built by Scarb. Optionally takes the path to Scarb.toml, which \
+        defaults to the one in the current directory"
+    )]
+    scarb: Option<PathBuf>,
+    #[clap(
+        long,
+        requires = "scarb",
+        help = "Name or module path of the Scarb contract to declare. All contracts in the \
+        project or workspace are declared if not set"
+    )]
+    contract: Option<String>,
+    #[clap(
+        long,
+        requires = "scarb",
+        default_value = DEFAULT_SCARB_PROFILE,
+        help = "Scarb profile the contracts were built with"
+    )]
+    scarb_profile: String,
+    #[clap(
+        value_parser = ExpandedPathbufParser,
+        required_unless_present = "scarb",
         help = "Path to contract artifact file"
     )]
-    file: PathBuf,
+    file: Option<PathBuf>,
     #[clap(flatten)]
     verbosity: VerbosityArgs,
 }
 
+/// Outcome of declaring a single class.
+enum Declaration {
+    Sent {
+        class_hash: FieldElement,
+        transaction_hash: FieldElement,
+    },
+    AlreadyDeclared {
+        class_hash: FieldElement,
+    },
+}
+
 impl Declare {
     pub async fn run(self) -> Result<()> {
         self.verbosity.setup_logging();
 
+        match self.scarb.clone() {
+            Some(manifest_path) => self.run_scarb(manifest_path).await,
+            None => {
+                // Safe to unwrap as clap requires the file when `--scarb` is not used
+                let file = self.file.clone().unwrap();
+
+                match self.clone().run_file(file).await? {
+                    Some(Declaration::Sent {
+                        class_hash,
+                        transaction_hash,
+                    }) => {
+                        record_transaction(transaction_hash);
+                        if self.watch {
+                            self.watch_declaration(transaction_hash).await?;
+                        }
+
+                        eprintln!("Class hash declared:");
+
+                        // Only the class hash goes to stdout so this can be easily scripted
+                        print_text(
+                            "class_hash",
+                            format!("{:#064x}", class_hash).bright_yellow(),
+                        );
+                    }
+                    Some(Declaration::AlreadyDeclared { class_hash }) => {
+                        print_text(
+                            "class_hash",
+                            format!("{:#064x}", class_hash).bright_yellow(),
+                        );
+                        record("already_declared", true);
+                    }
+                    None => {}
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    async fn run_scarb(self, manifest_path: PathBuf) -> Result<()> {
+        let contracts = ScarbContract::load_all(&manifest_path, &self.scarb_profile)?;
+        let contracts = match &self.contract {
+            Some(name) => vec![ScarbContract::find(contracts, name)?],
+            None => contracts,
+        };
+
+        let multiple = contracts.len() > 1;
+        if multiple && self.build_only {
+            anyhow::bail!(
+                "--build-only only works with a single contract. Use --contract to choose one"
+            );
+        }
+        if multiple && self.nonce.is_some() {
+            anyhow::bail!(
+                "--nonce only works with a single contract. Use --contract to choose one"
+            );
+        }
+
+        let mut declared_contracts = vec![];
+        for contract in contracts.into_iter() {
+            eprintln!(
+                "Declaring Scarb contract {} from package {}",
+                contract.module_path.bright_yellow(),
+                contract.package_name.bright_yellow()
+            );
+
+            let class: SierraClass =
+                serde_json::from_reader(std::fs::File::open(&contract.sierra_path)?)?;
+            let mut entry = serde_json::json!({
+                "contract": contract.module_path,
+                "class_hash": format!("{:#064x}", class.class_hash()?),
+            });
+
+            let mut cmd = self.clone();
+            // Compiling is only needed when CASM output is disabled for the target
+            if let Some(casm_path) = contract.casm_path {
+                cmd.casm = CasmArgs::from_casm_file(casm_path);
+            }
+            // Declarations are sent one after another, so each one has to confirm before the next
+            // nonce can be fetched
+            let watch = cmd.watch || multiple;
+
+            // Results are kept in each entry, as top-level fields would be overwritten by the next
+            // contract in JSON mode
+            match cmd.run_file(contract.sierra_path).await? {
+                Some(Declaration::Sent {
+                    class_hash,
+                    transaction_hash,
+                }) => {
+                    entry["transaction_hash"] = format!("{:#064x}", transaction_hash).into();
+                    if watch {
+                        self.watch_declaration(transaction_hash).await?;
+                    }
+
+                    eprintln!("Class hash declared:");
+                    if !is_json_output() {
+                        println!("{}", format!("{:#064x}", class_hash).bright_yellow());
+                    }
+                }
+                Some(Declaration::AlreadyDeclared { class_hash }) => {
+                    entry["already_declared"] = true.into();
+                    if !is_json_output() {
+                        println!("{}", format!("{:#064x}", class_hash).bright_yellow());
+                    }
+                }
+                None => {}
+            }
+
+            declared_contracts.push(entry);
+        }
+
+        record("contracts", declared_contracts);
+
+        Ok(())
+    }
+
+    /// Declares the class in `file` without waiting for confirmation. Returns `None` when no
+    /// transaction is sent, i.e. when it's only built, estimated, or simulated.
+    async fn run_file(self, file: PathBuf) -> Result<Option<Declaration>> {
         let fee_setting = self.fee.into_setting()?;
         if self.simulate && fee_setting.is_estimate_only() {
             anyhow::bail!("--simulate cannot be used with --estimate-only");
@@ -68,30 +237,21 @@ impl Declare {
 
         let provider = Arc::new(self.provider.into_provider()?);
 
-        let account = self.account.into_account(provider.clone()).await?;
-
-        // Workaround for issue:
-        //   https://github.com/eqlabs/pathfinder/issues/1208
-        let (fee_multiplier_num, fee_multiplier_denom): (FieldElement, FieldElement) =
-            if provider.is_rpc() {
-                (felt!("5"), felt!("2"))
-            } else {
-                (felt!("3"), felt!("2"))
-            };
+        let default_fee_multiplier = declare_fee_multiplier(&provider);
 
         // Working around a deserialization bug in `starknet-rs`:
         //   https://github.com/xJonathanLEI/starknet-rs/issues/392
 
         #[allow(clippy::redundant_pattern_matching)]
         let (class_hash, declaration_tx_hash) = if let Ok(class) =
-            serde_json::from_reader::<_, SierraClass>(std::fs::File::open(&self.file)?)
+            serde_json::from_reader::<_, SierraClass>(std::fs::File::open(&file)?)
         {
             // Declaring Cairo 1 class
             let class_hash = class.class_hash()?;
 
             // TODO: add option to skip checking
             if Self::check_already_declared(&provider, class_hash).await? {
-                return Ok(());
+                return Ok(Some(Declaration::AlreadyDeclared { class_hash }));
             }
 
             let casm_source = self.casm.into_casm_hash_source(&provider).await?;
@@ -139,64 +299,62 @@ impl Declare {
                 );
             }
 
-            // TODO: make buffer configurable
-            let declaration = account.declare(Arc::new(class.flatten()?), casm_class_hash);
-
-            let max_fee = match fee_setting {
-                FeeSetting::Manual(fee) => fee,
-                FeeSetting::EstimateOnly | FeeSetting::None => {
-                    let estimated_fee = declaration
-                        .estimate_fee()
-                        .await
-                        .map_err(account_error_mapper)?
-                        .overall_fee;
-
-                    if fee_setting.is_estimate_only() {
-                        println!(
-                            "{} ETH",
-                            format!("{}", estimated_fee.to_big_decimal(18)).bright_yellow(),
-                        );
-                        return Ok(());
-                    }
+            let class = Arc::new(class.flatten()?);
+
+            if self.build_only {
+                let account = self.account.resolve_unsigned(&provider).await?;
+
+                let transaction_file = TransactionFile::build(
+                    &provider,
+                    account.chain_id,
+                    self.nonce,
+                    V3Transaction::Declare {
+                        sender_address: account.address,
+                        contract_class: class,
+                        compiled_class_hash: casm_class_hash,
+                    },
+                    &fee_setting,
+                    default_fee_multiplier,
+                    account.num_signatures(),
+                )
+                .await?;
+
+                transaction_file.print()?;
+                return Ok(None);
+            }
 
-                    // TODO: make buffer configurable
-                    (estimated_fee * fee_multiplier_num).floor_div(fee_multiplier_denom)
-                }
-            };
+            let account = self.account.resolve(&provider).await?;
 
-            let declaration = match self.nonce {
-                Some(nonce) => declaration.nonce(nonce),
-                None => declaration,
+            let declaration_tx_hash = match declare_sierra_class(
+                &provider,
+                account,
+                class,
+                casm_class_hash,
+                self.nonce,
+                fee_setting,
+                self.simulate,
+            )
+            .await?
+            {
+                Some(declaration_tx_hash) => declaration_tx_hash,
+                None => return Ok(None),
             };
-            let declaration = declaration.max_fee(max_fee);
 
-            if self.simulate {
-                let simulation = declaration.simulate(false, false).await?;
-                let simulation_json = serde_json::to_value(simulation)?;
-
-                let simulation_json = colored_json::to_colored_json(
-                    &simulation_json,
-                    ColorMode::Auto(Output::StdOut),
-                )?;
-                println!("{simulation_json}");
-                return Ok(());
-            }
-
-            (class_hash, declaration.send().await?.transaction_hash)
+            (class_hash, declaration_tx_hash)
         } else if let Ok(_) =
-            serde_json::from_reader::<_, CompiledClass>(std::fs::File::open(&self.file)?)
+            serde_json::from_reader::<_, CompiledClass>(std::fs::File::open(&file)?)
         {
             // TODO: add more helpful instructions to fix this
             anyhow::bail!("unexpected CASM class");
         } else if let Ok(class) =
-            serde_json::from_reader::<_, LegacyContractClass>(std::fs::File::open(self.file)?)
+            serde_json::from_reader::<_, LegacyContractClass>(std::fs::File::open(file)?)
         {
             // Declaring Cairo 0 class
             let class_hash = class.class_hash()?;
 
             // TODO: add option to skip checking
             if Self::check_already_declared(&provider, class_hash).await? {
-                return Ok(());
+                return Ok(Some(Declaration::AlreadyDeclared { class_hash }));
             }
 
             if !fee_setting.is_estimate_only() {
@@ -206,28 +364,42 @@ impl Declare {
                 );
             }
 
-            // TODO: make buffer configurable
+            let fee_setting = match fee_setting {
+                FeeSetting::Eth(fee_setting) => fee_setting,
+                FeeSetting::Strk(_) => {
+                    anyhow::bail!("Cairo 0 classes can only be declared with fees paid in ETH")
+                }
+            };
+
+            if self.build_only {
+                anyhow::bail!("--build-only is not supported for Cairo 0 classes");
+            }
+
+            let account = self.account.resolve(&provider).await?;
+            if account.guardian.is_some() {
+                // Only Sierra classes can be built as transaction files for co-signing
+                anyhow::bail!(
+                    "co-signing with the guardian is not supported for declaring Cairo 0 classes"
+                );
+            }
+            let account = account.into_single_owner(provider.clone());
             let declaration = account.declare_legacy(Arc::new(class));
 
             let max_fee = match fee_setting {
-                FeeSetting::Manual(fee) => fee,
-                FeeSetting::EstimateOnly | FeeSetting::None => {
-                    let estimated_fee = declaration
+                EthFeeSetting::Manual(fee) => fee,
+                EthFeeSetting::EstimateOnly | EthFeeSetting::Estimate { .. } => {
+                    let estimate = declaration
                         .estimate_fee()
                         .await
-                        .map_err(account_error_mapper)?
-                        .overall_fee;
+                        .map_err(account_error_mapper)?;
+                    record_fee_estimate(&estimate, FeeToken::Eth);
 
-                    if fee_setting.is_estimate_only() {
-                        println!(
-                            "{} ETH",
-                            format!("{}", estimated_fee.to_big_decimal(18)).bright_yellow(),
-                        );
-                        return Ok(());
+                    if matches!(fee_setting, EthFeeSetting::EstimateOnly) {
+                        print_fee_estimate(&estimate, FeeToken::Eth);
+                        return Ok(None);
                     }
 
-                    // TODO: make buffer configurable
-                    (estimated_fee * fee_multiplier_num).floor_div(fee_multiplier_denom)
+                    fee_setting.apply_multiplier(estimate.overall_fee, default_fee_multiplier)
                 }
             };
 
@@ -239,14 +411,8 @@ impl Declare {
 
             if self.simulate {
                 let simulation = declaration.simulate(false, false).await?;
-                let simulation_json = serde_json::to_value(simulation)?;
-
-                let simulation_json = colored_json::to_colored_json(
-                    &simulation_json,
-                    ColorMode::Auto(Output::StdOut),
-                )?;
-                println!("{simulation_json}");
-                return Ok(());
+                print_colored_json(&simulation)?;
+                return Ok(None);
             }
 
             (class_hash, declaration.send().await?.transaction_hash)
@@ -259,43 +425,146 @@ impl Declare {
             format!("{:#064x}", declaration_tx_hash).bright_yellow()
         );
 
-        if self.watch {
-            eprintln!(
-                "Waiting for transaction {} to confirm...",
-                format!("{:#064x}", declaration_tx_hash).bright_yellow(),
-            );
-            watch_tx(
-                &provider,
-                declaration_tx_hash,
-                Duration::from_millis(self.poll_interval),
-            )
-            .await?;
-        }
-
-        eprintln!("Class hash declared:");
+        Ok(Some(Declaration::Sent {
+            class_hash,
+            transaction_hash: declaration_tx_hash,
+        }))
+    }
 
-        // Only the class hash goes to stdout so this can be easily scripted
-        println!("{}", format!("{:#064x}", class_hash).bright_yellow());
+    async fn watch_declaration(&self, transaction_hash: FieldElement) -> Result<()> {
+        let provider = self.provider.clone().into_provider()?;
 
-        Ok(())
+        eprintln!(
+            "Waiting for transaction {} to confirm...",
+            format!("{:#064x}", transaction_hash).bright_yellow(),
+        );
+        watch_tx(
+            &provider,
+            transaction_hash,
+            ProfileDefaults::resolve_poll_interval(self.poll_interval)?,
+        )
+        .await
     }
 
     async fn check_already_declared<P>(provider: P, class_hash: FieldElement) -> Result<bool>
     where
         P: Provider,
     {
-        match provider
-            .get_class(BlockId::Tag(BlockTag::Pending), class_hash)
-            .await
-        {
-            Ok(_) => {
-                eprintln!("Not declaring class as it's already declared. Class hash:");
-                println!("{}", format!("{:#064x}", class_hash).bright_yellow());
+        if is_class_declared(provider, class_hash).await? {
+            eprintln!("Not declaring class as it's already declared. Class hash:");
 
-                Ok(true)
-            }
-            Err(ProviderError::StarknetError(StarknetError::ClassHashNotFound)) => Ok(false),
-            Err(err) => Err(err.into()),
+            Ok(true)
+        } else {
+            Ok(false)
         }
     }
 }
+
+/// Default fee multiplier for declarations, which is higher on JSON-RPC as a workaround for issue:
+///   https://github.com/eqlabs/pathfinder/issues/1208
+pub fn declare_fee_multiplier(provider: &ExtendedProvider) -> f64 {
+    if provider.is_rpc() {
+        2.5
+    } else {
+        DEFAULT_FEE_MULTIPLIER
+    }
+}
+
+/// Declares a Sierra class with fees paid in either token. Returns `None` when the transaction is
+/// only estimated or simulated instead of being sent.
+pub async fn declare_sierra_class(
+    provider: &Arc<ExtendedProvider>,
+    account: ResolvedAccount,
+    class: Arc<FlattenedSierraClass>,
+    compiled_class_hash: FieldElement,
+    nonce: Option<FieldElement>,
+    fee_setting: FeeSetting,
+    simulate: bool,
+) -> Result<Option<FieldElement>> {
+    let default_fee_multiplier = declare_fee_multiplier(provider);
+
+    Ok(Some(match fee_setting {
+        FeeSetting::Eth(fee_setting) if account.guardian.is_some() => {
+            TransactionFile::send_co_signed(
+                provider,
+                &account,
+                nonce,
+                V3Transaction::Declare {
+                    sender_address: account.address,
+                    contract_class: class,
+                    compiled_class_hash,
+                },
+                fee_setting,
+                default_fee_multiplier,
+                simulate,
+            )
+            .await?
+        }
+        FeeSetting::Eth(fee_setting) => {
+            let account = account.into_single_owner(provider.clone());
+            let declaration = account.declare(class, compiled_class_hash);
+
+            let max_fee = match fee_setting {
+                EthFeeSetting::Manual(fee) => fee,
+                EthFeeSetting::EstimateOnly | EthFeeSetting::Estimate { .. } => {
+                    let estimate = declaration
+                        .estimate_fee()
+                        .await
+                        .map_err(account_error_mapper)?;
+                    record_fee_estimate(&estimate, FeeToken::Eth);
+
+                    if matches!(fee_setting, EthFeeSetting::EstimateOnly) {
+                        print_fee_estimate(&estimate, FeeToken::Eth);
+                        return Ok(None);
+                    }
+
+                    fee_setting.apply_multiplier(estimate.overall_fee, default_fee_multiplier)
+                }
+            };
+
+            let declaration = match nonce {
+                Some(nonce) => declaration.nonce(nonce),
+                None => declaration,
+            };
+            let declaration = declaration.max_fee(max_fee);
+
+            if simulate {
+                let simulation = declaration.simulate(false, false).await?;
+                print_colored_json(&simulation)?;
+                return Ok(None);
+            }
+
+            declaration.send().await?.transaction_hash
+        }
+        FeeSetting::Strk(fee_setting) => {
+            let sender = V3Sender::new(
+                provider,
+                &account,
+                account.chain_id,
+                nonce,
+                V3Transaction::Declare {
+                    sender_address: account.address,
+                    contract_class: class,
+                    compiled_class_hash,
+                },
+            )
+            .await?;
+
+            let fee = match sender
+                .resolve_fee(&fee_setting, default_fee_multiplier)
+                .await?
+            {
+                Some(fee) => fee,
+                None => return Ok(None),
+            };
+
+            if simulate {
+                let simulation = sender.simulate(&fee).await?;
+                print_colored_json(&simulation)?;
+                return Ok(None);
+            }
+
+            sender.send(&fee).await?
+        }
+    }))
+}
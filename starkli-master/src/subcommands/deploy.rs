@@ -1,32 +1,37 @@
-use std::{sync::Arc, time::Duration};
+use std::sync::Arc;
 
 use anyhow::Result;
 use clap::Parser;
 use colored::Colorize;
-use colored_json::{ColorMode, Output};
 use starknet::{
-    contract::ContractFactory, core::types::FieldElement, macros::felt, signers::SigningKey,
+    contract::ContractFactory,
+    core::{
+        types::{BlockId, BlockTag, FieldElement},
+        utils::{get_udc_deployed_address, UdcUniqueSettings, UdcUniqueness},
+    },
+    signers::SigningKey,
 };
 
 use crate::{
+    abi::AbiArgs,
     account::AccountArgs,
     address_book::AddressBookResolver,
     decode::FeltDecoder,
     error::account_error_mapper,
-    fee::{FeeArgs, FeeSetting},
-    utils::watch_tx,
+    fee::{
+        print_fee_estimate, record_fee_estimate, EthFeeSetting, FeeArgs, FeeSetting, FeeToken,
+        DEFAULT_FEE_MULTIPLIER,
+    },
+    offline::TransactionFile,
+    output::{print_text, record_transaction},
+    profile::ProfileDefaults,
+    udc::{udc_deploy_call, UdcArgs},
+    utils::{print_colored_json, watch_tx},
+    v3::{V3Sender, V3Transaction},
     verbosity::VerbosityArgs,
     ProviderArgs,
 };
 
-/// The default UDC address: 0x041a78e741e5af2fec34b695679bc6891742439f7afb8484ecd7766661ad02bf.
-const DEFAULT_UDC_ADDRESS: FieldElement = FieldElement::from_mont([
-    15144800532519055890,
-    15685625669053253235,
-    9333317513348225193,
-    121672436446604875,
-]);
-
 #[derive(Debug, Parser)]
 pub struct Deploy {
     #[clap(flatten)]
@@ -36,9 +41,20 @@ pub struct Deploy {
     #[clap(long, help = "Do not derive contract address from deployer address")]
     not_unique: bool,
     #[clap(flatten)]
+    udc: UdcArgs,
+    #[clap(flatten)]
     fee: FeeArgs,
+    #[clap(flatten)]
+    abi: AbiArgs,
     #[clap(long, help = "Simulate the transaction only")]
     simulate: bool,
+    #[clap(
+        long,
+        conflicts_with_all = ["simulate", "estimate_only", "watch"],
+        help = "Build the transaction without signing or sending it, and print it as JSON to be \
+        signed with `starkli tx sign`"
+    )]
+    build_only: bool,
     #[clap(long, help = "Use the given salt to compute contract deploy address")]
     salt: Option<String>,
     #[clap(long, help = "Provide transaction nonce manually")]
@@ -48,13 +64,13 @@ pub struct Deploy {
     #[clap(
         long,
         env = "STARKNET_POLL_INTERVAL",
-        default_value = "5000",
-        help = "Transaction result poll interval in milliseconds"
+        help = "Transaction result poll interval in milliseconds. Falls back to the default of the \
+        active profile, or 5000 if not set"
     )]
-    poll_interval: u64,
+    poll_interval: Option<u64>,
     #[clap(help = "Class hash")]
     class_hash: String,
-    #[clap(help = "Raw constructor arguments")]
+    #[clap(help = "Constructor arguments (raw field elements unless --typed or --abi is used)")]
     ctor_args: Vec<String>,
     #[clap(flatten)]
     verbosity: VerbosityArgs,
@@ -69,14 +85,28 @@ impl Deploy {
             anyhow::bail!("--simulate cannot be used with --estimate-only");
         }
 
+        let abi_source = self.abi.into_source()?;
+
         let provider = Arc::new(self.provider.into_provider()?);
         let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
 
         let class_hash = FieldElement::from_hex_be(&self.class_hash)?;
-        let mut ctor_args = vec![];
-        for element in self.ctor_args.iter() {
-            ctor_args.append(&mut felt_decoder.decode(element).await?);
-        }
+        let ctor_args = match &abi_source {
+            Some(abi_source) => {
+                abi_source
+                    .abi_of_class(&provider, class_hash, BlockId::Tag(BlockTag::Pending))
+                    .await?
+                    .encode_constructor_call(&self.ctor_args, &felt_decoder)
+                    .await?
+            }
+            None => {
+                let mut ctor_args = vec![];
+                for element in self.ctor_args.iter() {
+                    ctor_args.append(&mut felt_decoder.decode(element).await?);
+                }
+                ctor_args
+            }
+        };
 
         let salt = if let Some(s) = self.salt {
             FieldElement::from_hex_be(&s)?
@@ -84,68 +114,162 @@ impl Deploy {
             SigningKey::from_random().secret_scalar()
         };
 
-        let account = self.account.into_account(provider.clone()).await?;
+        let udc_address = self.udc.resolve(provider.udc_address());
+
+        let account = self.account.resolve_unsigned(&provider).await?;
+
+        let unique = !self.not_unique;
+        let udc_uniqueness = if unique {
+            UdcUniqueness::Unique(UdcUniqueSettings {
+                deployer_address: account.address,
+                udc_contract_address: udc_address,
+            })
+        } else {
+            UdcUniqueness::NotUnique
+        };
+        let deployed_address =
+            get_udc_deployed_address(salt, class_hash, &udc_uniqueness, &ctor_args);
+
+        if !fee_setting.is_estimate_only() {
+            eprintln!(
+                "Deploying class {} with salt {}...",
+                format!("{:#064x}", class_hash).bright_yellow(),
+                format!("{:#064x}", salt).bright_yellow()
+            );
+            eprintln!(
+                "The contract will be deployed at address {}",
+                format!("{:#064x}", deployed_address).bright_yellow()
+            );
+        }
+
+        if self.build_only {
+            let transaction_file = TransactionFile::build(
+                &provider,
+                account.chain_id,
+                self.nonce,
+                V3Transaction::invoke(
+                    account.address,
+                    &[udc_deploy_call(
+                        udc_address,
+                        class_hash,
+                        salt,
+                        unique,
+                        &ctor_args,
+                    )],
+                    account.encoding,
+                ),
+                &fee_setting,
+                DEFAULT_FEE_MULTIPLIER,
+                account.num_signatures(),
+            )
+            .await?;
+
+            return transaction_file.print();
+        }
+
+        let account = account.resolve_signer()?;
 
-        // TODO: allow custom UDC
-        let factory = ContractFactory::new_with_udc(class_hash, account, DEFAULT_UDC_ADDRESS);
+        let deployment_tx = match fee_setting {
+            FeeSetting::Eth(fee_setting) if account.guardian.is_some() => {
+                TransactionFile::send_co_signed(
+                    &provider,
+                    &account,
+                    self.nonce,
+                    V3Transaction::invoke(
+                        account.address,
+                        &[udc_deploy_call(
+                            udc_address,
+                            class_hash,
+                            salt,
+                            unique,
+                            &ctor_args,
+                        )],
+                        account.encoding,
+                    ),
+                    fee_setting,
+                    DEFAULT_FEE_MULTIPLIER,
+                    self.simulate,
+                )
+                .await?
+            }
+            FeeSetting::Eth(fee_setting) => {
+                let factory = ContractFactory::new_with_udc(
+                    class_hash,
+                    account.into_single_owner(provider.clone()),
+                    udc_address,
+                );
+
+                let contract_deployment = factory.deploy(ctor_args, salt, unique);
+
+                let max_fee = match fee_setting {
+                    EthFeeSetting::Manual(fee) => fee,
+                    EthFeeSetting::EstimateOnly | EthFeeSetting::Estimate { .. } => {
+                        let estimate = contract_deployment
+                            .estimate_fee()
+                            .await
+                            .map_err(account_error_mapper)?;
+                        record_fee_estimate(&estimate, FeeToken::Eth);
 
-        let contract_deployment = factory.deploy(ctor_args, salt, !self.not_unique);
-        let deployed_address = contract_deployment.deployed_address();
+                        if matches!(fee_setting, EthFeeSetting::EstimateOnly) {
+                            print_fee_estimate(&estimate, FeeToken::Eth);
+                            return Ok(());
+                        }
 
-        let max_fee = match fee_setting {
-            FeeSetting::Manual(fee) => fee,
-            FeeSetting::EstimateOnly | FeeSetting::None => {
-                let estimated_fee = contract_deployment
-                    .estimate_fee()
-                    .await
-                    .map_err(account_error_mapper)?
-                    .overall_fee;
+                        fee_setting.apply_multiplier(estimate.overall_fee, DEFAULT_FEE_MULTIPLIER)
+                    }
+                };
 
-                if fee_setting.is_estimate_only() {
-                    eprintln!(
-                        "{} ETH",
-                        format!("{}", estimated_fee.to_big_decimal(18)).bright_yellow(),
-                    );
+                let contract_deployment = match self.nonce {
+                    Some(nonce) => contract_deployment.nonce(nonce),
+                    None => contract_deployment,
+                };
+                let contract_deployment = contract_deployment.max_fee(max_fee);
+
+                if self.simulate {
+                    let simulation = contract_deployment.simulate(false, false).await?;
+                    print_colored_json(&simulation)?;
                     return Ok(());
                 }
 
-                // TODO: make buffer configurable
-                (estimated_fee * felt!("3")).floor_div(felt!("2"))
+                contract_deployment.send().await?.transaction_hash
             }
-        };
+            FeeSetting::Strk(fee_setting) => {
+                let call = udc_deploy_call(udc_address, class_hash, salt, unique, &ctor_args);
 
-        eprintln!(
-            "Deploying class {} with salt {}...",
-            format!("{:#064x}", class_hash).bright_yellow(),
-            format!("{:#064x}", salt).bright_yellow()
-        );
-        eprintln!(
-            "The contract will be deployed at address {}",
-            format!("{:#064x}", deployed_address).bright_yellow()
-        );
+                let sender = V3Sender::new(
+                    &provider,
+                    &account,
+                    account.chain_id,
+                    self.nonce,
+                    V3Transaction::invoke(account.address, &[call], account.encoding),
+                )
+                .await?;
 
-        let contract_deployment = match self.nonce {
-            Some(nonce) => contract_deployment.nonce(nonce),
-            None => contract_deployment,
-        };
-        let contract_deployment = contract_deployment.max_fee(max_fee);
+                let fee = match sender
+                    .resolve_fee(&fee_setting, DEFAULT_FEE_MULTIPLIER)
+                    .await?
+                {
+                    Some(fee) => fee,
+                    None => return Ok(()),
+                };
 
-        if self.simulate {
-            let simulation = contract_deployment.simulate(false, false).await?;
-            let simulation_json = serde_json::to_value(simulation)?;
+                if self.simulate {
+                    let simulation = sender.simulate(&fee).await?;
+                    print_colored_json(&simulation)?;
+                    return Ok(());
+                }
 
-            let simulation_json =
-                colored_json::to_colored_json(&simulation_json, ColorMode::Auto(Output::StdOut))?;
-            println!("{simulation_json}");
-            return Ok(());
-        }
+                sender.send(&fee).await?
+            }
+        };
 
-        let deployment_tx = contract_deployment.send().await?.transaction_hash;
         eprintln!(
             "Contract deployment transaction: {}",
             format!("{:#064x}", deployment_tx).bright_yellow()
         );
 
+        record_transaction(deployment_tx);
+
         if self.watch {
             eprintln!(
                 "Waiting for transaction {} to confirm...",
@@ -154,7 +278,7 @@ impl Deploy {
             watch_tx(
                 &provider,
                 deployment_tx,
-                Duration::from_millis(self.poll_interval),
+                ProfileDefaults::resolve_poll_interval(self.poll_interval)?,
             )
             .await?;
         }
@@ -162,7 +286,10 @@ impl Deploy {
         eprintln!("Contract deployed:");
 
         // Only the contract goes to stdout so this can be easily scripted
-        println!("{}", format!("{:#064x}", deployed_address).bright_yellow());
+        print_text(
+            "address",
+            format!("{:#064x}", deployed_address).bright_yellow(),
+        );
 
         Ok(())
     }
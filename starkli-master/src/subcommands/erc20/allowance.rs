@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use clap::Parser;
+
+use crate::{
+    address_book::AddressBookResolver,
+    block::BlockArgs,
+    decode::FeltDecoder,
+    erc20::{format_amount, get_allowance},
+    output::print_text,
+    subcommands::erc20::TokenArgs,
+    verbosity::VerbosityArgs,
+    ProviderArgs,
+};
+
+#[derive(Debug, Parser)]
+pub struct Allowance {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(flatten)]
+    token: TokenArgs,
+    #[clap(help = "Owner address")]
+    owner: String,
+    #[clap(help = "Spender address")]
+    spender: String,
+    #[clap(flatten)]
+    block: BlockArgs,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+impl Allowance {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let provider = Arc::new(self.provider.into_provider()?);
+        let block_id = self.block.resolve(provider.as_ref()).await?;
+        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+
+        let token = self
+            .token
+            .resolve(provider.as_ref(), &felt_decoder, block_id)
+            .await?;
+        let owner = felt_decoder
+            .decode_single_with_addr_fallback(&self.owner)
+            .await?;
+        let spender = felt_decoder
+            .decode_single_with_addr_fallback(&self.spender)
+            .await?;
+
+        let allowance =
+            get_allowance(provider.as_ref(), token.address, owner, spender, block_id).await?;
+
+        print_text("allowance", format_amount(&allowance, token.decimals));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use num_bigint::BigUint;
+use starknet::{
+    accounts::Call,
+    core::types::{BlockId, BlockTag},
+    macros::selector,
+};
+
+use crate::{
+    account::AccountArgs,
+    address_book::AddressBookResolver,
+    decode::FeltDecoder,
+    erc20::amount_to_u256,
+    fee::FeeArgs,
+    output::record,
+    subcommands::{erc20::TokenArgs, invoke::ExecutionArgs},
+    verbosity::VerbosityArgs,
+    ProviderArgs,
+};
+
+#[derive(Debug, Parser)]
+pub struct Approve {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(flatten)]
+    account: AccountArgs,
+    #[clap(flatten)]
+    fee: FeeArgs,
+    #[clap(flatten)]
+    token: TokenArgs,
+    #[clap(flatten)]
+    execution: ExecutionArgs,
+    #[clap(help = "Spender address")]
+    spender: String,
+    #[clap(help = "Amount to approve in token units (e.g. 1.25)")]
+    amount: String,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+impl Approve {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let fee_setting = self.fee.into_setting()?;
+        self.execution.validate(&fee_setting)?;
+
+        let provider = Arc::new(self.provider.into_provider()?);
+        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+
+        let token = self
+            .token
+            .resolve(
+                provider.as_ref(),
+                &felt_decoder,
+                BlockId::Tag(BlockTag::Pending),
+            )
+            .await?;
+        let spender = felt_decoder
+            .decode_single_with_addr_fallback(&self.spender)
+            .await?;
+        let amount = token.parse_amount(&self.amount)?;
+
+        let mut calldata = vec![spender];
+        calldata.extend_from_slice(&amount_to_u256(amount));
+
+        let watch = self.execution.watches();
+        let transaction_hash = self
+            .execution
+            .execute(
+                provider,
+                self.account,
+                fee_setting,
+                vec![Call {
+                    to: token.address,
+                    selector: selector!("approve"),
+                    calldata,
+                }],
+            )
+            .await?;
+
+        if transaction_hash.is_some() {
+            record(
+                "amount",
+                token.format_amount(&BigUint::from_bytes_be(&amount.to_bytes_be())),
+            );
+            eprintln!(
+                "{} {} to spend {}",
+                if watch { "Approved" } else { "Approving" },
+                format!("{:#064x}", spender).bright_yellow(),
+                token
+                    .format_amount(&BigUint::from_bytes_be(&amount.to_bytes_be()))
+                    .bright_yellow()
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,129 @@
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use clap::{Parser, Subcommand};
+use num_bigint::BigUint;
+use starknet::{
+    core::types::{BlockId, FieldElement},
+    providers::Provider,
+};
+
+use crate::{
+    chain_id::ChainIdSource,
+    decode::FeltDecoder,
+    erc20::{format_amount, get_decimals, get_symbol, DEFAULT_ETH_ADDRESS},
+    utils::bigdecimal_to_felt,
+};
+
+mod transfer;
+use transfer::Transfer;
+
+mod approve;
+use approve::Approve;
+
+mod allowance;
+use allowance::Allowance;
+
+#[derive(Debug, Parser)]
+pub struct Erc20 {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(about = "Transfer tokens from an account to a recipient")]
+    Transfer(Transfer),
+    #[clap(about = "Approve a spender to spend tokens on behalf of an account")]
+    Approve(Approve),
+    #[clap(about = "Get the amount of tokens a spender is allowed to spend for an owner")]
+    Allowance(Allowance),
+}
+
+#[derive(Debug, Clone, Parser)]
+pub struct TokenArgs {
+    #[clap(
+        long,
+        help = "Token contract address or address book entry name (e.g. strk). Defaults to ETH"
+    )]
+    token: Option<String>,
+    #[clap(
+        long,
+        help = "Treat amounts as raw integers instead of scaling them by token decimals"
+    )]
+    raw: bool,
+}
+
+/// An ERC20 token with its metadata resolved.
+pub struct Token {
+    pub address: FieldElement,
+    /// `0` when amounts are used as raw integers.
+    pub decimals: u8,
+    pub symbol: String,
+}
+
+impl Erc20 {
+    pub async fn run(self) -> Result<()> {
+        match self.command {
+            Subcommands::Transfer(cmd) => cmd.run().await,
+            Subcommands::Approve(cmd) => cmd.run().await,
+            Subcommands::Allowance(cmd) => cmd.run().await,
+        }
+    }
+}
+
+impl TokenArgs {
+    /// Resolves the token address and fetches its metadata as of `block_id`.
+    pub async fn resolve<P, S>(
+        &self,
+        provider: &P,
+        felt_decoder: &FeltDecoder<S>,
+        block_id: BlockId,
+    ) -> Result<Token>
+    where
+        P: Provider,
+        S: ChainIdSource,
+    {
+        let address = match &self.token {
+            Some(token) => felt_decoder.decode_single_with_addr_fallback(token).await?,
+            None => DEFAULT_ETH_ADDRESS,
+        };
+
+        let decimals = if self.raw {
+            0
+        } else {
+            get_decimals(provider, address, block_id).await?
+        };
+
+        // Symbols are only used for display so it's fine to fall back to addresses
+        let symbol = match get_symbol(provider, address, block_id).await {
+            Ok(symbol) => symbol,
+            Err(_) => format!("{:#064x}", address),
+        };
+
+        Ok(Token {
+            address,
+            decimals,
+            symbol,
+        })
+    }
+}
+
+impl Token {
+    /// Parses an amount in token units (e.g. `1.25`) into the raw integer amount.
+    pub fn parse_amount(&self, amount: &str) -> Result<FieldElement> {
+        let amount: BigDecimal = amount
+            .parse()
+            .map_err(|_| anyhow::anyhow!("invalid amount: {}", amount))?;
+
+        bigdecimal_to_felt(&amount, self.decimals)
+    }
+
+    /// Formats a raw amount in token units with the token symbol.
+    pub fn format_amount(&self, raw_amount: &BigUint) -> String {
+        format!(
+            "{} {}",
+            format_amount(raw_amount, self.decimals),
+            self.symbol
+        )
+    }
+}
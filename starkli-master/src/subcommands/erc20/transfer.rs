@@ -0,0 +1,114 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use num_bigint::BigUint;
+use starknet::{
+    accounts::Call,
+    core::types::{BlockId, BlockTag},
+    macros::selector,
+};
+
+use crate::{
+    account::AccountArgs,
+    address_book::AddressBookResolver,
+    decode::FeltDecoder,
+    erc20::{amount_to_u256, get_balance},
+    fee::FeeArgs,
+    output::record,
+    subcommands::{erc20::TokenArgs, invoke::ExecutionArgs},
+    verbosity::VerbosityArgs,
+    ProviderArgs,
+};
+
+#[derive(Debug, Parser)]
+pub struct Transfer {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(flatten)]
+    account: AccountArgs,
+    #[clap(flatten)]
+    fee: FeeArgs,
+    #[clap(flatten)]
+    token: TokenArgs,
+    #[clap(flatten)]
+    execution: ExecutionArgs,
+    #[clap(help = "Recipient address")]
+    recipient: String,
+    #[clap(help = "Amount to transfer in token units (e.g. 1.25)")]
+    amount: String,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+impl Transfer {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let fee_setting = self.fee.into_setting()?;
+        self.execution.validate(&fee_setting)?;
+
+        let provider = Arc::new(self.provider.into_provider()?);
+        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+
+        // Transactions are built on top of the pending block
+        let block_id = BlockId::Tag(BlockTag::Pending);
+
+        let token = self
+            .token
+            .resolve(provider.as_ref(), &felt_decoder, block_id)
+            .await?;
+        let recipient = felt_decoder
+            .decode_single_with_addr_fallback(&self.recipient)
+            .await?;
+        let amount = token.parse_amount(&self.amount)?;
+        let raw_amount = BigUint::from_bytes_be(&amount.to_bytes_be());
+
+        // Checking the balance does not need the signer
+        let sender = self
+            .account
+            .clone()
+            .resolve_unsigned(&provider)
+            .await?
+            .address;
+        let balance = get_balance(provider.as_ref(), token.address, sender, block_id).await?;
+        if balance < raw_amount {
+            anyhow::bail!(
+                "insufficient balance: transferring {} but only {} is available",
+                token.format_amount(&raw_amount),
+                token.format_amount(&balance)
+            );
+        }
+
+        let mut calldata = vec![recipient];
+        calldata.extend_from_slice(&amount_to_u256(amount));
+
+        let watch = self.execution.watches();
+        let transaction_hash = self
+            .execution
+            .execute(
+                provider,
+                self.account,
+                fee_setting,
+                vec![Call {
+                    to: token.address,
+                    selector: selector!("transfer"),
+                    calldata,
+                }],
+            )
+            .await?;
+
+        if transaction_hash.is_some() {
+            record("amount", token.format_amount(&raw_amount));
+            eprintln!(
+                "{} {} to {}",
+                if watch { "Transferred" } else { "Transferring" },
+                token.format_amount(&raw_amount).bright_yellow(),
+                format!("{:#064x}", recipient).bright_yellow()
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,197 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::{
+    core::{
+        types::{BlockId, BlockTag, EventFilter},
+        utils::get_selector_from_name,
+    },
+    providers::Provider,
+};
+
+use crate::{
+    abi::{AbiSource, EventDecoder},
+    address_book::AddressBookResolver,
+    decode::FeltDecoder,
+    error::provider_error_mapper,
+    utils::{parse_block_id, print_colored_json},
+    verbosity::VerbosityArgs,
+    ProviderArgs,
+};
+
+/// Value used in `--keys` to match any key at a position.
+const KEY_WILDCARD: &str = "*";
+
+#[derive(Debug, Parser)]
+pub struct Events {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(long, help = "Only return events emitted from this contract address")]
+    address: Option<String>,
+    #[clap(
+        long,
+        help = "Only return events with this name. Names are looked up from the contract ABI when \
+        --address is used, and otherwise hashed directly. Can be used multiple times"
+    )]
+    event: Vec<String>,
+    #[clap(
+        long,
+        help = "Comma-separated list of accepted values for one event key position, or `*` for any \
+        value. Use multiple times for subsequent positions. Positions start after the event \
+        selector when --event is used"
+    )]
+    keys: Vec<String>,
+    #[clap(
+        long,
+        default_value = "0",
+        help = "Block number, hash, or tag (latest/pending) to search from"
+    )]
+    from_block: String,
+    #[clap(
+        long,
+        default_value = "latest",
+        help = "Block number, hash, or tag (latest/pending) to search until"
+    )]
+    to_block: String,
+    #[clap(
+        long,
+        default_value = "100",
+        help = "Number of events to request from the node per page"
+    )]
+    chunk_size: u64,
+    #[clap(
+        long,
+        value_parser = clap::value_parser!(u64).range(1..),
+        help = "Stop after this number of events. The continuation token for resuming is printed"
+    )]
+    limit: Option<u64>,
+    #[clap(
+        long,
+        help = "Continuation token returned from a previous query to resume from"
+    )]
+    continuation_token: Option<String>,
+    #[clap(long, help = "Decode events using the ABI of the emitting contracts")]
+    decode: bool,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+impl Events {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let provider = Arc::new(self.provider.into_provider()?);
+        let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
+
+        let address = match &self.address {
+            Some(address) => Some(
+                felt_decoder
+                    .decode_single_with_addr_fallback(address)
+                    .await?,
+            ),
+            None => None,
+        };
+
+        let mut keys = vec![];
+
+        if !self.event.is_empty() {
+            // Event names are resolved against the ABI when possible, as the selector is derived
+            // from the variant name in the contract `Event` enum, not the event struct name.
+            let abi = match address {
+                Some(address) => AbiSource::Network
+                    .abi_at(&provider, address, BlockId::Tag(BlockTag::Pending))
+                    .await
+                    .map(|abi| abi.into_owned())
+                    .ok(),
+                None => None,
+            };
+
+            let mut selectors = vec![];
+            for name in self.event.iter() {
+                selectors.push(
+                    match abi.as_ref().and_then(|abi| abi.find_event_selector(name)) {
+                        Some(selector) => selector,
+                        None => get_selector_from_name(name)?,
+                    },
+                );
+            }
+            keys.push(selectors);
+        }
+
+        for position in self.keys.iter() {
+            let mut values = vec![];
+            if position.trim() != KEY_WILDCARD {
+                for value in position.split(',') {
+                    values.push(
+                        felt_decoder
+                            .decode_single_with_addr_fallback(value.trim())
+                            .await?,
+                    );
+                }
+            }
+            keys.push(values);
+        }
+
+        let filter = EventFilter {
+            from_block: Some(parse_block_id(&self.from_block)?),
+            to_block: Some(parse_block_id(&self.to_block)?),
+            address,
+            keys: if keys.is_empty() { None } else { Some(keys) },
+        };
+
+        let mut event_decoder = EventDecoder::new(&provider);
+        let mut events = vec![];
+        let mut continuation_token = self.continuation_token;
+
+        loop {
+            // Never request more than needed so that the continuation token stays accurate
+            let chunk_size = match self.limit {
+                Some(limit) => self.chunk_size.min(limit - events.len() as u64),
+                None => self.chunk_size,
+            };
+
+            let page = provider
+                .get_events(filter.clone(), continuation_token, chunk_size)
+                .await
+                .map_err(provider_error_mapper)?;
+            continuation_token = page.continuation_token;
+
+            for event in page.events.into_iter() {
+                let mut event_json = serde_json::to_value(&event)?;
+                if self.decode {
+                    if let Some(decoded) = event_decoder
+                        .decode(
+                            event.from_address,
+                            event.block_number,
+                            &event.keys,
+                            &event.data,
+                        )
+                        .await
+                    {
+                        event_json["decoded"] = serde_json::to_value(decoded)?;
+                    }
+                }
+                events.push(event_json);
+            }
+
+            if continuation_token.is_none()
+                || self.limit.is_some_and(|limit| events.len() as u64 >= limit)
+            {
+                break;
+            }
+        }
+
+        print_colored_json(&events)?;
+
+        if let Some(continuation_token) = continuation_token {
+            eprintln!(
+                "More events available. Use --continuation-token {} to continue.",
+                continuation_token.bright_yellow()
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -1,22 +1,31 @@
-use std::{sync::Arc, time::Duration};
+use std::{path::PathBuf, sync::Arc};
 
 use anyhow::Result;
 use clap::Parser;
 use colored::Colorize;
-use colored_json::{ColorMode, Output};
 use starknet::{
     accounts::{Account, Call},
-    core::types::FieldElement,
-    macros::felt,
+    core::types::{BlockId, BlockTag, FieldElement},
 };
 
 use crate::{
+    abi::{AbiArgs, AbiSource},
     account::AccountArgs,
     address_book::AddressBookResolver,
+    calls_file::{confirm_calls, parse_variable, print_summary, CallsFile},
     decode::FeltDecoder,
     error::account_error_mapper,
-    fee::{FeeArgs, FeeSetting},
-    utils::watch_tx,
+    fee::{
+        print_fee_estimate, record_fee_estimate, EthFeeSetting, FeeArgs, FeeSetting, FeeToken,
+        DEFAULT_FEE_MULTIPLIER,
+    },
+    offline::TransactionFile,
+    output::record_transaction,
+    path::ExpandedPathbufParser,
+    profile::ProfileDefaults,
+    provider::ExtendedProvider,
+    utils::{print_colored_json, watch_tx},
+    v3::{V3Sender, V3Transaction},
     verbosity::VerbosityArgs,
     ProviderArgs,
 };
@@ -29,8 +38,47 @@ pub struct Invoke {
     account: AccountArgs,
     #[clap(flatten)]
     fee: FeeArgs,
+    #[clap(flatten)]
+    abi: AbiArgs,
+    #[clap(flatten)]
+    execution: ExecutionArgs,
+    #[clap(
+        long,
+        conflicts_with = "calls",
+        value_parser = ExpandedPathbufParser,
+        help = "Path to a TOML or JSON file containing the calls to make, with arguments encoded \
+        as typed values"
+    )]
+    calls_file: Option<PathBuf>,
+    #[clap(
+        long = "var",
+        value_parser = parse_variable,
+        requires = "calls_file",
+        help = "Set a variable used in the calls file, in the format of `name=value`. Overrides \
+        variables defined in the file"
+    )]
+    vars: Vec<(String, String)>,
+    #[clap(long, help = "Skip confirmation of calls loaded from a calls file")]
+    yes: bool,
+    #[clap(help = "One or more contract calls. See documentation for more details")]
+    calls: Vec<String>,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+/// Options for sending a transaction with a list of calls from an account, shared by commands
+/// that build the calls for the user.
+#[derive(Debug, Parser)]
+pub struct ExecutionArgs {
     #[clap(long, help = "Simulate the transaction only")]
     simulate: bool,
+    #[clap(
+        long,
+        conflicts_with_all = ["simulate", "estimate_only", "watch"],
+        help = "Build the transaction without signing or sending it, and print it as JSON to be \
+        signed with `starkli tx sign`"
+    )]
+    build_only: bool,
     #[clap(long, help = "Provide transaction nonce manually")]
     nonce: Option<FieldElement>,
     #[clap(long, short, help = "Wait for the transaction to confirm")]
@@ -38,14 +86,10 @@ pub struct Invoke {
     #[clap(
         long,
         env = "STARKNET_POLL_INTERVAL",
-        default_value = "5000",
-        help = "Transaction result poll interval in milliseconds"
+        help = "Transaction result poll interval in milliseconds. Falls back to the default of the \
+        active profile, or 5000 if not set"
     )]
-    poll_interval: u64,
-    #[clap(help = "One or more contract calls. See documentation for more details")]
-    calls: Vec<String>,
-    #[clap(flatten)]
-    verbosity: VerbosityArgs,
+    poll_interval: Option<u64>,
 }
 
 impl Invoke {
@@ -53,103 +97,272 @@ impl Invoke {
         self.verbosity.setup_logging();
 
         let fee_setting = self.fee.into_setting()?;
-        if self.simulate && fee_setting.is_estimate_only() {
-            anyhow::bail!("--simulate cannot be used with --estimate-only");
-        }
+        self.execution.validate(&fee_setting)?;
+
+        let abi_source = self.abi.into_source()?;
 
         let provider = Arc::new(self.provider.into_provider()?);
         let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
 
         // Parses and resolves the calls
-        let calls = {
-            // TODO: show more helpful message
-            let unexpected_end_of_args = || anyhow::anyhow!("unexpected end of arguments");
+        let calls = match self.calls_file {
+            Some(calls_file) => {
+                let entries = CallsFile::load(&calls_file)?.into_entries(&self.vars)?;
 
-            let mut buffer = vec![];
+                // Arguments in calls files are always typed
+                let abi_source = abi_source.unwrap_or(AbiSource::Network);
 
-            let mut arg_iter = self.calls.into_iter();
+                let mut buffer = vec![];
+                for entry in entries.iter() {
+                    let contract_address = felt_decoder
+                        .decode_single_with_addr_fallback(&entry.to)
+                        .await?;
+                    let selector = felt_decoder
+                        .decode_single_with_selector_fallback(&entry.function)
+                        .await?;
+                    let calldata = abi_source
+                        .abi_at(&provider, contract_address, BlockId::Tag(BlockTag::Pending))
+                        .await?
+                        .encode_function_call(selector, &entry.args, &felt_decoder)
+                        .await?;
 
-            while let Some(first_arg) = arg_iter.next() {
-                let contract_address = felt_decoder
-                    .decode_single_with_addr_fallback(&first_arg)
-                    .await?;
+                    buffer.push(Call {
+                        to: contract_address,
+                        selector,
+                        calldata,
+                    });
+                }
 
-                let next_arg = arg_iter.next().ok_or_else(unexpected_end_of_args)?;
-                let selector = felt_decoder
-                    .decode_single_with_selector_fallback(&next_arg)
-                    .await?;
+                print_summary(&entries, &buffer);
 
-                let mut calldata = vec![];
-                for arg in &mut arg_iter {
-                    let mut arg = match arg.as_str() {
-                        "/" | "-" | "\\" => break,
-                        _ => felt_decoder.decode(&arg).await?,
-                    };
-                    calldata.append(&mut arg);
+                let needs_confirmation =
+                    !self.yes && self.execution.sends_transaction(&fee_setting);
+                if needs_confirmation && !buffer.is_empty() && !confirm_calls(buffer.len())? {
+                    anyhow::bail!("transaction cancelled");
                 }
 
-                buffer.push(Call {
-                    to: contract_address,
-                    selector,
-                    calldata,
-                });
+                buffer
             }
+            None => {
+                // TODO: show more helpful message
+                let unexpected_end_of_args = || anyhow::anyhow!("unexpected end of arguments");
+
+                let mut buffer = vec![];
+
+                let mut arg_iter = self.calls.into_iter();
+
+                while let Some(first_arg) = arg_iter.next() {
+                    let contract_address = felt_decoder
+                        .decode_single_with_addr_fallback(&first_arg)
+                        .await?;
+
+                    let next_arg = arg_iter.next().ok_or_else(unexpected_end_of_args)?;
+                    let selector = felt_decoder
+                        .decode_single_with_selector_fallback(&next_arg)
+                        .await?;
+
+                    let mut args = vec![];
+                    for arg in &mut arg_iter {
+                        match arg.as_str() {
+                            "/" | "-" | "\\" => break,
+                            _ => args.push(arg),
+                        }
+                    }
 
-            buffer
+                    let calldata = match &abi_source {
+                        Some(abi_source) => {
+                            abi_source
+                                .abi_at(
+                                    &provider,
+                                    contract_address,
+                                    BlockId::Tag(BlockTag::Pending),
+                                )
+                                .await?
+                                .encode_function_call(selector, &args, &felt_decoder)
+                                .await?
+                        }
+                        None => {
+                            let mut calldata = vec![];
+                            for arg in args.iter() {
+                                calldata.append(&mut felt_decoder.decode(arg).await?);
+                            }
+                            calldata
+                        }
+                    };
+
+                    buffer.push(Call {
+                        to: contract_address,
+                        selector,
+                        calldata,
+                    });
+                }
+
+                buffer
+            }
         };
 
         if calls.is_empty() {
             anyhow::bail!("empty execution");
         }
 
-        let account = self.account.into_account(provider.clone()).await?;
-
-        let execution = account.execute(calls).fee_estimate_multiplier(1.5f64);
-
-        let max_fee = match fee_setting {
-            FeeSetting::Manual(fee) => fee,
-            FeeSetting::EstimateOnly | FeeSetting::None => {
-                let estimated_fee = execution
-                    .estimate_fee()
-                    .await
-                    .map_err(account_error_mapper)?
-                    .overall_fee;
-
-                if fee_setting.is_estimate_only() {
-                    println!(
-                        "{} ETH",
-                        format!("{}", estimated_fee.to_big_decimal(18)).bright_yellow(),
-                    );
-                    return Ok(());
-                }
+        self.execution
+            .execute(provider, self.account, fee_setting, calls)
+            .await?;
 
-                // TODO: make buffer configurable
-                (estimated_fee * felt!("3")).floor_div(felt!("2"))
-            }
-        };
+        Ok(())
+    }
+}
 
-        let execution = match self.nonce {
-            Some(nonce) => execution.nonce(nonce),
-            None => execution,
-        };
-        let execution = execution.max_fee(max_fee);
+impl ExecutionArgs {
+    /// Sends transactions and waits for them to confirm, for commands that run several dependent
+    /// transactions in a row.
+    pub fn watching(poll_interval: Option<u64>) -> Self {
+        Self {
+            simulate: false,
+            build_only: false,
+            nonce: None,
+            watch: true,
+            poll_interval,
+        }
+    }
 
-        if self.simulate {
-            let simulation = execution.simulate(false, false).await?;
-            let simulation_json = serde_json::to_value(simulation)?;
+    pub fn validate(&self, fee_setting: &FeeSetting) -> Result<()> {
+        if self.simulate && fee_setting.is_estimate_only() {
+            anyhow::bail!("--simulate cannot be used with --estimate-only");
+        }
+
+        Ok(())
+    }
 
-            let simulation_json =
-                colored_json::to_colored_json(&simulation_json, ColorMode::Auto(Output::StdOut))?;
-            println!("{simulation_json}");
-            return Ok(());
+    /// Whether a transaction is actually sent, as opposed to only estimating, simulating, or
+    /// building it.
+    pub fn sends_transaction(&self, fee_setting: &FeeSetting) -> bool {
+        !(self.simulate || self.build_only || fee_setting.is_estimate_only())
+    }
+
+    /// Whether the transaction is confirmed by the time `execute` returns.
+    pub fn watches(&self) -> bool {
+        self.watch
+    }
+
+    /// Sends the calls in a single transaction, returning the transaction hash if the transaction
+    /// is sent.
+    pub async fn execute(
+        self,
+        provider: Arc<ExtendedProvider>,
+        account_args: AccountArgs,
+        fee_setting: FeeSetting,
+        calls: Vec<Call>,
+    ) -> Result<Option<FieldElement>> {
+        if self.build_only {
+            let account = account_args.resolve_unsigned(&provider).await?;
+
+            let transaction_file = TransactionFile::build(
+                &provider,
+                account.chain_id,
+                self.nonce,
+                V3Transaction::invoke(account.address, &calls, account.encoding),
+                &fee_setting,
+                DEFAULT_FEE_MULTIPLIER,
+                account.num_signatures(),
+            )
+            .await?;
+
+            transaction_file.print()?;
+            return Ok(None);
         }
 
-        let invoke_tx = execution.send().await?.transaction_hash;
+        let invoke_tx = match fee_setting {
+            FeeSetting::Eth(fee_setting) => {
+                let account = account_args.resolve(&provider).await?;
+
+                if account.guardian.is_some() {
+                    TransactionFile::send_co_signed(
+                        &provider,
+                        &account,
+                        self.nonce,
+                        V3Transaction::invoke(account.address, &calls, account.encoding),
+                        fee_setting,
+                        DEFAULT_FEE_MULTIPLIER,
+                        self.simulate,
+                    )
+                    .await?
+                } else {
+                    let account = account.into_single_owner(provider.clone());
+
+                    let execution = account.execute(calls).fee_estimate_multiplier(1.5f64);
+
+                    let max_fee = match fee_setting {
+                        EthFeeSetting::Manual(fee) => fee,
+                        EthFeeSetting::EstimateOnly | EthFeeSetting::Estimate { .. } => {
+                            let estimate = execution
+                                .estimate_fee()
+                                .await
+                                .map_err(account_error_mapper)?;
+                            record_fee_estimate(&estimate, FeeToken::Eth);
+
+                            if matches!(fee_setting, EthFeeSetting::EstimateOnly) {
+                                print_fee_estimate(&estimate, FeeToken::Eth);
+                                return Ok(None);
+                            }
+
+                            fee_setting
+                                .apply_multiplier(estimate.overall_fee, DEFAULT_FEE_MULTIPLIER)
+                        }
+                    };
+
+                    let execution = match self.nonce {
+                        Some(nonce) => execution.nonce(nonce),
+                        None => execution,
+                    };
+                    let execution = execution.max_fee(max_fee);
+
+                    if self.simulate {
+                        let simulation = execution.simulate(false, false).await?;
+                        print_colored_json(&simulation)?;
+                        return Ok(None);
+                    }
+
+                    execution.send().await?.transaction_hash
+                }
+            }
+            FeeSetting::Strk(fee_setting) => {
+                let account = account_args.resolve(&provider).await?;
+
+                let sender = V3Sender::new(
+                    &provider,
+                    &account,
+                    account.chain_id,
+                    self.nonce,
+                    V3Transaction::invoke(account.address, &calls, account.encoding),
+                )
+                .await?;
+
+                let fee = match sender
+                    .resolve_fee(&fee_setting, DEFAULT_FEE_MULTIPLIER)
+                    .await?
+                {
+                    Some(fee) => fee,
+                    None => return Ok(None),
+                };
+
+                if self.simulate {
+                    let simulation = sender.simulate(&fee).await?;
+                    print_colored_json(&simulation)?;
+                    return Ok(None);
+                }
+
+                sender.send(&fee).await?
+            }
+        };
+
         eprintln!(
             "Invoke transaction: {}",
             format!("{:#064x}", invoke_tx).bright_yellow()
         );
 
+        record_transaction(invoke_tx);
+
         if self.watch {
             eprintln!(
                 "Waiting for transaction {} to confirm...",
@@ -158,11 +371,11 @@ impl Invoke {
             watch_tx(
                 &provider,
                 invoke_tx,
-                Duration::from_millis(self.poll_interval),
+                ProfileDefaults::resolve_poll_interval(self.poll_interval)?,
             )
             .await?;
         }
 
-        Ok(())
+        Ok(Some(invoke_tx))
     }
 }
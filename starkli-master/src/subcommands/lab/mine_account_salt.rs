@@ -0,0 +1,65 @@
+use std::{io::Write, path::PathBuf};
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::core::utils::UdcUniqueness;
+
+use crate::{
+    account::{AccountConfig, DeploymentStatus},
+    output::record,
+    path::ExpandedPathbufParser,
+    subcommands::lab::{mine_udc_salt::print_results, salt_miner::MiningArgs},
+};
+
+#[derive(Debug, Parser)]
+pub struct MineAccountSalt {
+    #[clap(flatten)]
+    mining: MiningArgs,
+    #[clap(
+        value_parser = ExpandedPathbufParser,
+        help = "Path to the config file of an undeployed account"
+    )]
+    file: PathBuf,
+}
+
+impl MineAccountSalt {
+    pub fn run(self) -> Result<()> {
+        let mut account: AccountConfig = serde_json::from_reader(std::fs::File::open(&self.file)?)?;
+
+        let calldata = account.deploy_account_calldata()?;
+        let class_hash = match &account.deployment {
+            DeploymentStatus::Undeployed(undeployed_status) => undeployed_status.class_hash,
+            DeploymentStatus::Deployed(_) => anyhow::bail!("account already deployed"),
+        };
+
+        // Accounts are deployed with a deployer address of zero, which is exactly how the UDC
+        // computes addresses for deployments that are not unique
+        let (results, duration) =
+            self.mining
+                .mine(UdcUniqueness::NotUnique, class_hash, &calldata)?;
+
+        print_results(&results, duration);
+
+        // The first salt found is used when collecting multiple candidates
+        if let DeploymentStatus::Undeployed(undeployed_status) = &mut account.deployment {
+            undeployed_status.salt = results[0].nonce;
+        }
+
+        let mut file = std::fs::File::create(&self.file)?;
+        serde_json::to_writer_pretty(&mut file, &account)?;
+        file.write_all(b"\n")?;
+
+        let deployed_address = account.deploy_account_address()?;
+        record("address", format!("{:#064x}", deployed_address));
+
+        eprintln!(
+            "Account config file updated with salt {}. Once deployed, this account will be \
+            available at:\n    {}",
+            format!("{:#064x}", results[0].nonce).bright_yellow(),
+            format!("{:#064x}", deployed_address).bright_yellow()
+        );
+
+        Ok(())
+    }
+}
@@ -1,9 +1,14 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 
+mod salt_miner;
+
 mod mine_udc_salt;
 use mine_udc_salt::MineUdcSalt;
 
+mod mine_account_salt;
+use mine_account_salt::MineAccountSalt;
+
 #[derive(Debug, Parser)]
 pub struct Lab {
     #[clap(subcommand)]
@@ -14,12 +19,17 @@ pub struct Lab {
 enum Subcommands {
     #[clap(about = "Mine UDC contract deployment salt for specific address prefix and/or suffix")]
     MineUdcSalt(MineUdcSalt),
+    #[clap(
+        about = "Mine the salt of an undeployed account for specific address prefix and/or suffix"
+    )]
+    MineAccountSalt(MineAccountSalt),
 }
 
 impl Lab {
-    pub fn run(self) -> Result<()> {
+    pub async fn run(self) -> Result<()> {
         match self.command {
-            Subcommands::MineUdcSalt(cmd) => cmd.run(),
+            Subcommands::MineUdcSalt(cmd) => cmd.run().await,
+            Subcommands::MineAccountSalt(cmd) => cmd.run(),
         }
     }
 }
@@ -0,0 +1,398 @@
+//! Salt mining shared by the `mine-*-salt` commands. Contract addresses are derived from the
+//! salt, so candidate salts are tried until the address matches the requested pattern.
+
+use std::{
+    io::IsTerminal,
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, SystemTime},
+};
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use rand::{rngs::StdRng, RngCore, SeedableRng};
+use rayon::prelude::*;
+use starknet::{
+    core::{
+        crypto::{compute_hash_on_elements, pedersen_hash},
+        types::FieldElement,
+        utils::{normalize_address, UdcUniqueness},
+    },
+    macros::felt,
+};
+
+// Cairo string of "STARKNET_CONTRACT_ADDRESS"
+const CONTRACT_ADDRESS_PREFIX: FieldElement = FieldElement::from_mont([
+    3829237882463328880,
+    17289941567720117366,
+    8635008616843941496,
+    533439743893157637,
+]);
+
+/// Number of attempts each job makes before adding them to the shared counter, so that jobs don't
+/// contend on the counter.
+const ATTEMPTS_REPORT_BATCH: u64 = 4096;
+
+const PROGRESS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Contract addresses are always normalized to be below this value.
+const ADDR_BOUND: FieldElement =
+    felt!("0x7ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff00");
+
+#[derive(Debug, Clone, Parser)]
+pub struct MiningArgs {
+    #[clap(
+        long,
+        default_value = "",
+        help = "Address prefix, either in BINARY representation ASSUMING 252-BIT ADDRESSES, or in \
+        case-insensitive hex with the 0x prefix, ASSUMING 63 HEX DIGITS (e.g. 0x0abc)"
+    )]
+    prefix: String,
+    #[clap(
+        long,
+        default_value = "",
+        help = "Address suffix, either in BINARY representation, or in case-insensitive hex with \
+        the 0x prefix"
+    )]
+    suffix: String,
+    #[clap(long, default_value = "1", help = "The number of parallel jobs to run")]
+    jobs: u32,
+    #[clap(
+        long,
+        default_value = "1",
+        help = "The number of matching salts to find before stopping"
+    )]
+    count: usize,
+}
+
+#[derive(Debug)]
+pub struct MineResult {
+    pub nonce: FieldElement,
+    pub deployed_address: FieldElement,
+}
+
+struct Miner {
+    udc_uniqueness: UdcUniqueness,
+    class_hash: FieldElement,
+    ctor_hash: FieldElement,
+    bloom: [bool; 252],
+    suffix_length: usize,
+    prefix_length: usize,
+    start_nonce: FieldElement,
+    count: usize,
+    results: Arc<Mutex<Vec<MineResult>>>,
+    attempts: Arc<AtomicU64>,
+    cancellation_token: Arc<AtomicBool>,
+}
+
+impl MiningArgs {
+    /// Mines salts for a contract deployed with `udc_uniqueness`. Direct deployments, such as
+    /// account deployments, are the same as UDC deployments that are not unique. Returns the
+    /// results in the order they're found, along with the time spent.
+    pub fn mine(
+        &self,
+        udc_uniqueness: UdcUniqueness,
+        class_hash: FieldElement,
+        ctor_args: &[FieldElement],
+    ) -> Result<(Vec<MineResult>, Duration)> {
+        if self.prefix.is_empty() && self.suffix.is_empty() {
+            anyhow::bail!("at least one of --prefix and --suffix must be used");
+        }
+        if self.count == 0 {
+            anyhow::bail!("--count must be at least 1");
+        }
+
+        let prefix_bits = parse_pattern(&self.prefix)?;
+        let suffix_bits = parse_pattern(&self.suffix)?;
+
+        if prefix_bits.len() > 252 {
+            anyhow::bail!("invalid prefix length");
+        }
+        if suffix_bits.len() > 252 {
+            anyhow::bail!("invalid suffix length");
+        }
+
+        // Bits in the bloom are little-endian, with the suffix at the start
+        let suffix_len = suffix_bits.len();
+        let prefix_len = prefix_bits.len();
+
+        let mut bloom = [false; 252];
+        bloom[..suffix_len].copy_from_slice(&suffix_bits.into_iter().rev().collect::<Vec<_>>());
+        bloom[(252 - prefix_len)..]
+            .copy_from_slice(&prefix_bits.into_iter().rev().collect::<Vec<_>>());
+
+        if validate_bloom(&bloom).is_err() {
+            anyhow::bail!("prefix/suffix out of range and impossible to mine");
+        }
+
+        // Each matching bit halves the chance of an address matching
+        let expected_attempts = 2f64.powi((prefix_len + suffix_len) as i32) * self.count as f64;
+        eprintln!(
+            "Mining {} salt(s) for {} fixed bits. Expected attempts: {}",
+            self.count.to_string().bright_yellow(),
+            (prefix_len + suffix_len).to_string().bright_yellow(),
+            format!("{:.0}", expected_attempts).bright_yellow()
+        );
+
+        let ctor_hash = compute_hash_on_elements(ctor_args);
+
+        let start_time = SystemTime::now();
+
+        let cancellation_token = Arc::new(AtomicBool::new(false));
+        let attempts = Arc::new(AtomicU64::new(0));
+        let results = Arc::new(Mutex::new(vec![]));
+
+        let progress = if std::io::stderr().is_terminal() {
+            let attempts = attempts.clone();
+            let cancellation_token = cancellation_token.clone();
+            Some(std::thread::spawn(move || {
+                report_progress(
+                    start_time,
+                    expected_attempts,
+                    &attempts,
+                    &cancellation_token,
+                )
+            }))
+        } else {
+            None
+        };
+
+        // Randomizes starting nonces so that the mining process can be horizontally scaled.
+        let mut rng = StdRng::from_entropy();
+        let mut nonce_offset = [0u8; 32];
+        rng.fill_bytes(&mut nonce_offset[1..]);
+
+        // We only filled 31 bytes so this value is always in range.
+        let nonce_offset = FieldElement::from_bytes_be(&nonce_offset).unwrap();
+
+        (0..self.jobs).into_par_iter().for_each(|job_id| {
+            let start_nonce = FieldElement::MAX.floor_div(self.jobs.into())
+                * FieldElement::from(job_id)
+                + nonce_offset;
+
+            let miner = Miner {
+                udc_uniqueness: udc_uniqueness.clone(),
+                class_hash,
+                ctor_hash,
+                bloom,
+                suffix_length: suffix_len,
+                prefix_length: prefix_len,
+                start_nonce,
+                count: self.count,
+                results: results.clone(),
+                attempts: attempts.clone(),
+                cancellation_token: cancellation_token.clone(),
+            };
+
+            miner.mine();
+        });
+
+        if let Some(progress) = progress {
+            let _ = progress.join();
+        }
+
+        let end_time = SystemTime::now();
+
+        let duration = end_time.duration_since(start_time)?;
+
+        // More results than needed can be found when jobs finish at the same time
+        let mut results = std::mem::take(&mut *results.lock().unwrap());
+        results.truncate(self.count);
+
+        Ok((results, duration))
+    }
+}
+
+impl Miner {
+    /// Mines until enough results are found across all jobs.
+    fn mine(&self) {
+        let bloom = self.bloom;
+        let suffix_len = self.suffix_length;
+        let prefix_len = self.prefix_length;
+
+        let mut nonce = self.start_nonce;
+        let mut pending_attempts = 0u64;
+
+        while !self.cancellation_token.load(Ordering::Relaxed) {
+            let (effective_salt, effective_deployer) = match &self.udc_uniqueness {
+                UdcUniqueness::NotUnique => (nonce, FieldElement::ZERO),
+                UdcUniqueness::Unique(settings) => (
+                    pedersen_hash(&settings.deployer_address, &nonce),
+                    settings.udc_contract_address,
+                ),
+            };
+
+            let deployed_address = normalize_address(compute_hash_on_elements(&[
+                CONTRACT_ADDRESS_PREFIX,
+                effective_deployer,
+                effective_salt,
+                self.class_hash,
+                self.ctor_hash,
+            ]));
+
+            let address_bits = deployed_address.to_bits_le();
+
+            if Self::validate_address(&address_bits[..252], &bloom, suffix_len, prefix_len) {
+                let mut results = self.results.lock().unwrap();
+                results.push(MineResult {
+                    nonce,
+                    deployed_address,
+                });
+
+                if results.len() >= self.count {
+                    self.cancellation_token.store(true, Ordering::Relaxed);
+                }
+            }
+
+            pending_attempts += 1;
+            if pending_attempts == ATTEMPTS_REPORT_BATCH {
+                self.attempts.fetch_add(pending_attempts, Ordering::Relaxed);
+                pending_attempts = 0;
+            }
+
+            nonce += FieldElement::ONE;
+        }
+
+        self.attempts.fetch_add(pending_attempts, Ordering::Relaxed);
+    }
+
+    #[inline(always)]
+    fn validate_address(
+        address: &[bool],
+        bloom: &[bool],
+        suffix_len: usize,
+        prefix_len: usize,
+    ) -> bool {
+        for ind in 0..suffix_len {
+            unsafe {
+                if address.get_unchecked(ind) != bloom.get_unchecked(ind) {
+                    return false;
+                }
+            }
+        }
+
+        for ind in (252 - prefix_len)..252 {
+            unsafe {
+                if address.get_unchecked(ind) != bloom.get_unchecked(ind) {
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+}
+
+/// Parses a pattern into bits, from the most significant to the least significant. Hex patterns
+/// are prefixed with `0x`, with each digit taking 4 bits.
+fn parse_pattern(pattern: &str) -> Result<Vec<bool>> {
+    match pattern
+        .strip_prefix("0x")
+        .or_else(|| pattern.strip_prefix("0X"))
+    {
+        Some(hex) => {
+            let mut bits = vec![];
+            for digit in hex.chars() {
+                let value = digit
+                    .to_digit(16)
+                    .ok_or_else(|| anyhow::anyhow!("invalid hex digit: {}", digit))?;
+                bits.extend((0..4).rev().map(|ind| value & (1 << ind) != 0));
+            }
+            Ok(bits)
+        }
+        None => pattern
+            .chars()
+            .map(|bit| match bit {
+                '1' => Ok(true),
+                '0' => Ok(false),
+                _ => anyhow::bail!("invalid bit: {}", bit),
+            })
+            .collect(),
+    }
+}
+
+/// Prints the hash rate and estimated time left on a single line until mining is done.
+fn report_progress(
+    start_time: SystemTime,
+    expected_attempts: f64,
+    attempts: &AtomicU64,
+    cancellation_token: &AtomicBool,
+) {
+    // Checks for completion more often than printing so that the final result is not delayed
+    let tick = Duration::from_millis(100);
+    let ticks_per_report = (PROGRESS_INTERVAL.as_millis() / tick.as_millis()) as u32;
+
+    let mut ticks = 0u32;
+    while !cancellation_token.load(Ordering::Relaxed) {
+        std::thread::sleep(tick);
+
+        ticks += 1;
+        if ticks % ticks_per_report != 0 {
+            continue;
+        }
+
+        let elapsed = start_time.elapsed().unwrap_or_default().as_secs_f64();
+        let attempts = attempts.load(Ordering::Relaxed);
+        let hash_rate = attempts as f64 / elapsed;
+
+        let eta = if hash_rate > 0.0 {
+            let remaining = (expected_attempts - attempts as f64).max(0.0) / hash_rate;
+            format!("~{}", format_duration(remaining as u64))
+        } else {
+            String::from("unknown")
+        };
+
+        eprint!(
+            "\r\x1b[2KAttempts: {}  Hash rate: {}  ETA: {}",
+            attempts.to_string().bright_yellow(),
+            format!("{:.0} H/s", hash_rate).bright_yellow(),
+            eta.bright_yellow()
+        );
+    }
+
+    // Clears the progress line
+    eprint!("\r\x1b[2K");
+}
+
+fn format_duration(seconds: u64) -> String {
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+    if hours > 0 {
+        format!("{}h {}m {}s", hours, minutes, seconds)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+fn validate_bloom(bloom: &[bool]) -> Result<()> {
+    let mut bloom_256 = [false; 256];
+    bloom_256[..252].copy_from_slice(bloom);
+    bloom_256.reverse();
+
+    let bytes = bloom_256
+        .chunks_exact(8)
+        .map(|bits| {
+            (if bits[0] { 128u8 } else { 0 })
+                + (if bits[1] { 64u8 } else { 0 })
+                + (if bits[2] { 32u8 } else { 0 })
+                + (if bits[3] { 16u8 } else { 0 })
+                + (if bits[4] { 8u8 } else { 0 })
+                + (if bits[5] { 4u8 } else { 0 })
+                + (if bits[6] { 2u8 } else { 0 })
+                + (if bits[7] { 1u8 } else { 0 })
+        })
+        .collect::<Vec<_>>();
+
+    // Unfixed bits are all zeros here, so this is the smallest address matching the pattern
+    let min_address = FieldElement::from_byte_slice_be(&bytes)?;
+    if min_address >= ADDR_BOUND {
+        anyhow::bail!("pattern exceeds the maximum address");
+    }
+
+    Ok(())
+}
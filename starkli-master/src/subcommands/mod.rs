@@ -25,6 +25,9 @@ pub use block_time::BlockTime;
 mod transaction_receipt;
 pub use transaction_receipt::TransactionReceipt;
 
+mod events;
+pub use events::Events;
+
 mod chain_id;
 pub use chain_id::ChainId;
 
@@ -37,6 +40,9 @@ pub use parse_cairo_string::ParseCairoString;
 mod mont;
 pub use mont::Mont;
 
+mod address;
+pub use address::Address;
+
 mod class_by_hash;
 pub use class_by_hash::ClassByHash;
 
@@ -67,6 +73,12 @@ pub use signer::Signer;
 mod account;
 pub use account::Account;
 
+mod profile;
+pub use profile::Profile;
+
+mod address_book;
+pub use address_book::AddressBook;
+
 mod deploy;
 pub use deploy::Deploy;
 
@@ -79,6 +91,12 @@ pub use call::Call;
 mod invoke;
 pub use invoke::Invoke;
 
+mod erc20;
+pub use erc20::Erc20;
+
+mod apply;
+pub use apply::Apply;
+
 mod lab;
 pub use lab::Lab;
 
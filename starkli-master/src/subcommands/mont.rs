@@ -1,7 +1,10 @@
 use anyhow::Result;
 use clap::Parser;
 
-use crate::utils::parse_felt_value;
+use crate::{
+    output::{is_json_output, record},
+    utils::parse_felt_value,
+};
 
 #[derive(Debug, Parser)]
 pub struct Mont {
@@ -16,16 +19,28 @@ impl Mont {
         let felt = parse_felt_value(&self.felt)?;
         let mont = felt.into_mont();
 
+        let elements = mont
+            .into_iter()
+            .map(|element| {
+                if self.hex {
+                    format!("{:#x}", element)
+                } else {
+                    format!("{}", element)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        if is_json_output() {
+            record("result", elements);
+            return Ok(());
+        }
+
         let mut output = String::new();
 
         output.push_str("[\n");
 
-        for element in mont.into_iter() {
-            output.push_str(&if self.hex {
-                format!("    {:#x},\n", element)
-            } else {
-                format!("    {},\n", element)
-            });
+        for element in elements.iter() {
+            output.push_str(&format!("    {},\n", element));
         }
 
         output.push_str("]\n");
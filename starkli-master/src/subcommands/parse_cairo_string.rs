@@ -2,7 +2,7 @@ use anyhow::Result;
 use clap::Parser;
 use starknet::core::utils::parse_cairo_short_string;
 
-use crate::utils::parse_felt_value;
+use crate::{output::print_text, utils::parse_felt_value};
 
 #[derive(Debug, Parser)]
 pub struct ParseCairoString {
@@ -14,7 +14,7 @@ impl ParseCairoString {
     pub fn run(self) -> Result<()> {
         let felt = parse_felt_value(&self.felt)?;
         let decoded = parse_cairo_short_string(&felt)?;
-        println!("{decoded}");
+        print_text("result", decoded);
 
         Ok(())
     }
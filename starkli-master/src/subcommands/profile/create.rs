@@ -0,0 +1,37 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use crate::profile::{Profile, Profiles};
+
+#[derive(Debug, Parser)]
+pub struct Create {
+    #[clap(long, help = "Also set the new profile as the default profile")]
+    set_default: bool,
+    #[clap(help = "Name of the new profile")]
+    name: String,
+}
+
+impl Create {
+    pub fn run(self) -> Result<()> {
+        Profiles::validate_profile_name(&self.name)?;
+
+        let mut profiles = Profiles::load()?;
+        if profiles.profiles.contains_key(&self.name) {
+            anyhow::bail!("profile `{}` already exists", self.name);
+        }
+
+        profiles
+            .profiles
+            .insert(self.name.clone(), Profile::default());
+        if self.set_default {
+            profiles.default_profile = Some(self.name.clone());
+        }
+
+        profiles.save()?;
+
+        eprintln!("Profile {} created.", self.name.bright_yellow());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use crate::profile::{Profiles, DEFAULT_PROFILE_NAME};
+
+#[derive(Debug, Parser)]
+pub struct Delete {
+    #[clap(help = "Name of the profile to delete")]
+    name: String,
+}
+
+impl Delete {
+    pub fn run(self) -> Result<()> {
+        let mut profiles = Profiles::load()?;
+
+        profiles.get_profile(&self.name)?;
+        profiles.profiles.shift_remove(&self.name);
+
+        if profiles.default_profile.as_deref() == Some(self.name.as_str()) {
+            profiles.default_profile = None;
+
+            eprintln!(
+                "{}",
+                format!(
+                    "NOTE: the deleted profile was the default profile. The `{}` profile is now \
+                    used by default.",
+                    DEFAULT_PROFILE_NAME
+                )
+                .bright_magenta()
+            );
+        }
+
+        profiles.save()?;
+
+        eprintln!("Profile {} deleted.", self.name.bright_yellow());
+
+        Ok(())
+    }
+}
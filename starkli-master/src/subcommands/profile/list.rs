@@ -0,0 +1,45 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use crate::{
+    output::{is_json_output, record},
+    profile::{Profiles, DEFAULT_PROFILE_NAME},
+};
+
+#[derive(Debug, Parser)]
+pub struct List {}
+
+impl List {
+    pub fn run(self) -> Result<()> {
+        let profiles = Profiles::load()?;
+        let active_profile = profiles.active_profile_name();
+
+        let mut names = profiles
+            .profiles
+            .keys()
+            .map(|name| name.as_str())
+            .collect::<Vec<_>>();
+
+        // The `default` profile always exists implicitly
+        if !profiles.profiles.contains_key(DEFAULT_PROFILE_NAME) {
+            names.insert(0, DEFAULT_PROFILE_NAME);
+        }
+
+        if is_json_output() {
+            record("profiles", &names);
+            record("active", active_profile);
+            return Ok(());
+        }
+
+        for name in names.into_iter() {
+            if name == active_profile {
+                println!("{} {}", name.bright_yellow(), "(active)".bright_green());
+            } else {
+                println!("{}", name);
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+mod list;
+use list::List;
+
+mod show;
+use show::Show;
+
+mod create;
+use create::Create;
+
+mod delete;
+use delete::Delete;
+
+mod set_default;
+use set_default::SetDefault;
+
+mod network;
+use network::Network;
+
+#[derive(Debug, Parser)]
+pub struct Profile {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(about = "List all profiles")]
+    List(List),
+    #[clap(about = "Show networks defined in a profile")]
+    Show(Show),
+    #[clap(about = "Create a new empty profile")]
+    Create(Create),
+    #[clap(about = "Delete a profile and all its networks")]
+    Delete(Delete),
+    #[clap(about = "Set the profile to use when --profile is not specified")]
+    SetDefault(SetDefault),
+    #[clap(about = "Manage networks of the active profile")]
+    Network(Network),
+}
+
+impl Profile {
+    pub async fn run(self) -> Result<()> {
+        match self.command {
+            Subcommands::List(cmd) => cmd.run(),
+            Subcommands::Show(cmd) => cmd.run(),
+            Subcommands::Create(cmd) => cmd.run(),
+            Subcommands::Delete(cmd) => cmd.run(),
+            Subcommands::SetDefault(cmd) => cmd.run(),
+            Subcommands::Network(cmd) => cmd.run().await,
+        }
+    }
+}
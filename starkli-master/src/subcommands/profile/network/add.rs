@@ -0,0 +1,89 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::{
+    core::{
+        types::FieldElement,
+        utils::{cairo_short_string_to_felt, parse_cairo_short_string},
+    },
+    providers::{jsonrpc::HttpTransport, JsonRpcClient, Provider},
+};
+use url::Url;
+
+use crate::profile::{Network, NetworkProvider, Profiles};
+
+#[derive(Debug, Parser)]
+pub struct Add {
+    #[clap(long, help = "JSON-RPC endpoint of the network")]
+    rpc: Url,
+    #[clap(
+        long,
+        help = "Chain ID as a short string (e.g. SN_SEPOLIA). Fetched from the JSON-RPC endpoint \
+        if not specified"
+    )]
+    chain_id: Option<String>,
+    #[clap(long, help = "Human-readable network name")]
+    name: Option<String>,
+    #[clap(long, help = "Mark the network as an integration network")]
+    integration: bool,
+    #[clap(
+        long,
+        help = "Address of the Universal Deployer Contract, for networks with their own deployer"
+    )]
+    udc: Option<FieldElement>,
+    #[clap(help = "Identifier of the network to be used with --network")]
+    id: String,
+}
+
+impl Add {
+    pub async fn run(self) -> Result<()> {
+        let mut profiles = Profiles::load()?;
+        let profile_name = profiles.active_profile_name().to_owned();
+        let profile = profiles.active_profile_mut()?;
+
+        if profile.networks.contains_key(&self.id) {
+            anyhow::bail!(
+                "network `{}` already exists in profile `{}`",
+                self.id,
+                profile_name
+            );
+        }
+
+        let chain_id = match &self.chain_id {
+            Some(chain_id) => cairo_short_string_to_felt(chain_id)?,
+            None => {
+                let chain_id = JsonRpcClient::new(HttpTransport::new(self.rpc.clone()))
+                    .chain_id()
+                    .await?;
+
+                eprintln!(
+                    "Chain ID fetched from endpoint: {}",
+                    parse_cairo_short_string(&chain_id)?.bright_yellow()
+                );
+
+                chain_id
+            }
+        };
+
+        profile.networks.insert(
+            self.id.clone(),
+            Network {
+                name: self.name,
+                chain_id,
+                is_integration: self.integration,
+                provider: NetworkProvider::Rpc(self.rpc),
+                udc: self.udc,
+            },
+        );
+
+        profiles.save()?;
+
+        eprintln!(
+            "Network {} added to profile {}.",
+            self.id.bright_yellow(),
+            profile_name.bright_yellow()
+        );
+
+        Ok(())
+    }
+}
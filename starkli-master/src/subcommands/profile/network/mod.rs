@@ -0,0 +1,37 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+
+mod add;
+use add::Add;
+
+mod remove;
+use remove::Remove;
+
+mod set_rpc;
+use set_rpc::SetRpc;
+
+#[derive(Debug, Parser)]
+pub struct Network {
+    #[clap(subcommand)]
+    command: Subcommands,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(about = "Add a network with a JSON-RPC endpoint")]
+    Add(Add),
+    #[clap(about = "Remove a network")]
+    Remove(Remove),
+    #[clap(about = "Change the JSON-RPC endpoint of a network")]
+    SetRpc(SetRpc),
+}
+
+impl Network {
+    pub async fn run(self) -> Result<()> {
+        match self.command {
+            Subcommands::Add(cmd) => cmd.run().await,
+            Subcommands::Remove(cmd) => cmd.run(),
+            Subcommands::SetRpc(cmd) => cmd.run(),
+        }
+    }
+}
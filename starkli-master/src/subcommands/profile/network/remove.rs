@@ -0,0 +1,37 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use crate::profile::Profiles;
+
+#[derive(Debug, Parser)]
+pub struct Remove {
+    #[clap(help = "Identifier of the network to remove")]
+    id: String,
+}
+
+impl Remove {
+    pub fn run(self) -> Result<()> {
+        let mut profiles = Profiles::load()?;
+        let profile_name = profiles.active_profile_name().to_owned();
+        let profile = profiles.get_profile_mut(&profile_name)?;
+
+        if profile.networks.shift_remove(&self.id).is_none() {
+            anyhow::bail!(
+                "network `{}` not found in profile `{}`",
+                self.id,
+                profile_name
+            );
+        }
+
+        profiles.save()?;
+
+        eprintln!(
+            "Network {} removed from profile {}.",
+            self.id.bright_yellow(),
+            profile_name.bright_yellow()
+        );
+
+        Ok(())
+    }
+}
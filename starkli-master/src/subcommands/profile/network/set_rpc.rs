@@ -0,0 +1,42 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use url::Url;
+
+use crate::profile::{NetworkProvider, Profiles};
+
+#[derive(Debug, Parser)]
+pub struct SetRpc {
+    #[clap(help = "Identifier of the network to update")]
+    id: String,
+    #[clap(help = "New JSON-RPC endpoint of the network")]
+    rpc: Url,
+}
+
+impl SetRpc {
+    pub fn run(self) -> Result<()> {
+        let mut profiles = Profiles::load()?;
+        let profile_name = profiles.active_profile_name().to_owned();
+        let profile = profiles.get_profile_mut(&profile_name)?;
+
+        let network = profile.networks.get_mut(&self.id).ok_or_else(|| {
+            anyhow::anyhow!(
+                "network `{}` not found in profile `{}`",
+                self.id,
+                profile_name
+            )
+        })?;
+
+        // This also replaces free RPC vendors previously assigned to the network
+        network.provider = NetworkProvider::Rpc(self.rpc);
+
+        profiles.save()?;
+
+        eprintln!(
+            "JSON-RPC endpoint of network {} updated.",
+            self.id.bright_yellow()
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,31 @@
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+
+use crate::profile::{Profiles, DEFAULT_PROFILE_NAME};
+
+#[derive(Debug, Parser)]
+pub struct SetDefault {
+    #[clap(help = "Name of the profile to use by default")]
+    name: String,
+}
+
+impl SetDefault {
+    pub fn run(self) -> Result<()> {
+        let mut profiles = Profiles::load()?;
+
+        if self.name == DEFAULT_PROFILE_NAME {
+            // No need to persist the fallback value
+            profiles.default_profile = None;
+        } else {
+            profiles.get_profile(&self.name)?;
+            profiles.default_profile = Some(self.name.clone());
+        }
+
+        profiles.save()?;
+
+        eprintln!("Default profile set to {}.", self.name.bright_yellow());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+use anyhow::Result;
+use clap::Parser;
+
+use crate::{
+    output::{is_json_output, record},
+    profile::{Profile, Profiles, DEFAULT_PROFILE_NAME},
+};
+
+#[derive(Debug, Parser)]
+pub struct Show {
+    #[clap(help = "Name of the profile to show. Defaults to the active profile")]
+    name: Option<String>,
+}
+
+impl Show {
+    pub fn run(self) -> Result<()> {
+        let profiles = Profiles::load()?;
+
+        let name = match &self.name {
+            Some(name) => name.as_str(),
+            None => profiles.active_profile_name(),
+        };
+
+        let empty_profile = Profile::default();
+        let profile = match profiles.profiles.get(name) {
+            Some(profile) => profile,
+            // The `default` profile always exists implicitly
+            None if name == DEFAULT_PROFILE_NAME => &empty_profile,
+            None => profiles.get_profile(name)?,
+        };
+
+        if is_json_output() {
+            record("name", name);
+            record("profile", profile);
+        } else if profile.networks.is_empty() {
+            eprintln!("No network is defined in profile `{}`.", name);
+        } else {
+            print!("{}", toml::to_string_pretty(profile)?);
+        }
+
+        Ok(())
+    }
+}
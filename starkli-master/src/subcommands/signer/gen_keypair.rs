@@ -2,6 +2,8 @@ use anyhow::Result;
 use clap::Parser;
 use starknet::signers::SigningKey;
 
+use crate::output::{is_json_output, record};
+
 #[derive(Debug, Parser)]
 pub struct GenKeypair {}
 
@@ -9,6 +11,15 @@ impl GenKeypair {
     pub fn run(self) -> Result<()> {
         let key = SigningKey::from_random();
 
+        if is_json_output() {
+            record("private_key", format!("{:#064x}", key.secret_scalar()));
+            record(
+                "public_key",
+                format!("{:#064x}", key.verifying_key().scalar()),
+            );
+            return Ok(());
+        }
+
         println!("Private key : {:#064x}", key.secret_scalar());
         println!("Public key  : {:#064x}", key.verifying_key().scalar());
 
@@ -5,7 +5,10 @@ use clap::Parser;
 use colored::Colorize;
 use starknet::{core::types::FieldElement, signers::SigningKey};
 
-use crate::path::ExpandedPathbufParser;
+use crate::{
+    output::{is_json_output, record},
+    path::ExpandedPathbufParser,
+};
 
 #[derive(Debug, Parser)]
 pub struct FromKey {
@@ -59,6 +62,15 @@ impl FromKey {
         let key = SigningKey::from_secret_scalar(private_key);
         key.save_as_keystore(&self.file, &password)?;
 
+        if is_json_output() {
+            record("file", std::fs::canonicalize(&self.file)?);
+            record(
+                "public_key",
+                format!("{:#064x}", key.verifying_key().scalar()),
+            );
+            return Ok(());
+        }
+
         println!(
             "Created new encrypted keystore file: {}",
             std::fs::canonicalize(self.file)?.display()
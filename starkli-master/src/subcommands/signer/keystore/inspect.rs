@@ -5,7 +5,10 @@ use clap::Parser;
 use colored::Colorize;
 use starknet::signers::SigningKey;
 
-use crate::path::ExpandedPathbufParser;
+use crate::{
+    output::{is_json_output, record},
+    path::ExpandedPathbufParser,
+};
 
 #[derive(Debug, Parser)]
 pub struct Inspect {
@@ -46,7 +49,12 @@ impl Inspect {
 
         let key = SigningKey::from_keystore(self.file, &password)?;
 
-        if self.raw {
+        if is_json_output() {
+            record(
+                "public_key",
+                format!("{:#064x}", key.verifying_key().scalar()),
+            );
+        } else if self.raw {
             println!("{:#064x}", key.verifying_key().scalar());
         } else {
             println!("Public key: {:#064x}", key.verifying_key().scalar());
@@ -5,7 +5,10 @@ use clap::Parser;
 use colored::Colorize;
 use starknet::signers::SigningKey;
 
-use crate::path::ExpandedPathbufParser;
+use crate::{
+    output::{is_json_output, record},
+    path::ExpandedPathbufParser,
+};
 
 #[derive(Debug, Parser)]
 pub struct InspectPrivate {
@@ -46,7 +49,9 @@ impl InspectPrivate {
 
         let key = SigningKey::from_keystore(self.file, &password)?;
 
-        if self.raw {
+        if is_json_output() {
+            record("private_key", format!("{:#064x}", key.secret_scalar()));
+        } else if self.raw {
             println!("{:#064x}", key.secret_scalar());
         } else {
             println!("Private key: {:#064x}", key.secret_scalar());
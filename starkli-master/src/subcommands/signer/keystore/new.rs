@@ -5,7 +5,10 @@ use clap::Parser;
 use colored::Colorize;
 use starknet::signers::SigningKey;
 
-use crate::path::ExpandedPathbufParser;
+use crate::{
+    output::{is_json_output, record},
+    path::ExpandedPathbufParser,
+};
 
 #[derive(Debug, Parser)]
 pub struct New {
@@ -47,6 +50,15 @@ impl New {
         let key = SigningKey::from_random();
         key.save_as_keystore(&self.file, &password)?;
 
+        if is_json_output() {
+            record("file", std::fs::canonicalize(&self.file)?);
+            record(
+                "public_key",
+                format!("{:#064x}", key.verifying_key().scalar()),
+            );
+            return Ok(());
+        }
+
         println!(
             "Created new encrypted keystore file: {}",
             std::fs::canonicalize(self.file)?.display()
@@ -2,7 +2,7 @@ use anyhow::Result;
 use clap::Parser;
 use starknet::providers::Provider;
 
-use crate::{verbosity::VerbosityArgs, ProviderArgs};
+use crate::{output::print_text, verbosity::VerbosityArgs, ProviderArgs};
 
 #[derive(Debug, Parser)]
 pub struct SpecVersion {
@@ -20,7 +20,7 @@ impl SpecVersion {
 
         let spec_version = provider.spec_version().await?;
 
-        println!("{}", spec_version);
+        print_text("spec_version", spec_version);
 
         Ok(())
     }
@@ -1,9 +1,12 @@
 use anyhow::Result;
 use clap::Parser;
-use colored_json::{ColorMode, Output};
 use starknet::providers::Provider;
 
-use crate::{utils::parse_block_id, verbosity::VerbosityArgs, ProviderArgs};
+use crate::{
+    utils::{parse_block_id, print_colored_json},
+    verbosity::VerbosityArgs,
+    ProviderArgs,
+};
 
 #[derive(Debug, Parser)]
 pub struct StateUpdate {
@@ -28,9 +31,7 @@ impl StateUpdate {
 
         let update_json = serde_json::to_value(provider.get_state_update(block_id).await?)?;
 
-        let update_json =
-            colored_json::to_colored_json(&update_json, ColorMode::Auto(Output::StdOut))?;
-        println!("{update_json}");
+        print_colored_json(&update_json)?;
 
         Ok(())
     }
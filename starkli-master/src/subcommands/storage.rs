@@ -2,13 +2,15 @@ use std::sync::Arc;
 
 use anyhow::Result;
 use clap::Parser;
-use starknet::{
-    core::types::{BlockId, BlockTag},
-    providers::Provider,
-};
+use starknet::{core::types::FieldElement, providers::Provider};
 
 use crate::{
-    address_book::AddressBookResolver, decode::FeltDecoder, verbosity::VerbosityArgs, ProviderArgs,
+    address_book::AddressBookResolver,
+    block::BlockArgs,
+    decode::FeltDecoder,
+    output::{is_json_output, record},
+    verbosity::VerbosityArgs,
+    ProviderArgs,
 };
 
 #[derive(Debug, Parser)]
@@ -17,9 +19,17 @@ pub struct Storage {
     provider: ProviderArgs,
     #[clap(help = "Contract address")]
     address: String,
+    #[clap(
+        long,
+        default_value = "1",
+        help = "Number of consecutive storage slots to read, such as 2 for u256 values"
+    )]
+    slots: u8,
     #[clap(help = "Storage key")]
     key: String,
     #[clap(flatten)]
+    block: BlockArgs,
+    #[clap(flatten)]
     verbosity: VerbosityArgs,
 }
 
@@ -27,7 +37,12 @@ impl Storage {
     pub async fn run(self) -> Result<()> {
         self.verbosity.setup_logging();
 
+        if self.slots == 0 {
+            anyhow::bail!("--slots must be at least 1");
+        }
+
         let provider = Arc::new(self.provider.into_provider()?);
+        let block_id = self.block.resolve(provider.as_ref()).await?;
         let felt_decoder = FeltDecoder::new(AddressBookResolver::new(provider.clone()));
 
         let address = felt_decoder
@@ -37,12 +52,23 @@ impl Storage {
             .decode_single_with_storage_fallback(&self.key)
             .await?;
 
-        // TODO: allow custom block
-        let value = provider
-            .get_storage_at(address, key, BlockId::Tag(BlockTag::Pending))
-            .await?;
+        // Multi-slot values are stored in consecutive slots starting from the base address
+        let mut values = vec![];
+        for offset in 0..self.slots {
+            let value = provider
+                .get_storage_at(address, key + FieldElement::from(offset), block_id)
+                .await?;
+
+            values.push(format!("{:#064x}", value));
+        }
 
-        println!("{:#064x}", value);
+        if is_json_output() {
+            record("values", values);
+        } else {
+            for value in values.iter() {
+                println!("{}", value);
+            }
+        }
 
         Ok(())
     }
@@ -1,9 +1,10 @@
 use anyhow::Result;
 use clap::Parser;
-use colored_json::{ColorMode, Output};
 use starknet::{core::types::SyncStatusType, providers::Provider};
 
-use crate::{verbosity::VerbosityArgs, ProviderArgs};
+use crate::{
+    output::print_text, utils::print_colored_json, verbosity::VerbosityArgs, ProviderArgs,
+};
 
 #[derive(Debug, Parser)]
 pub struct Syncing {
@@ -24,12 +25,10 @@ impl Syncing {
         match sync_status {
             SyncStatusType::Syncing(status) => {
                 let status_json = serde_json::to_value(status)?;
-                let status_json =
-                    colored_json::to_colored_json(&status_json, ColorMode::Auto(Output::StdOut))?;
-                println!("{status_json}");
+                print_colored_json(&status_json)?;
             }
             SyncStatusType::NotSyncing => {
-                println!("Not syncing");
+                print_text("result", "Not syncing");
             }
         }
 
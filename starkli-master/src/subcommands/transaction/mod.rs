@@ -0,0 +1,57 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use starknet::{core::types::FieldElement, providers::Provider};
+
+use crate::{utils::print_colored_json, verbosity::VerbosityArgs, ProviderArgs};
+
+mod sign;
+use sign::SignTransaction;
+
+mod send;
+use send::SendTransaction;
+
+#[derive(Debug, Parser)]
+#[clap(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+pub struct Transaction {
+    #[clap(subcommand)]
+    command: Option<Subcommands>,
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(required = true, help = "Transaction hash")]
+    hash: Option<String>,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+#[derive(Debug, Subcommand)]
+enum Subcommands {
+    #[clap(about = "Sign a transaction file built with --build-only")]
+    Sign(SignTransaction),
+    #[clap(about = "Broadcast a signed transaction file")]
+    Send(SendTransaction),
+}
+
+impl Transaction {
+    pub async fn run(self) -> Result<()> {
+        match self.command {
+            Some(Subcommands::Sign(cmd)) => return cmd.run().await,
+            Some(Subcommands::Send(cmd)) => return cmd.run().await,
+            None => {}
+        }
+
+        self.verbosity.setup_logging();
+
+        let provider = self.provider.into_provider()?;
+        let transaction_hash = FieldElement::from_hex_be(
+            // Safe to unwrap as it's required without subcommands
+            &self.hash.unwrap(),
+        )?;
+
+        let transaction = provider.get_transaction_by_hash(transaction_hash).await?;
+
+        let transaction_json = serde_json::to_value(transaction)?;
+        print_colored_json(&transaction_json)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::providers::Provider;
+
+use crate::{
+    offline::TransactionFile,
+    output::{is_json_output, record_transaction},
+    path::ExpandedPathbufParser,
+    profile::ProfileDefaults,
+    utils::watch_tx,
+    verbosity::VerbosityArgs,
+    ProviderArgs,
+};
+
+#[derive(Debug, Parser)]
+pub struct SendTransaction {
+    #[clap(flatten)]
+    provider: ProviderArgs,
+    #[clap(long, short, help = "Wait for the transaction to confirm")]
+    watch: bool,
+    #[clap(
+        long,
+        env = "STARKNET_POLL_INTERVAL",
+        help = "Transaction result poll interval in milliseconds. Falls back to the default of the \
+        active profile, or 5000 if not set"
+    )]
+    poll_interval: Option<u64>,
+    #[clap(
+        value_parser = ExpandedPathbufParser,
+        help = "Path to the transaction file signed with `starkli tx sign`"
+    )]
+    file: PathBuf,
+    #[clap(flatten)]
+    verbosity: VerbosityArgs,
+}
+
+impl SendTransaction {
+    pub async fn run(self) -> Result<()> {
+        self.verbosity.setup_logging();
+
+        let transaction_file = TransactionFile::load(&self.file)?;
+        if transaction_file.signature.is_none() {
+            anyhow::bail!("transaction not signed. Sign with `starkli tx sign`");
+        }
+
+        let provider = self.provider.into_provider()?;
+
+        let chain_id = provider.chain_id().await?;
+        if chain_id != transaction_file.chain_id {
+            anyhow::bail!(
+                "chain ID mismatch. Transaction: {:#x}; network: {:#x}",
+                transaction_file.chain_id,
+                chain_id
+            );
+        }
+
+        let transaction_hash = transaction_file.send(&provider).await?;
+
+        eprintln!("Transaction sent:");
+
+        // Only the transaction hash goes to stdout so this can be easily scripted
+        if is_json_output() {
+            record_transaction(transaction_hash);
+        } else {
+            println!("{}", format!("{:#064x}", transaction_hash).bright_yellow());
+        }
+
+        if self.watch {
+            eprintln!(
+                "Waiting for transaction {} to confirm...",
+                format!("{:#064x}", transaction_hash).bright_yellow(),
+            );
+            watch_tx(
+                &provider,
+                transaction_hash,
+                ProfileDefaults::resolve_poll_interval(self.poll_interval)?,
+            )
+            .await?;
+        }
+
+        Ok(())
+    }
+}
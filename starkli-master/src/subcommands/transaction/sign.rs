@@ -0,0 +1,125 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::Parser;
+use colored::Colorize;
+use starknet::core::{types::FieldElement, utils::parse_cairo_short_string};
+
+use crate::{
+    fee::FeeToken,
+    offline::{TransactionFee, TransactionFile, UnsignedTransaction},
+    path::ExpandedPathbufParser,
+    signer::SignerArgs,
+    v3::TransactionSigner,
+};
+
+#[derive(Debug, Parser)]
+pub struct SignTransaction {
+    #[clap(flatten)]
+    signer: SignerArgs,
+    #[clap(
+        long,
+        help = "Replace the existing signature if the transaction is already signed"
+    )]
+    force: bool,
+    #[clap(
+        long,
+        help = "ID of the signer in a Braavos multisig account. The signature is prefixed with \
+        the ID and appended to the existing signature, if any, so that each signer can sign in turn"
+    )]
+    signer_id: Option<u64>,
+    #[clap(
+        long,
+        num_args = 1,
+        value_delimiter = ',',
+        help = "Attach a signature produced elsewhere, such as by a hardware signer, instead of \
+        signing with a Starknet signer. Elements are separated by commas"
+    )]
+    raw_signature: Option<Vec<FieldElement>>,
+    #[clap(
+        value_parser = ExpandedPathbufParser,
+        help = "Path to the transaction file built with --build-only"
+    )]
+    file: PathBuf,
+}
+
+impl SignTransaction {
+    pub async fn run(self) -> Result<()> {
+        let mut transaction_file = TransactionFile::load(&self.file)?;
+        if transaction_file.signature.is_some() && self.signer_id.is_none() && !self.force {
+            anyhow::bail!("transaction already signed. Use --force to sign again");
+        }
+
+        let transaction_hash = transaction_file.transaction_hash()?;
+
+        // No network access is needed from here on, so everything shown is from the file itself
+        let transaction = &transaction_file.transaction;
+        let (transaction_type, fee_token) = match (transaction, transaction.fee()) {
+            (UnsignedTransaction::Invoke { .. }, TransactionFee::Eth { .. }) => {
+                ("INVOKE_V1", FeeToken::Eth)
+            }
+            (UnsignedTransaction::Invoke { .. }, TransactionFee::Strk { .. }) => {
+                ("INVOKE_V3", FeeToken::Strk)
+            }
+            (UnsignedTransaction::Declare { .. }, TransactionFee::Eth { .. }) => {
+                ("DECLARE_V2", FeeToken::Eth)
+            }
+            (UnsignedTransaction::Declare { .. }, TransactionFee::Strk { .. }) => {
+                ("DECLARE_V3", FeeToken::Strk)
+            }
+        };
+        let chain_id = parse_cairo_short_string(&transaction_file.chain_id)
+            .unwrap_or_else(|_| format!("{:#x}", transaction_file.chain_id));
+
+        eprintln!("Transaction type: {}", transaction_type.bright_yellow());
+        eprintln!("Chain ID: {}", chain_id.bright_yellow());
+        eprintln!(
+            "Sender: {}",
+            format!("{:#064x}", transaction.sender_address()).bright_yellow()
+        );
+        eprintln!(
+            "Nonce: {}",
+            format!("{}", transaction.nonce()).bright_yellow()
+        );
+        eprintln!(
+            "Max fee: {}",
+            format!(
+                "{} {}",
+                transaction.fee().max_fee()?.to_big_decimal(18),
+                fee_token
+            )
+            .bright_yellow()
+        );
+        eprintln!(
+            "Transaction hash: {}",
+            format!("{:#064x}", transaction_hash).bright_yellow()
+        );
+
+        let signature = match self.raw_signature {
+            Some(raw_signature) => raw_signature,
+            None => {
+                let signer = self.signer.into_signer()?;
+                signer.sign_transaction_hash(transaction_hash).await?
+            }
+        };
+
+        transaction_file.signature = Some(match self.signer_id {
+            Some(signer_id) => {
+                let mut combined = if self.force {
+                    vec![]
+                } else {
+                    transaction_file.signature.take().unwrap_or_default()
+                };
+                combined.push(signer_id.into());
+                combined.extend(signature);
+                combined
+            }
+            None => signature,
+        });
+
+        // Only the signed transaction goes to stdout so it can be redirected to a file
+        transaction_file.print()?;
+
+        Ok(())
+    }
+}
@@ -1,9 +1,12 @@
 use anyhow::Result;
 use clap::Parser;
-use colored_json::{ColorMode, Output};
-use starknet::{core::types::FieldElement, providers::Provider};
+use serde_json::Value;
+use starknet::{
+    core::types::{Event, FieldElement},
+    providers::Provider,
+};
 
-use crate::{verbosity::VerbosityArgs, ProviderArgs};
+use crate::{abi::EventDecoder, utils::print_colored_json, verbosity::VerbosityArgs, ProviderArgs};
 
 #[derive(Debug, Parser)]
 pub struct TransactionReceipt {
@@ -11,6 +14,8 @@ pub struct TransactionReceipt {
     provider: ProviderArgs,
     #[clap(help = "Transaction hash")]
     hash: String,
+    #[clap(long, help = "Decode events using the ABI of the emitting contracts")]
+    decode: bool,
     #[clap(flatten)]
     verbosity: VerbosityArgs,
 }
@@ -24,10 +29,27 @@ impl TransactionReceipt {
 
         let receipt = provider.get_transaction_receipt(transaction_hash).await?;
 
-        let receipt_json = serde_json::to_value(receipt)?;
-        let receipt_json =
-            colored_json::to_colored_json(&receipt_json, ColorMode::Auto(Output::StdOut))?;
-        println!("{receipt_json}");
+        let mut receipt_json = serde_json::to_value(receipt)?;
+
+        if self.decode {
+            let mut event_decoder = EventDecoder::new(&provider);
+            // Pending receipts come without a block number
+            let block_number = receipt_json.get("block_number").and_then(Value::as_u64);
+
+            if let Some(Value::Array(events)) = receipt_json.get_mut("events") {
+                for event_json in events.iter_mut() {
+                    let event: Event = serde_json::from_value(event_json.clone())?;
+                    if let Some(decoded) = event_decoder
+                        .decode(event.from_address, block_number, &event.keys, &event.data)
+                        .await
+                    {
+                        event_json["decoded"] = serde_json::to_value(decoded)?;
+                    }
+                }
+            }
+        }
+
+        print_colored_json(&receipt_json)?;
 
         Ok(())
     }
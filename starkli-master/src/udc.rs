@@ -0,0 +1,55 @@
+use clap::Parser;
+use starknet::{accounts::Call, core::types::FieldElement, macros::selector};
+
+/// The default UDC address: 0x041a78e741e5af2fec34b695679bc6891742439f7afb8484ecd7766661ad02bf.
+pub const DEFAULT_UDC_ADDRESS: FieldElement = FieldElement::from_mont([
+    15144800532519055890,
+    15685625669053253235,
+    9333317513348225193,
+    121672436446604875,
+]);
+
+#[derive(Debug, Clone, Parser)]
+pub struct UdcArgs {
+    #[clap(
+        long,
+        help = "Address of the Universal Deployer Contract. Falls back to the UDC configured for \
+        the network in the active profile, or the default UDC if not set"
+    )]
+    udc: Option<FieldElement>,
+}
+
+impl UdcArgs {
+    /// Resolves the UDC address, with the command line option taking precedence over the one
+    /// configured for the network, if any.
+    pub fn resolve(self, network_udc: Option<FieldElement>) -> FieldElement {
+        self.udc.or(network_udc).unwrap_or(DEFAULT_UDC_ADDRESS)
+    }
+}
+
+/// Builds the call to the UDC for deploying a contract, the same way `ContractFactory` does.
+pub fn udc_deploy_call(
+    udc_address: FieldElement,
+    class_hash: FieldElement,
+    salt: FieldElement,
+    unique: bool,
+    ctor_args: &[FieldElement],
+) -> Call {
+    let mut calldata = vec![
+        class_hash,
+        salt,
+        if unique {
+            FieldElement::ONE
+        } else {
+            FieldElement::ZERO
+        },
+        ctor_args.len().into(),
+    ];
+    calldata.extend_from_slice(ctor_args);
+
+    Call {
+        to: udc_address,
+        selector: selector!("deployContract"),
+        calldata,
+    }
+}
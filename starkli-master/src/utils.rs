@@ -24,6 +24,8 @@ use starknet::{
     providers::{Provider, ProviderError},
 };
 
+use crate::output::{is_json_output, record};
+
 pub async fn watch_tx<P>(
     provider: P,
     transaction_hash: FieldElement,
@@ -34,19 +36,23 @@ where
 {
     loop {
         match provider.get_transaction_receipt(transaction_hash).await {
-            Ok(receipt) => match receipt.execution_result() {
-                ExecutionResult::Succeeded => {
-                    eprintln!(
-                        "Transaction {} confirmed",
-                        format!("{:#064x}", transaction_hash).bright_yellow()
-                    );
-
-                    return Ok(());
-                }
-                ExecutionResult::Reverted { reason } => {
-                    return Err(anyhow::anyhow!("transaction reverted: {}", reason));
+            Ok(receipt) => {
+                record_receipt(&receipt);
+
+                match receipt.execution_result() {
+                    ExecutionResult::Succeeded => {
+                        eprintln!(
+                            "Transaction {} confirmed",
+                            format!("{:#064x}", transaction_hash).bright_yellow()
+                        );
+
+                        return Ok(());
+                    }
+                    ExecutionResult::Reverted { reason } => {
+                        return Err(anyhow::anyhow!("transaction reverted: {}", reason));
+                    }
                 }
-            },
+            }
             Err(ProviderError::StarknetError(StarknetError::TransactionHashNotFound)) => {
                 eprintln!("Transaction not confirmed yet...");
             }
@@ -57,6 +63,37 @@ where
     }
 }
 
+/// Checks whether the class has been declared, as of the pending block.
+pub async fn is_class_declared<P>(provider: P, class_hash: FieldElement) -> Result<bool>
+where
+    P: Provider,
+{
+    match provider
+        .get_class(BlockId::Tag(BlockTag::Pending), class_hash)
+        .await
+    {
+        Ok(_) => Ok(true),
+        Err(ProviderError::StarknetError(StarknetError::ClassHashNotFound)) => Ok(false),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Records the status and actual fee from a transaction receipt for JSON output.
+fn record_receipt<T>(receipt: &T)
+where
+    T: Serialize,
+{
+    if !is_json_output() {
+        return;
+    }
+
+    if let Ok(receipt) = serde_json::to_value(receipt) {
+        record("status", receipt.get("execution_status"));
+        record("finality_status", receipt.get("finality_status"));
+        record("actual_fee", receipt.get("actual_fee"));
+    }
+}
+
 pub fn parse_block_id(id: &str) -> Result<BlockId> {
     let regex_block_number = Regex::new("^[0-9]{1,}$").unwrap();
 
@@ -113,13 +150,36 @@ where
     Ok(FieldElement::from_byte_slice_be(&biguint.to_bytes_be())?)
 }
 
+pub fn felt_to_u128(felt: FieldElement) -> Result<u128> {
+    let bytes = felt.to_bytes_be();
+    if bytes[0..16].iter().any(|byte| *byte != 0) {
+        anyhow::bail!("value out of range for u128: {:#x}", felt);
+    }
+
+    let mut low = [0u8; 16];
+    low.copy_from_slice(&bytes[16..32]);
+    Ok(u128::from_be_bytes(low))
+}
+
+pub fn u128_to_felt(value: u128) -> FieldElement {
+    // Safe to unwrap as u128 values always fit in a field element
+    FieldElement::from_byte_slice_be(&value.to_be_bytes()).unwrap()
+}
+
 /// Prints colored JSON for any serializable value. This is better then directly calling
 /// `colored_json::to_colored_json` as that method only takes `serde_json::Value`. Unfortunately,
 /// converting certain values to `serde_json::Value` would result in data loss.
+///
+/// In JSON mode, the value is recorded as the `result` field of the output document instead.
 pub fn print_colored_json<T>(value: &T) -> Result<()>
 where
     T: Serialize,
 {
+    if is_json_output() {
+        record("result", value);
+        return Ok(());
+    }
+
     let mut writer = Vec::with_capacity(128);
 
     if ColorMode::Auto(Output::StdOut).use_color() {
@@ -0,0 +1,628 @@
+//! V3 transactions, which pay fees in STRK.
+//!
+//! The `starknet-accounts` version in use only builds legacy transactions, so V3 transactions are
+//! hashed, signed, and broadcast here directly instead.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use starknet::{
+    accounts::{Call, ExecutionEncoding},
+    core::{
+        types::{
+            BlockId, BlockTag, BroadcastedDeclareTransaction, BroadcastedDeclareTransactionV3,
+            BroadcastedDeployAccountTransaction, BroadcastedDeployAccountTransactionV3,
+            BroadcastedInvokeTransaction, BroadcastedInvokeTransactionV3, BroadcastedTransaction,
+            DataAvailabilityMode, FeeEstimate, FieldElement, FlattenedSierraClass, ResourceBounds,
+            ResourceBoundsMapping, SimulatedTransaction, SimulationFlag,
+            SimulationFlagForEstimateFee,
+        },
+        utils::get_contract_address,
+    },
+    macros::{felt, short_string},
+    providers::Provider,
+    signers::Signer,
+};
+use starknet_crypto::poseidon_hash_many;
+
+use crate::{
+    account::ResolvedAccount,
+    error::provider_error_mapper,
+    fee::{print_fee_estimate, record_fee_estimate, FeeToken, StrkFeeSetting},
+    signer::AnySigner,
+    utils::u128_to_felt,
+};
+
+pub const PREFIX_INVOKE: FieldElement = short_string!("invoke");
+pub const PREFIX_DECLARE: FieldElement = short_string!("declare");
+const PREFIX_DEPLOY_ACCOUNT: FieldElement = short_string!("deploy_account");
+
+const RESOURCE_L1_GAS: FieldElement = short_string!("L1_GAS");
+const RESOURCE_L2_GAS: FieldElement = short_string!("L2_GAS");
+
+const TRANSACTION_VERSION: FieldElement = felt!("0x3");
+
+/// Version used for transactions only meant for queries, which is `2^128 + 3`.
+const QUERY_TRANSACTION_VERSION: FieldElement = felt!("0x100000000000000000000000000000003");
+
+const TWO_POW_128: FieldElement = felt!("0x100000000000000000000000000000000");
+const TWO_POW_192: FieldElement = felt!("0x1000000000000000000000000000000000000000000000000");
+
+/// Resource bounds and tip of a V3 transaction.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct V3Fee {
+    pub l1_gas: u64,
+    pub l1_gas_price: u128,
+    pub tip: u64,
+}
+
+/// Content of a V3 transaction, excluding nonce, fee, and signature.
+#[derive(Debug, Clone)]
+pub enum V3Transaction {
+    Invoke {
+        sender_address: FieldElement,
+        calldata: Vec<FieldElement>,
+    },
+    Declare {
+        sender_address: FieldElement,
+        contract_class: Arc<FlattenedSierraClass>,
+        compiled_class_hash: FieldElement,
+    },
+    DeployAccount {
+        class_hash: FieldElement,
+        salt: FieldElement,
+        constructor_calldata: Vec<FieldElement>,
+    },
+}
+
+/// Turns V3 transaction hashes into transaction signatures.
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+pub trait TransactionSigner {
+    async fn sign_transaction_hash(
+        &self,
+        transaction_hash: FieldElement,
+    ) -> Result<Vec<FieldElement>>;
+}
+
+/// A V3 transaction with everything but the fee determined, ready to be estimated, simulated, or
+/// sent.
+pub struct V3Sender<'a, P, S> {
+    provider: &'a P,
+    signer: &'a S,
+    chain_id: FieldElement,
+    nonce: FieldElement,
+    transaction: V3Transaction,
+}
+
+impl V3Fee {
+    /// Maximum amount of fee this transaction can be charged, excluding tips.
+    pub fn max_fee(&self) -> FieldElement {
+        FieldElement::from(self.l1_gas) * u128_to_felt(self.l1_gas_price)
+    }
+
+    fn resource_bounds(&self) -> ResourceBoundsMapping {
+        ResourceBoundsMapping {
+            l1_gas: ResourceBounds {
+                max_amount: self.l1_gas,
+                max_price_per_unit: self.l1_gas_price,
+            },
+            l2_gas: ResourceBounds {
+                max_amount: 0,
+                max_price_per_unit: 0,
+            },
+        }
+    }
+
+    fn hash(&self) -> FieldElement {
+        poseidon_hash_many(&[
+            FieldElement::from(self.tip),
+            encode_resource_bound(RESOURCE_L1_GAS, self.l1_gas, self.l1_gas_price),
+            encode_resource_bound(RESOURCE_L2_GAS, 0, 0),
+        ])
+    }
+}
+
+impl V3Transaction {
+    /// Builds an invoke transaction executing `calls` from `sender_address`.
+    pub fn invoke(
+        sender_address: FieldElement,
+        calls: &[Call],
+        encoding: ExecutionEncoding,
+    ) -> Self {
+        Self::Invoke {
+            sender_address,
+            calldata: encode_calls(calls, encoding),
+        }
+    }
+
+    /// Address of the account sending the transaction, or the address of the account to be
+    /// deployed for account deployments.
+    pub fn sender_address(&self) -> FieldElement {
+        match self {
+            Self::Invoke { sender_address, .. } | Self::Declare { sender_address, .. } => {
+                *sender_address
+            }
+            Self::DeployAccount {
+                class_hash,
+                salt,
+                constructor_calldata,
+            } => get_contract_address(*salt, *class_hash, constructor_calldata, FieldElement::ZERO),
+        }
+    }
+
+    pub fn transaction_hash(
+        &self,
+        chain_id: FieldElement,
+        nonce: FieldElement,
+        fee: &V3Fee,
+        is_query: bool,
+    ) -> FieldElement {
+        let version = if is_query {
+            QUERY_TRANSACTION_VERSION
+        } else {
+            TRANSACTION_VERSION
+        };
+
+        // Paymaster data and account deployment data are always empty, and both data availability
+        // modes are always L1, which encodes to zero.
+        let empty_hash = poseidon_hash_many(&[]);
+        let data_availability_modes = FieldElement::ZERO;
+
+        match self {
+            Self::Invoke {
+                sender_address,
+                calldata,
+            } => poseidon_hash_many(&[
+                PREFIX_INVOKE,
+                version,
+                *sender_address,
+                fee.hash(),
+                empty_hash,
+                chain_id,
+                nonce,
+                data_availability_modes,
+                empty_hash,
+                poseidon_hash_many(calldata),
+            ]),
+            Self::Declare {
+                sender_address,
+                contract_class,
+                compiled_class_hash,
+            } => poseidon_hash_many(&[
+                PREFIX_DECLARE,
+                version,
+                *sender_address,
+                fee.hash(),
+                empty_hash,
+                chain_id,
+                nonce,
+                data_availability_modes,
+                empty_hash,
+                contract_class.class_hash(),
+                *compiled_class_hash,
+            ]),
+            Self::DeployAccount {
+                class_hash,
+                salt,
+                constructor_calldata,
+            } => poseidon_hash_many(&[
+                PREFIX_DEPLOY_ACCOUNT,
+                version,
+                self.sender_address(),
+                fee.hash(),
+                empty_hash,
+                chain_id,
+                nonce,
+                data_availability_modes,
+                poseidon_hash_many(constructor_calldata),
+                *class_hash,
+                *salt,
+            ]),
+        }
+    }
+
+    pub fn to_broadcasted(
+        &self,
+        nonce: FieldElement,
+        fee: &V3Fee,
+        signature: Vec<FieldElement>,
+        is_query: bool,
+    ) -> BroadcastedTransaction {
+        match self {
+            Self::Invoke {
+                sender_address,
+                calldata,
+            } => BroadcastedTransaction::Invoke(BroadcastedInvokeTransaction::V3(
+                BroadcastedInvokeTransactionV3 {
+                    sender_address: *sender_address,
+                    calldata: calldata.clone(),
+                    signature,
+                    nonce,
+                    resource_bounds: fee.resource_bounds(),
+                    tip: fee.tip,
+                    paymaster_data: vec![],
+                    account_deployment_data: vec![],
+                    nonce_data_availability_mode: DataAvailabilityMode::L1,
+                    fee_data_availability_mode: DataAvailabilityMode::L1,
+                    is_query,
+                },
+            )),
+            Self::Declare {
+                sender_address,
+                contract_class,
+                compiled_class_hash,
+            } => BroadcastedTransaction::Declare(BroadcastedDeclareTransaction::V3(
+                BroadcastedDeclareTransactionV3 {
+                    sender_address: *sender_address,
+                    compiled_class_hash: *compiled_class_hash,
+                    signature,
+                    nonce,
+                    contract_class: contract_class.clone(),
+                    resource_bounds: fee.resource_bounds(),
+                    tip: fee.tip,
+                    paymaster_data: vec![],
+                    account_deployment_data: vec![],
+                    nonce_data_availability_mode: DataAvailabilityMode::L1,
+                    fee_data_availability_mode: DataAvailabilityMode::L1,
+                    is_query,
+                },
+            )),
+            Self::DeployAccount {
+                class_hash,
+                salt,
+                constructor_calldata,
+            } => BroadcastedTransaction::DeployAccount(BroadcastedDeployAccountTransaction::V3(
+                BroadcastedDeployAccountTransactionV3 {
+                    signature,
+                    nonce,
+                    contract_address_salt: *salt,
+                    constructor_calldata: constructor_calldata.clone(),
+                    class_hash: *class_hash,
+                    resource_bounds: fee.resource_bounds(),
+                    tip: fee.tip,
+                    paymaster_data: vec![],
+                    nonce_data_availability_mode: DataAvailabilityMode::L1,
+                    fee_data_availability_mode: DataAvailabilityMode::L1,
+                    is_query,
+                },
+            )),
+        }
+    }
+}
+
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl TransactionSigner for AnySigner {
+    async fn sign_transaction_hash(
+        &self,
+        transaction_hash: FieldElement,
+    ) -> Result<Vec<FieldElement>> {
+        let signature = self.sign_hash(&transaction_hash).await?;
+        Ok(vec![signature.r, signature.s])
+    }
+}
+
+/// Signs with the owner, followed by the guardian if any, which is the signature format expected by
+/// Argent accounts with guardians.
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+impl TransactionSigner for ResolvedAccount {
+    async fn sign_transaction_hash(
+        &self,
+        transaction_hash: FieldElement,
+    ) -> Result<Vec<FieldElement>> {
+        let mut signature = self.signer.sign_transaction_hash(transaction_hash).await?;
+        if let Some(guardian) = &self.guardian {
+            signature.extend(guardian.sign_transaction_hash(transaction_hash).await?);
+        }
+        Ok(signature)
+    }
+}
+
+impl<'a, P, S> V3Sender<'a, P, S>
+where
+    P: Provider + Send + Sync,
+    S: TransactionSigner + Send + Sync,
+{
+    /// Creates a sender for `transaction`, fetching the nonce from the pending block if not
+    /// provided. Account deployments always use the zero nonce.
+    pub async fn new(
+        provider: &'a P,
+        signer: &'a S,
+        chain_id: FieldElement,
+        nonce: Option<FieldElement>,
+        transaction: V3Transaction,
+    ) -> Result<Self> {
+        let nonce = match (nonce, &transaction) {
+            (Some(nonce), _) => nonce,
+            (None, V3Transaction::DeployAccount { .. }) => FieldElement::ZERO,
+            (None, _) => provider
+                .get_nonce(
+                    BlockId::Tag(BlockTag::Pending),
+                    transaction.sender_address(),
+                )
+                .await
+                .map_err(provider_error_mapper)?,
+        };
+
+        Ok(Self {
+            provider,
+            signer,
+            chain_id,
+            nonce,
+            transaction,
+        })
+    }
+
+    pub fn transaction(&self) -> &V3Transaction {
+        &self.transaction
+    }
+
+    pub fn nonce(&self) -> FieldElement {
+        self.nonce
+    }
+
+    pub fn transaction_hash(&self, fee: &V3Fee) -> FieldElement {
+        self.transaction
+            .transaction_hash(self.chain_id, self.nonce, fee, false)
+    }
+
+    /// Determines the fee to use, estimating it when not fully specified. Returns `None` after
+    /// printing the estimate if only estimation is requested.
+    pub async fn resolve_fee(
+        &self,
+        fee_setting: &StrkFeeSetting,
+        default_multiplier: f64,
+    ) -> Result<Option<V3Fee>> {
+        let estimate = if fee_setting.needs_estimate() {
+            Some(self.estimate_fee().await?)
+        } else {
+            None
+        };
+        if let Some(estimate) = &estimate {
+            record_fee_estimate(estimate, FeeToken::Strk);
+        }
+
+        if fee_setting.estimate_only {
+            if let Some(estimate) = &estimate {
+                print_fee_estimate(estimate, FeeToken::Strk);
+            }
+            return Ok(None);
+        }
+
+        Ok(Some(
+            fee_setting.resolve(estimate.as_ref(), default_multiplier)?,
+        ))
+    }
+
+    pub async fn estimate_fee(&self) -> Result<FeeEstimate> {
+        // Resource bounds are ignored by nodes during estimation
+        let transaction = self.build(&V3Fee::default(), true).await?;
+
+        self.provider
+            .estimate_fee(
+                [transaction],
+                [] as [SimulationFlagForEstimateFee; 0],
+                BlockId::Tag(BlockTag::Pending),
+            )
+            .await
+            .map_err(provider_error_mapper)?
+            .pop()
+            .ok_or_else(|| anyhow::anyhow!("no fee estimate returned"))
+    }
+
+    pub async fn simulate(&self, fee: &V3Fee) -> Result<SimulatedTransaction> {
+        let transaction = self.build(fee, true).await?;
+
+        self.provider
+            .simulate_transactions(
+                BlockId::Tag(BlockTag::Pending),
+                [transaction],
+                [] as [SimulationFlag; 0],
+            )
+            .await
+            .map_err(provider_error_mapper)?
+            .pop()
+            .ok_or_else(|| anyhow::anyhow!("no simulation result returned"))
+    }
+
+    /// Signs and sends the transaction, returning the transaction hash.
+    pub async fn send(&self, fee: &V3Fee) -> Result<FieldElement> {
+        let transaction_hash = match self.build(fee, false).await? {
+            BroadcastedTransaction::Invoke(tx) => {
+                self.provider
+                    .add_invoke_transaction(tx)
+                    .await
+                    .map_err(provider_error_mapper)?
+                    .transaction_hash
+            }
+            BroadcastedTransaction::Declare(tx) => {
+                self.provider
+                    .add_declare_transaction(tx)
+                    .await
+                    .map_err(provider_error_mapper)?
+                    .transaction_hash
+            }
+            BroadcastedTransaction::DeployAccount(tx) => {
+                self.provider
+                    .add_deploy_account_transaction(tx)
+                    .await
+                    .map_err(provider_error_mapper)?
+                    .transaction_hash
+            }
+        };
+
+        Ok(transaction_hash)
+    }
+
+    async fn build(&self, fee: &V3Fee, is_query: bool) -> Result<BroadcastedTransaction> {
+        let transaction_hash =
+            self.transaction
+                .transaction_hash(self.chain_id, self.nonce, fee, is_query);
+        let signature = self.signer.sign_transaction_hash(transaction_hash).await?;
+
+        Ok(self
+            .transaction
+            .to_broadcasted(self.nonce, fee, signature, is_query))
+    }
+}
+
+/// Encodes calls into `__execute__` calldata the same way `starknet-accounts` does.
+pub fn encode_calls(calls: &[Call], encoding: ExecutionEncoding) -> Vec<FieldElement> {
+    let mut calldata = vec![FieldElement::from(calls.len())];
+
+    match encoding {
+        ExecutionEncoding::Legacy => {
+            let mut concated_calldata = vec![];
+            for call in calls.iter() {
+                calldata.push(call.to);
+                calldata.push(call.selector);
+                calldata.push(FieldElement::from(concated_calldata.len()));
+                calldata.push(FieldElement::from(call.calldata.len()));
+                concated_calldata.extend_from_slice(&call.calldata);
+            }
+
+            calldata.push(FieldElement::from(concated_calldata.len()));
+            calldata.extend_from_slice(&concated_calldata);
+        }
+        ExecutionEncoding::New => {
+            for call in calls.iter() {
+                calldata.push(call.to);
+                calldata.push(call.selector);
+                calldata.push(FieldElement::from(call.calldata.len()));
+                calldata.extend_from_slice(&call.calldata);
+            }
+        }
+    }
+
+    calldata
+}
+
+/// Packs a resource bound as `resource_name (64 bits) | max_amount (64 bits) | max_price (128
+/// bits)`.
+fn encode_resource_bound(resource: FieldElement, max_amount: u64, max_price: u128) -> FieldElement {
+    resource * TWO_POW_192 + FieldElement::from(max_amount) * TWO_POW_128 + u128_to_felt(max_price)
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet::{core::types::EntryPointsByType, macros::selector};
+
+    use super::*;
+
+    const CHAIN_ID: FieldElement = short_string!("SN_SEPOLIA");
+    const SENDER_ADDRESS: FieldElement =
+        felt!("0x04a9f57d1e0c2dd7e5f5c4ab8ea1f4f1a8b1bd5c20e5a0a0b3e33d6e7e1b1b2c");
+    const STRK_ADDRESS: FieldElement =
+        felt!("0x04718f5a0fc34cc1af16a1cdee98ffb20c31f5cd61d6ab07201858f4287c938d");
+    const OZ_CLASS_HASH: FieldElement =
+        felt!("0x061dac032f228abef9c6626f995015233097ae253a7f72d68552db02f2971b8f");
+
+    // Expected hashes are computed independently from the transaction hash specification.
+
+    fn test_fee() -> V3Fee {
+        V3Fee {
+            l1_gas: 0x1234,
+            l1_gas_price: 0x5678,
+            tip: 0,
+        }
+    }
+
+    fn test_invoke() -> V3Transaction {
+        V3Transaction::invoke(
+            SENDER_ADDRESS,
+            &[Call {
+                to: STRK_ADDRESS,
+                selector: selector!("transfer"),
+                calldata: vec![felt!("0x1234"), felt!("100"), FieldElement::ZERO],
+            }],
+            ExecutionEncoding::New,
+        )
+    }
+
+    #[test]
+    fn test_encode_resource_bound() {
+        assert_eq!(
+            encode_resource_bound(RESOURCE_L1_GAS, 0x1234, 0x5678),
+            felt!("0x4c315f474153000000000000123400000000000000000000000000005678")
+        );
+        assert_eq!(
+            encode_resource_bound(RESOURCE_L2_GAS, u64::MAX, u128::MAX),
+            felt!("0x4c325f474153ffffffffffffffffffffffffffffffffffffffffffffffff")
+        );
+    }
+
+    #[test]
+    fn test_resource_bounds() {
+        let bounds = test_fee().resource_bounds();
+
+        assert_eq!(bounds.l1_gas.max_amount, 0x1234);
+        assert_eq!(bounds.l1_gas.max_price_per_unit, 0x5678);
+        assert_eq!(bounds.l2_gas.max_amount, 0);
+        assert_eq!(bounds.l2_gas.max_price_per_unit, 0);
+        assert_eq!(test_fee().max_fee(), felt!("0x6260060"));
+    }
+
+    #[test]
+    fn test_invoke_transaction_hash() {
+        let transaction = test_invoke();
+
+        assert_eq!(
+            transaction.transaction_hash(CHAIN_ID, felt!("5"), &test_fee(), false),
+            felt!("0x12645c8c3055f15981300d4ee408fd9e92be98c166143d49976521bd13bb8a6")
+        );
+        assert_eq!(
+            transaction.transaction_hash(CHAIN_ID, felt!("5"), &test_fee(), true),
+            felt!("0x31c7e19f0863d185dceb7c93118bd49b417ad40698a9729aa5d6bebf08bc070")
+        );
+    }
+
+    #[test]
+    fn test_declare_transaction_hash() {
+        let contract_class = FlattenedSierraClass {
+            sierra_program: vec![felt!("1"), felt!("2"), felt!("3")],
+            contract_class_version: String::from("0.1.0"),
+            entry_points_by_type: EntryPointsByType {
+                constructor: vec![],
+                external: vec![],
+                l1_handler: vec![],
+            },
+            abi: String::new(),
+        };
+        assert_eq!(
+            contract_class.class_hash(),
+            felt!("0x5c597e34a7af312903e2667b7f4e3d2bb8b61632d03819585b22556994cd226")
+        );
+
+        let transaction = V3Transaction::Declare {
+            sender_address: SENDER_ADDRESS,
+            contract_class: Arc::new(contract_class),
+            compiled_class_hash: felt!("0x5678"),
+        };
+
+        assert_eq!(
+            transaction.transaction_hash(CHAIN_ID, felt!("5"), &test_fee(), false),
+            felt!("0x46cbcd100521c3022a6c9aaca37bc13cb8ece1550fe35462a28fe44d515b566")
+        );
+    }
+
+    #[test]
+    fn test_deploy_account_transaction_hash() {
+        let transaction = V3Transaction::DeployAccount {
+            class_hash: OZ_CLASS_HASH,
+            salt: felt!("0x1111"),
+            constructor_calldata: vec![felt!("0x2222")],
+        };
+
+        assert_eq!(
+            transaction.sender_address(),
+            felt!("0x382183d06fdde8d6d0ec34da7b55134b7225e466f22044ab0aa616d3c998c56")
+        );
+        assert_eq!(
+            transaction.transaction_hash(CHAIN_ID, FieldElement::ZERO, &test_fee(), false),
+            felt!("0x57c5e3e0c8199ab1ef60ba72eadcd43e2006da5c322669366aaf4607a0200a4")
+        );
+    }
+}